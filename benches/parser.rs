@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use godotdoc::{parse_str, ParseOptions};
+
+/// A few thousand lines, covering every kind of declaration
+fn large_source() -> String {
+    let chunk = include_str!("../test.gd");
+    let extra = r##"
+## A multi-line declaration with nested brackets and strings
+const LOOKUP = {
+	"key: value": [1, 2, (3 + 4)],
+	'other = 5': {"nested": "# not a comment"},
+}
+
+@export_range(0, 100) var health: int = 100 # Trailing comment
+var speed := 2.5; var direction: Vector2 = Vector2.ZERO
+var text = """Multi
+line = string"""
+
+signal damaged(amount: int, source)
+
+func move(delta: float, target: Vector2 = Vector2(1, 1), options := {"a": 1}) -> void:
+	position += direction * speed * delta
+"##;
+
+    let mut source = String::new();
+    for i in 0..200 {
+        source += &chunk.replace("MyClass", &format!("MyClass{}", i));
+        source += extra;
+    }
+    source
+}
+
+fn parse(c: &mut Criterion) {
+    let source = large_source();
+    let options = ParseOptions::default();
+
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Bytes(source.len() as u64));
+    group.bench_function("large file", |b| {
+        b.iter(|| parse_str("large.gd", &source, &options).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
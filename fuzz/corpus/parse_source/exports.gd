@@ -0,0 +1,18 @@
+extends Node
+
+# Godot 3 enum
+export(int, "Up", "Down") var direction = 0
+# Godot 3 flags
+export(int, FLAGS, "Fire", "Water") var elements = 0
+# Godot 3 range
+export(float, 0, 1, 0.1) var volume = 0.5
+export(String, FILE, "*.txt") var notes
+
+## Godot 4 enum
+@export_enum("Warrior", "Magician:3") var class_type: int
+## Godot 4 flags
+@export_flags("Fire", "Water") var spells = 0
+## Godot 4 range
+@export_range(0, 100) var health: int = 100
+@export_range(-10, 10, 0.5, "or_greater") var offset: float
+@export_file("*.png") var icon: String
@@ -0,0 +1,16 @@
+#![no_main]
+
+use godotdoc::log::{self, Verbosity};
+use godotdoc::{parse_source, ParseOptions};
+use libfuzzer_sys::fuzz_target;
+
+// Any input has to be either documented or rejected with an error, never panic
+fuzz_target!(|data: &[u8]| {
+    // Invalid UTF-8 is replaced with a warning, which would flood the output
+    log::set_verbosity(Verbosity::Quiet);
+    let options = ParseOptions {
+        warn_unrecognized: true,
+        ..ParseOptions::default()
+    };
+    let _ = parse_source("fuzz.gd", data, &options);
+});
@@ -1,10 +1,502 @@
 use crate::parser::DocumentationData;
 
-use std::fs::File;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
 
-pub trait Backend {
-    fn generate_output(&self, data: DocumentationData, f: &mut File) -> std::io::Result<()>;
+/// Backends are shared between the threads, which document the files.
+/// They write into files or, with `--stdin`, to stdout.
+pub trait Backend: Send + Sync {
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        f: &mut dyn Write,
+    ) -> Result<(), BackendError>;
     fn get_extension(&self) -> String;
+
+    /// Writes a page titled `title` in the format of the backend, which links every page,
+    /// pages of the same group are listed together. Returns false, if the backend has no index.
+    fn generate_index(
+        &self,
+        _title: &str,
+        _pages: &[IndexPage],
+        _f: &mut dyn Write,
+    ) -> Result<bool, BackendError> {
+        Ok(false)
+    }
+
+    /// Writes a page titled `title`, which shows the scripts extending each other.
+    /// Returns false, if the backend has no such page.
+    fn generate_inheritance(
+        &self,
+        _title: &str,
+        _tree: &InheritanceTree,
+        _f: &mut dyn Write,
+    ) -> Result<bool, BackendError> {
+        Ok(false)
+    }
+}
+
+/// What went wrong, so library users can react to errors without matching on the message
+#[derive(Debug)]
+pub enum BackendError {
+    /// No backend has the name, with the names of the ones, which exist
+    UnsupportedBackend {
+        name: String,
+        available: Vec<String>,
+        /// The available name, which is closest to the name, if it looks like a typo
+        suggestion: Option<String>,
+    },
+    /// The output couldn't be written
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for BackendError {
+    fn from(error: std::io::Error) -> BackendError {
+        BackendError::Io(error)
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+impl Display for BackendError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            BackendError::UnsupportedBackend {
+                name,
+                available,
+                suggestion,
+            } => {
+                write!(
+                    f,
+                    "Unsupported backend '{}', expected {} or all",
+                    name,
+                    available.join(", ")
+                )?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, ", did you mean '{}'?", suggestion)?;
+                }
+                Ok(())
+            }
+            BackendError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// A generated page, as listed in the index
+#[derive(Clone, Debug, Default)]
+pub struct IndexPage {
+    /// Path of the page relative to the index, separated by '/'
+    pub path: String,
+    pub title: String,
+    /// `class_name` of the script, if it has one
+    pub class_name: Option<String>,
+    /// Class the script extends
+    pub base: Option<String>,
+    /// First line of the script's comments
+    pub summary: Option<String>,
+    /// `res://` path of the script
+    pub script: Option<String>,
+    /// Global name of the script, if the project loads it as autoload singleton
+    pub autoload: Option<String>,
+    /// The script couldn't be documented, so the page doesn't exist
+    pub failed: bool,
+    /// Heading, the page is listed under, see `group_pages`
+    pub group: Option<String>,
+}
+
+/// How the index clusters its pages
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GroupBy {
+    None,
+    /// Pages in the same directory
+    Directory,
+    /// Classes, whose names start with the same words, like `UIButton` and `UIPanel`
+    ClassNamePrefix,
+}
+
+impl GroupBy {
+    pub fn from_name(name: &str) -> Result<GroupBy, String> {
+        match name {
+            "none" => Ok(GroupBy::None),
+            "directory" => Ok(GroupBy::Directory),
+            "class_name_prefix" => Ok(GroupBy::ClassNamePrefix),
+            _ => Err(format!(
+                "Unknown grouping '{}', expected none, directory or class_name_prefix",
+                name
+            )),
+        }
+    }
+}
+
+/// Where the Markdown backend lists the symbols prefixed with an '_'
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PrivateSection {
+    /// Together with the public symbols
+    None,
+    /// Under a heading after the public symbols
+    Heading,
+    /// Under a heading, which is collapsed in a `<details>` element
+    Details,
+}
+
+impl PrivateSection {
+    pub fn from_name(name: &str) -> Result<PrivateSection, String> {
+        match name {
+            "none" => Ok(PrivateSection::None),
+            "heading" => Ok(PrivateSection::Heading),
+            "details" => Ok(PrivateSection::Details),
+            _ => Err(format!(
+                "Unknown private section '{}', expected none, heading or details",
+                name
+            )),
+        }
+    }
+}
+
+/// How the Markdown backend shows the members a script inherits from the documented scripts it extends
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InheritedMembers {
+    None,
+    /// A line per script with the names of its members and a link to its page
+    Linked,
+    /// The members with their documentation, in a collapsed `<details>` element per script
+    Listed,
+}
+
+impl InheritedMembers {
+    pub fn from_name(name: &str) -> Result<InheritedMembers, String> {
+        match name {
+            "none" => Ok(InheritedMembers::None),
+            "linked" => Ok(InheritedMembers::Linked),
+            "listed" => Ok(InheritedMembers::Listed),
+            _ => Err(format!(
+                "Unknown inherited members '{}', expected none, linked or listed",
+                name
+            )),
+        }
+    }
+}
+
+/// How the Markdown backend formats the `@since`, `@deprecated`, `@note` and `@warning` tags
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CalloutStyle {
+    /// Bold labels like `**Deprecated:**`
+    Plain,
+    /// The alerts of GitHub flavored Markdown, like `> [!WARNING]`
+    Gfm,
+    /// Bold labels after an emoji
+    Emoji,
+}
+
+impl CalloutStyle {
+    pub fn from_name(name: &str) -> Result<CalloutStyle, String> {
+        match name {
+            "plain" => Ok(CalloutStyle::Plain),
+            "gfm" => Ok(CalloutStyle::Gfm),
+            "emoji" => Ok(CalloutStyle::Emoji),
+            _ => Err(format!(
+                "Unknown callout style '{}', expected plain, gfm or emoji",
+                name
+            )),
+        }
+    }
+}
+
+/// Assigns the group of every page and sorts the pages by it, keeping their order inside of a group.
+/// Pages without a group come first.
+pub fn group_pages(pages: &mut [IndexPage], group_by: GroupBy) {
+    let groups = match group_by {
+        GroupBy::None => vec![None; pages.len()],
+        GroupBy::Directory => pages
+            .iter()
+            .map(|page| page.path.rsplit_once('/').map(|(dir, _)| dir.to_string()))
+            .collect(),
+        GroupBy::ClassNamePrefix => {
+            // Pages without a class_name are titled with the file name of their script
+            let words = pages
+                .iter()
+                .map(|page| match page.title.ends_with(".gd") {
+                    true => Vec::new(),
+                    false => split_words(&page.title),
+                })
+                .collect::<Vec<_>>();
+            let prefixes = words
+                .iter()
+                .enumerate()
+                .map(|(i, own)| {
+                    // The longest prefix, which is shorter than the name and shared with another class
+                    let shared = words
+                        .iter()
+                        .enumerate()
+                        .filter(|(j, _)| *j != i)
+                        .map(|(_, other)| own.iter().zip(other).take_while(|(a, b)| a == b).count())
+                        .max()
+                        .unwrap_or(0)
+                        .min(own.len().saturating_sub(1));
+                    match shared {
+                        0 => None,
+                        n => Some(own[..n].concat().trim_end_matches('_').to_string()),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // A group of a single class would only add a heading
+            prefixes
+                .iter()
+                .map(|prefix| {
+                    prefix
+                        .clone()
+                        .filter(|_| prefixes.iter().filter(|x| *x == prefix).count() > 1)
+                })
+                .collect()
+        }
+    };
+
+    for (page, group) in pages.iter_mut().zip(groups) {
+        page.group = group;
+    }
+    // The sort is stable, so the pages stay in the order of their scripts
+    pages.sort_by(|a, b| a.group.cmp(&b.group));
+}
+
+/// A documented script with the scripts, which extend it
+#[derive(Debug)]
+pub struct InheritanceNode<'a> {
+    pub page: &'a IndexPage,
+    pub children: Vec<InheritanceNode<'a>>,
+}
+
+/// The documented scripts as a forest, see `inheritance_tree`
+#[derive(Debug, Default)]
+pub struct InheritanceTree<'a> {
+    /// Engine classes with the scripts extending them, ordered by name,
+    /// scripts without `extends` are listed under an empty name
+    pub roots: Vec<(String, Vec<InheritanceNode<'a>>)>,
+    /// Bases, which refer to no documented script, with the scripts extending them
+    pub unknown: Vec<(String, Vec<InheritanceNode<'a>>)>,
+    /// Scripts extending each other in a cycle, each followed by its base,
+    /// with the other scripts extending them
+    pub cycles: Vec<Vec<InheritanceNode<'a>>>,
+}
+
+enum Parent {
+    Script(usize),
+    Engine(String),
+    Unknown(String),
+}
+
+/// Arranges the documented pages by the classes they extend. Bases are resolved by the
+/// `class_name` of a script or by its path, either as `res://` path or relative to the script.
+/// Other identifiers are taken for engine classes.
+pub fn inheritance_tree(pages: &[IndexPage]) -> InheritanceTree<'_> {
+    let pages = pages.iter().filter(|x| !x.failed).collect::<Vec<_>>();
+    let find = |base: &str, script: Option<&str>| {
+        let position = match base.strip_prefix('"').or_else(|| base.strip_prefix('\'')) {
+            Some(path) => {
+                let path = script_path(path.trim_end_matches(['"', '\'']), script);
+                pages
+                    .iter()
+                    .position(|x| x.script.as_deref() == Some(&path))
+            }
+            None => pages
+                .iter()
+                .position(|x| x.class_name.as_deref() == Some(base)),
+        };
+        match position {
+            Some(i) => Parent::Script(i),
+            None if base.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                Parent::Engine(base.to_string())
+            }
+            None => Parent::Unknown(base.to_string()),
+        }
+    };
+    let parents = pages
+        .iter()
+        .map(|page| match &page.base {
+            Some(base) => find(base, page.script.as_deref()),
+            None => Parent::Engine(String::new()),
+        })
+        .collect::<Vec<_>>();
+
+    let mut children = vec![Vec::new(); pages.len()];
+    for (i, parent) in parents.iter().enumerate() {
+        if let Parent::Script(j) = parent {
+            children[*j].push(i);
+        }
+    }
+    // Scripts in a cycle are never reached from a root, so the recursion ends
+    fn node<'a>(
+        i: usize,
+        pages: &[&'a IndexPage],
+        children: &[Vec<usize>],
+        reached: &mut [bool],
+    ) -> InheritanceNode<'a> {
+        reached[i] = true;
+        let mut result = InheritanceNode {
+            page: pages[i],
+            children: Vec::new(),
+        };
+        for &j in &children[i] {
+            if !reached[j] {
+                result.children.push(node(j, pages, children, reached));
+            }
+        }
+        result
+    }
+
+    let mut tree = InheritanceTree::default();
+    let mut reached = vec![false; pages.len()];
+    for (i, parent) in parents.iter().enumerate() {
+        let (list, name) = match parent {
+            Parent::Script(_) => continue,
+            Parent::Engine(name) => (&mut tree.roots, name),
+            Parent::Unknown(name) => (&mut tree.unknown, name),
+        };
+        let node = node(i, &pages, &children, &mut reached);
+        match list.iter_mut().find(|(x, _)| x == name) {
+            Some((_, nodes)) => nodes.push(node),
+            None => list.push((name.clone(), vec![node])),
+        }
+    }
+    tree.roots.sort_by(|a, b| a.0.cmp(&b.0));
+    tree.unknown.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Following the bases of a script, which wasn't reached, ends in a cycle
+    for start in 0..pages.len() {
+        if reached[start] {
+            continue;
+        }
+        let mut path = vec![start];
+        let mut i = start;
+        while let Parent::Script(j) = parents[i] {
+            if let Some(k) = path.iter().position(|&x| x == j) {
+                path.drain(..k);
+                break;
+            }
+            path.push(j);
+            i = j;
+        }
+        // Mark the whole cycle first, so the other scripts in it aren't listed as its children
+        for &i in &path {
+            reached[i] = true;
+        }
+        let cycle = path
+            .iter()
+            .map(|&i| {
+                reached[i] = false;
+                node(i, &pages, &children, &mut reached)
+            })
+            .collect();
+        tree.cycles.push(cycle);
+    }
+    tree
+}
+
+/// The `res://` path of a script referred to by `path` from the script at `script`
+pub fn script_path(path: &str, script: Option<&str>) -> String {
+    let (dir, path) = match (path.strip_prefix("res://"), script) {
+        (Some(path), _) => ("", path),
+        (None, Some(script)) => {
+            let script = script.trim_start_matches("res://");
+            (script.rsplit_once('/').map_or("", |(dir, _)| dir), path)
+        }
+        (None, None) => ("", path),
+    };
+    let mut components = Vec::new();
+    for component in dir.split('/').chain(path.split('/')) {
+        match component {
+            "" | "." => (),
+            ".." => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+    format!("res://{}", components.join("/"))
+}
+
+/// Splits a name into its words at underscores and changes of case, keeping the underscores,
+/// e.g. `UIMenu_Item2D` into `UI`, `Menu_`, `Item2D`
+pub fn split_words(name: &str) -> Vec<&str> {
+    let chars = name.char_indices().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (k, &(i, c)) in chars.iter().enumerate().skip(1) {
+        let previous = chars[k - 1].1;
+        let next = chars.get(k + 1).map(|x| x.1);
+        let boundary = previous == '_' && c != '_'
+            || c.is_uppercase() && previous.is_lowercase()
+            || c.is_uppercase() && previous.is_uppercase() && next.is_some_and(char::is_lowercase);
+        if boundary {
+            words.push(&name[start..i]);
+            start = i;
+        }
+    }
+    if start < name.len() {
+        words.push(&name[start..]);
+    }
+    words
+}
+
+/// Describes the options of an export for the inspector, e.g. `("Options", "A, B")` or `("Range", "0–100")`.
+/// Godot 4 exports take them from the suffix of their `@export_*` annotation, Godot 3 exports from the export hint.
+/// Suffixes without a richer description, like the ones of newer Godot versions, are shown as hint.
+/// Strings keep their quotes, as an option may contain a ',' itself.
+pub fn describe_export_options(
+    export_hint: Option<&str>,
+    options: &[String],
+) -> Option<(&'static str, String)> {
+    match export_hint {
+        Some("enum") if !options.is_empty() => Some(("Options", options.join(", "))),
+        Some("flags") if !options.is_empty() => Some(("Flags", options.join(", "))),
+        Some("range") => describe_range(options).map(|x| ("Range", x)),
+        Some(hint) if options.is_empty() => Some(("Hint", hint.to_string())),
+        Some(hint) => Some(("Hint", format!("{}, {}", hint, options.join(", ")))),
+        None if options.is_empty() => None,
+        None if options[0] == "FLAGS" && options.len() > 1 => {
+            Some(("Flags", options[1..].join(", ")))
+        }
+        None if options.iter().all(|x| is_string(x)) => Some(("Options", options.join(", "))),
+        None => describe_range(options)
+            .map(|x| ("Range", x))
+            .or_else(|| Some(("Hint", options.join(", ")))),
+    }
+}
+
+fn is_string(value: &str) -> bool {
+    value.len() >= 2
+        && (value.starts_with('"') && value.ends_with('"')
+            || value.starts_with('\'') && value.ends_with('\''))
+}
+
+fn unquoted(value: &str) -> &str {
+    if is_string(value) {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+/// `min–max`, followed by the step and any other hints like `or_greater`, which are keywords and
+/// are shown without their quotes
+fn describe_range(options: &[String]) -> Option<String> {
+    let numbers = options
+        .iter()
+        .take_while(|x| x.parse::<f64>().is_ok())
+        .count();
+    if numbers < 2 {
+        return None;
+    }
+
+    let mut range = format!("{}–{}", options[0], options[1]);
+    if numbers > 2 {
+        range += &format!(", step {}", options[2]);
+    }
+    for hint in &options[numbers.min(3)..] {
+        range += &format!(", {}", unquoted(hint));
+    }
+    Some(range)
 }
 
+pub mod doxygenbackend;
 pub mod markdownbackend;
@@ -0,0 +1,399 @@
+use std::io::Write;
+
+use crate::backend::{describe_export_options, Backend, BackendError, IndexPage};
+use crate::parser::{
+    ClassArgStruct, EnumValue, ExportArgStruct, FunctionArgStruct, FunctionArgument, Override,
+    Symbol, SymbolArgs, VariableArgStruct,
+};
+use crate::parser::{DocumentationData, DocumentationEntry, EntryType};
+
+/// Writes C++ stubs, which Doxygen can index like the rest of a C++ project
+#[derive(Default)]
+pub struct DoxygenBackend {}
+
+impl DoxygenBackend {
+    pub fn new() -> DoxygenBackend {
+        DoxygenBackend {}
+    }
+
+    fn write_class(
+        &self,
+        indent: &str,
+        name: &str,
+        base: Option<&str>,
+        text: &[String],
+        entries: Vec<DocumentationEntry>,
+        f: &mut dyn Write,
+    ) -> std::io::Result<()> {
+        let mut lines = vec![format!("\\class {}", name)];
+        lines.extend(text.iter().cloned());
+        write!(f, "{}", format_comment(indent, &lines))?;
+
+        match base {
+            Some(base) => writeln!(f, "{}class {} : public {} {{", indent, name, base)?,
+            None => writeln!(f, "{}class {} {{", indent, name)?,
+        }
+        writeln!(f, "{}public:", indent)?;
+
+        let member_indent = format!("{}    ", indent);
+        for entry in entries {
+            writeln!(f, "{}/** \\name {} */", member_indent, entry.entry_type)?;
+            writeln!(f, "{}/** @{{ */", member_indent)?;
+            for symbol in entry.symbols {
+                self.write_symbol(&member_indent, &entry.entry_type, symbol, f)?;
+            }
+            writeln!(f, "{}/** @}} */", member_indent)?;
+            writeln!(f)?;
+        }
+
+        writeln!(f, "{}}};", indent)
+    }
+
+    fn write_symbol(
+        &self,
+        indent: &str,
+        entry_type: &EntryType,
+        symbol: Symbol,
+        f: &mut dyn Write,
+    ) -> std::io::Result<()> {
+        let name = symbol.name;
+        let mut text = symbol.text;
+        if let Some(since) = symbol.since {
+            text.push(format!("\\since {}", since));
+        }
+        if let Some(deprecated) = symbol.deprecated {
+            text.push(
+                format!("\\deprecated {}", deprecated)
+                    .trim_end()
+                    .to_string(),
+            );
+        }
+
+        match symbol.arg {
+            Some(SymbolArgs::ClassArgs(ClassArgStruct {
+                base,
+                entries,
+                link,
+            })) => {
+                // Classes with a page of their own are declared in their own stub
+                if link.is_some() {
+                    return Ok(());
+                }
+                let base = base.map(|base| type_name(&base));
+                return self.write_class(indent, &name, base.as_deref(), &text, entries, f);
+            }
+            Some(SymbolArgs::FunctionArgs(FunctionArgStruct {
+                arguments,
+                return_type,
+                is_virtual,
+                overrides,
+                ..
+            })) => {
+                let return_type = return_type.map(|x| type_name(&x));
+                let documented_return = return_type.clone().filter(|x| x != "void");
+                let return_type = return_type.unwrap_or_else(|| "Variant".to_string());
+                let signature = format!("{}({})", name, format_parameters(&arguments));
+                let mut lines = vec![format!("\\fn {} {}", return_type, signature)];
+                lines.append(&mut text);
+                // Doxygen finds the methods of the classes it knows itself
+                if overrides == Some(Override::Engine) {
+                    lines.push("\\remark Engine virtual".to_string());
+                }
+                lines.extend(format_param_commands(&lines, &arguments));
+                if let Some(return_type) = documented_return {
+                    if !has_command(&lines, "return", None) {
+                        lines.push(format!("\\return {}", return_type));
+                    }
+                }
+
+                write!(f, "{}", format_comment(indent, &lines))?;
+                let specifier = if is_virtual { "virtual " } else { "" };
+                writeln!(f, "{}{}{} {};", indent, specifier, return_type, signature)?;
+            }
+            Some(SymbolArgs::EnumArgs(values)) => {
+                write!(f, "{}", format_comment(indent, &text))?;
+                write!(f, "{}", format_enum(indent, &name, values))?;
+            }
+            Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                value_type,
+                assignment,
+                setter,
+                getter,
+            })) => {
+                text.extend(format_value_commands(assignment, setter, getter));
+                write!(f, "{}", format_comment(indent, &text))?;
+                let value_type = value_type.map_or("Variant".to_string(), |x| type_name(&x));
+                match entry_type {
+                    EntryType::CONST => {
+                        writeln!(f, "{}static const {} {};", indent, value_type, name)?
+                    }
+                    _ => writeln!(f, "{}{} {};", indent, value_type, name)?,
+                }
+            }
+            Some(SymbolArgs::ExportArgs(ExportArgStruct {
+                value_type,
+                assignment,
+                options,
+                setter,
+                getter,
+                export_hint,
+            })) => {
+                if let Some((label, value)) =
+                    describe_export_options(export_hint.as_deref(), &options)
+                {
+                    text.push(format!("{}: {}", label, value));
+                }
+                text.extend(format_value_commands(assignment, setter, getter));
+                write!(f, "{}", format_comment(indent, &text))?;
+                let value_type = value_type.map_or("Variant".to_string(), |x| type_name(&x));
+                writeln!(f, "{}{} {};", indent, value_type, name)?;
+            }
+            Some(SymbolArgs::SignalArgs(arguments)) => {
+                let signature = format!("{}({})", name, format_parameters(&arguments));
+                let mut lines = vec![format!("\\fn void {}", signature)];
+                lines.append(&mut text);
+                lines.extend(format_param_commands(&lines, &arguments));
+
+                write!(f, "{}", format_comment(indent, &lines))?;
+                writeln!(f, "{}void {};", indent, signature)?;
+            }
+            // Without arguments, there is no declaration to document
+            None => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a GDScript type into a C++ type, untyped values are Variants
+fn type_name(value_type: &str) -> String {
+    let value_type = value_type.trim();
+    if value_type.is_empty() {
+        return "Variant".to_string();
+    }
+
+    // Scripts are referenced by their path, they are named after their file
+    if value_type.starts_with('"') || value_type.starts_with('\'') {
+        return identifier(
+            value_type
+                .trim_matches(|c| c == '"' || c == '\'')
+                .rsplit('/')
+                .next()
+                .unwrap()
+                .trim_end_matches(".gd"),
+        );
+    }
+
+    value_type.replace('[', "<").replace(']', ">")
+}
+
+/// Replaces every character, which can't appear in a C++ identifier
+fn identifier(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    match name.chars().next() {
+        Some(c) if c.is_numeric() => format!("_{}", name),
+        _ => name,
+    }
+}
+
+/// Scripts without a class_name are named after their file, like in their stubs
+fn page_class_name(page: &IndexPage) -> String {
+    match &page.class_name {
+        Some(name) => name.clone(),
+        None => identifier(page.title.trim_end_matches(".gd")),
+    }
+}
+
+fn format_parameters(arguments: &[FunctionArgument]) -> String {
+    arguments
+        .iter()
+        .map(|x| {
+            format!(
+                "{} {}",
+                x.value_type
+                    .as_deref()
+                    .map_or("Variant".to_string(), type_name),
+                x.name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether the comment already contains a command, either written as `\name` or `@name`
+fn has_command(lines: &[String], command: &str, argument: Option<&str>) -> bool {
+    lines.iter().any(|line| {
+        let mut words = line.split_whitespace();
+        let name = words
+            .next()
+            .and_then(|word| word.strip_prefix('\\').or_else(|| word.strip_prefix('@')));
+        name == Some(command) && (argument.is_none() || words.next() == argument)
+    })
+}
+
+/// `\param` commands for every argument, which isn't documented in the comment
+fn format_param_commands(lines: &[String], arguments: &[FunctionArgument]) -> Vec<String> {
+    arguments
+        .iter()
+        .filter(|x| !has_command(lines, "param", Some(&x.name)))
+        .map(|x| match &x.default_value {
+            Some(default_value) => format!("\\param {} Defaults to `{}`", x.name, default_value),
+            None => format!("\\param {}", x.name),
+        })
+        .collect()
+}
+
+fn format_value_commands(
+    assignment: Option<String>,
+    setter: Option<String>,
+    getter: Option<String>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(assignment) = assignment {
+        // Values spanning multiple lines are cut after the first line
+        match assignment.split_once('\n') {
+            Some((first_line, _)) => lines.push(format!("Defaults to `{}...`", first_line)),
+            None => lines.push(format!("Defaults to `{}`", assignment)),
+        }
+    }
+    if let Some(getter) = getter {
+        lines.push(format!("Getter: {}()", getter));
+    }
+    if let Some(setter) = setter {
+        lines.push(format!("Setter: {}()", setter));
+    }
+    lines
+}
+
+fn format_comment(indent: &str, lines: &[String]) -> String {
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let mut comment = format!("{}/**\n", indent);
+    for line in lines {
+        match line.as_str() {
+            "" => comment += &format!("{} *\n", indent),
+            line => comment += &format!("{} * {}\n", indent, line.replace("*/", "*\\/")),
+        }
+    }
+    comment + &format!("{} */\n", indent)
+}
+
+fn format_enum(indent: &str, name: &str, values: Vec<EnumValue>) -> String {
+    let mut result = match name {
+        "" => format!("{}enum {{\n", indent),
+        _ => format!("{}enum {} {{\n", indent, name),
+    };
+    for value in values {
+        let initializer = match value.expression {
+            Some(expression) => expression,
+            None => value.value.to_string(),
+        };
+        result += &format!("{}    {} = {},", indent, value.name, initializer);
+        if !value.text.is_empty() {
+            result += &format!(" /**< {} */", value.text.join(" ").replace("*/", "*\\/"));
+        }
+        result += "\n";
+    }
+    result + &format!("{}}};\n", indent)
+}
+
+impl Backend for DoxygenBackend {
+    fn get_extension(&self) -> String {
+        "h".to_string()
+    }
+
+    /// The main page of Doxygen, which refers to the class of every script
+    fn generate_index(
+        &self,
+        title: &str,
+        pages: &[IndexPage],
+        f: &mut dyn Write,
+    ) -> Result<bool, BackendError> {
+        writeln!(f, "/**")?;
+        writeln!(f, " * \\mainpage {}", title.replace("*/", "*\\/"))?;
+        writeln!(f, " *")?;
+        let mut autoloads = pages
+            .iter()
+            .filter_map(|page| Some((page.autoload.as_ref()?, page)))
+            .collect::<Vec<_>>();
+        if !autoloads.is_empty() {
+            autoloads.sort_by_key(|(name, _)| *name);
+            writeln!(f, " * \\section autoloads Autoloads")?;
+            writeln!(f, " *")?;
+            for (name, page) in autoloads {
+                writeln!(
+                    f,
+                    " * - `{}`, \\ref {}",
+                    name.replace("*/", "*\\/"),
+                    page_class_name(page)
+                )?;
+            }
+            writeln!(f, " *")?;
+        }
+        let mut sections = 0;
+        for (i, page) in pages.iter().enumerate() {
+            if page.group.is_some() && (i == 0 || pages[i - 1].group != page.group) {
+                sections += 1;
+                writeln!(
+                    f,
+                    " * \\section group_{} {}",
+                    sections,
+                    page.group.as_deref().unwrap().replace("*/", "*\\/")
+                )?;
+                writeln!(f, " *")?;
+            }
+            if page.failed {
+                writeln!(
+                    f,
+                    " * - {} (failed to document)",
+                    page.title.replace("*/", "*\\/")
+                )?;
+                continue;
+            }
+            let name = page_class_name(page);
+            match &page.base {
+                Some(base) => writeln!(f, " * - \\ref {}, extends {}", name, type_name(base))?,
+                None => writeln!(f, " * - \\ref {}", name)?,
+            }
+            if let Some(summary) = &page.summary {
+                writeln!(f, " *   {}", summary.replace("*/", "*\\/"))?;
+            }
+        }
+        writeln!(f, " */")?;
+        Ok(true)
+    }
+
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        f: &mut dyn Write,
+    ) -> Result<(), BackendError> {
+        writeln!(f, "/**")?;
+        writeln!(f, " * \\file")?;
+        writeln!(
+            f,
+            " * Generated from {}",
+            data.source_file.replace("*/", "*\\/")
+        )?;
+        writeln!(f, " */")?;
+        writeln!(f)?;
+
+        // Scripts without a class_name are named after their file
+        let name = match data.class_name {
+            Some(name) => name,
+            None => identifier(data.source_file.trim_end_matches(".gd")),
+        };
+        let base = data.base.map(|base| type_name(&base));
+        let mut text = data.text;
+        if let Some(autoload) = data.autoload {
+            text.push(format!("Autoload singleton, accessible as `{}`", autoload));
+        }
+        self.write_class("", &name, base.as_deref(), &text, data.entries, f)?;
+        Ok(())
+    }
+}
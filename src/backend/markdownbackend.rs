@@ -1,201 +1,366 @@
-use std::fs::File;
 use std::io::Write;
 
-use crate::backend::Backend;
+use crate::backend::{
+    describe_export_options, Backend, BackendError, CalloutStyle, IndexPage, InheritanceNode,
+    InheritanceTree, InheritedMembers, PrivateSection,
+};
+use crate::parser::{
+    ClassArgStruct, EnumValue, ExportArgStruct, FunctionArgStruct, FunctionArgument, Override,
+    SymbolArgs, VariableArgStruct,
+};
 use crate::parser::{DocumentationData, DocumentationEntry};
-use crate::parser::{ExportArgStruct, FunctionArgStruct, SymbolArgs, VariableArgStruct};
 
 use std::fmt::Display;
 
-pub struct MarkdownBackend {}
+pub struct MarkdownBackend {
+    show_dependencies: bool,
+    heading_offset: u32,
+    signature_max_width: usize,
+    source_url_template: Option<String>,
+    private_section: PrivateSection,
+    callout_style: CalloutStyle,
+    inherited_members: InheritedMembers,
+}
 
 impl MarkdownBackend {
-    pub fn new() -> MarkdownBackend {
-        MarkdownBackend {}
+    pub fn new(
+        show_dependencies: bool,
+        heading_offset: u32,
+        signature_max_width: usize,
+        source_url_template: Option<String>,
+        private_section: PrivateSection,
+        callout_style: CalloutStyle,
+        inherited_members: InheritedMembers,
+    ) -> MarkdownBackend {
+        MarkdownBackend {
+            show_dependencies,
+            heading_offset,
+            signature_max_width,
+            source_url_template,
+            private_section,
+            callout_style,
+            inherited_members,
+        }
     }
-}
 
-fn sanitize_markdown(s: String) -> String {
-    s.replace("\\", "\\\\")
-        .replace("_", "\\_")
-        .replace("#", "\\#")
-        .replace("*", "\\*")
-        .replace("`", "\\`")
-        .replace("(", "\\(")
-        .replace(")", "\\)")
-        .replace("[", "\\[")
-        .replace("]", "\\]")
-}
+    /// Formats the tags, which call attention to a symbol: the version from `@since`, `@deprecated`
+    /// and the `@note` and `@warning` tags, which are taken out of the comments. Returns the source
+    /// link with the callouts, which follow the signature, and the ones, which follow the comments.
+    fn callouts(
+        &self,
+        prefix: &str,
+        source_link: String,
+        since: Option<&str>,
+        deprecated: Option<&str>,
+        text: &mut Vec<String>,
+    ) -> (String, String) {
+        let mut callouts = Vec::new();
+        if let Some(since) = since {
+            callouts.push((Callout::Since, since.to_string()));
+        }
+        if let Some(deprecated) = deprecated {
+            callouts.push((Callout::Deprecated, deprecated.to_string()));
+        }
+        callouts.append(&mut take_notes(text));
 
-fn sanitize_markdown_quoted(s: String) -> String {
-    s.replace("*", "\\*").replace("`", "\\`")
-}
+        match self.callout_style {
+            // The version stays a badge next to the name
+            CalloutStyle::Plain => {
+                let lines = callouts
+                    .iter()
+                    .filter(|(callout, _)| *callout != Callout::Since)
+                    .map(|(callout, message)| {
+                        format!("  \n{}{}", prefix, callout.describe(message))
+                    })
+                    .collect::<String>();
+                (since_badge(since) + &source_link + &lines, String::new())
+            }
+            CalloutStyle::Emoji => {
+                let lines = callouts
+                    .iter()
+                    .map(|(callout, message)| {
+                        format!(
+                            "  \n{}{} {}",
+                            prefix,
+                            callout.emoji(),
+                            callout.describe(message)
+                        )
+                    })
+                    .collect::<String>();
+                (source_link + &lines, String::new())
+            }
+            // Alerts are blockquotes, which would swallow the lines after the signature
+            CalloutStyle::Gfm => {
+                let alerts = callouts
+                    .iter()
+                    .map(|(callout, message)| {
+                        format!(
+                            "{prefix}> [!{}]\n{prefix}> {}\n\n",
+                            callout.alert(),
+                            callout.describe(message),
+                            prefix = prefix
+                        )
+                    })
+                    .collect::<String>();
+                (source_link, alerts)
+            }
+        }
+    }
 
-fn sanitize_markdown_format(f: &impl Display) -> String {
-    sanitize_markdown(format!("{}", f))
-}
+    /// A link to the line in the source repository, if there is a template for it
+    fn source_link(&self, path: &str, line: u32) -> String {
+        match &self.source_url_template {
+            Some(template) => format!(
+                " [source]({})",
+                template
+                    .replace("{path}", path)
+                    .replace("{line}", &line.to_string())
+            ),
+            None => String::new(),
+        }
+    }
 
-fn format_comments(prefix: &String, text: Vec<String>) -> String {
-    if text.is_empty() {
-        return "  \n".to_string();
+    /// Formats a heading shifted by the heading offset, Markdown has no levels beyond 6
+    fn heading(&self, level: u32, text: impl Display) -> String {
+        match level.saturating_add(self.heading_offset) {
+            level @ 1..=6 => format!("{} {}", "#".repeat(level as usize), text),
+            _ => format!("**{}**", text),
+        }
     }
 
-    format!(
-        "  \n{prefix}    ```\n{prefix}    {}\n{prefix}    ```\n\n",
-        text.join(format!("\n{}    ", prefix).as_str()),
-        prefix = prefix,
-    )
-}
+    /// Writes the entries of the page with their type as heading of the given level
+    fn write_entries(
+        &self,
+        source_path: &str,
+        entries: Vec<DocumentationEntry>,
+        level: u32,
+        f: &mut dyn Write,
+    ) -> std::io::Result<()> {
+        for entry in entries {
+            writeln!(
+                f,
+                "{}  ",
+                self.heading(level, format!("{}:", entry.entry_type))
+            )?;
 
-fn join<T: Display>(v: Vec<T>, s: &str) -> String {
-    v.iter()
-        .map(|x| sanitize_markdown_format(x))
-        .collect::<Vec<_>>()
-        .join(s)
-}
+            for mut entry in entry.symbols {
+                let sanitized_name = match &entry.arg {
+                    Some(SymbolArgs::ClassArgs(ClassArgStruct {
+                        link: Some(link), ..
+                    })) => format!("[{}]({})", sanitize_markdown(entry.name.clone()), link),
+                    _ => sanitize_markdown(entry.name.clone()),
+                };
 
-fn write_symbols(
-    prefix: String,
-    entries: Vec<DocumentationEntry>,
-    f: &mut File,
-) -> std::io::Result<()> {
-    for entry in entries {
-        write!(f, "{}* **{}**:  \n", prefix, entry.entry_type)?;
-
-        for entry in entry.symbols {
-            let sanitized_name = sanitize_markdown(entry.name);
-
-            write!(f, "{}    * {}", prefix, sanitized_name)?;
-            if let Some(args) = entry.arg {
-                match args {
-                    SymbolArgs::FunctionArgs(FunctionArgStruct {
-                        arguments,
-                        super_arguments,
-                        return_type,
-                    }) => {
-                        write!(f, "({})", join(arguments, ", "))?;
-                        if let Some(return_type) = return_type {
-                            write!(f, " -> {}", sanitize_markdown(return_type))?;
-                        }
-                        if let Some(super_arguments) = super_arguments {
+                write!(f, "* {}", sanitized_name)?;
+                let (source_link, callouts) = self.callouts(
+                    "",
+                    self.source_link(source_path, entry.line),
+                    entry.since.as_deref(),
+                    entry.deprecated.as_deref(),
+                    &mut entry.text,
+                );
+                if let Some(args) = entry.arg {
+                    match args {
+                        SymbolArgs::FunctionArgs(FunctionArgStruct {
+                            arguments,
+                            super_arguments,
+                            return_type,
+                            is_virtual,
+                            overrides,
+                        }) => {
                             write!(
                                 f,
-                                "  \n{}**Calls**: super.{}({})",
-                                prefix,
-                                sanitized_name,
-                                join(super_arguments, ", ")
+                                "{}",
+                                format_signature(
+                                    "",
+                                    &entry.name,
+                                    arguments,
+                                    return_type,
+                                    self.signature_max_width
+                                )
                             )?;
-                        }
-                    }
-                    SymbolArgs::VariableArgs(VariableArgStruct {
-                        value_type,
-                        assignment,
-                        setter,
-                        getter,
-                    }) => {
-                        if let Some(value_type) = value_type {
-                            write!(f, ": {}", sanitize_markdown(value_type))?;
-                        }
-                        if let Some(assignment) = assignment {
-                            write!(f, " = `{}`", sanitize_markdown_quoted(assignment))?;
-                        }
-                        if let Some(getter) = getter {
-                            write!(f, "  \n{}**Getter**: {}", prefix, sanitize_markdown(getter))?;
-                        }
-                        if let Some(setter) = setter {
-                            write!(f, "  \n{}**Setter**: {}", prefix, sanitize_markdown(setter))?;
-                        }
-                    }
-                    SymbolArgs::ExportArgs(ExportArgStruct {
-                        value_type,
-                        assignment,
-                        options,
-                        setter,
-                        getter,
-                    }) => {
-                        if let Some(value_type) = value_type {
-                            if options.len() == 0 {
-                                write!(f, ": {}", sanitize_markdown(value_type))?;
-                            } else {
+                            if is_virtual {
+                                write!(f, " `virtual`")?;
+                            }
+                            write!(f, "{}", source_link)?;
+                            if let Some(super_arguments) = super_arguments {
                                 write!(
                                     f,
-                                    ": ({}, {})",
-                                    sanitize_markdown(value_type),
-                                    sanitize_markdown(options.join(", "))
+                                    "  \n**Calls**: super.{}({})",
+                                    sanitized_name,
+                                    join(super_arguments, ", ")
                                 )?;
                             }
+                            write!(f, "{}", format_override("", &entry.name, overrides))?;
+                            write!(f, "{}", format_params("", &mut entry.text))?;
                         }
-                        if let Some(assignment) = assignment {
-                            write!(f, " = `{}`", sanitize_markdown_quoted(assignment))?;
+                        SymbolArgs::SignalArgs(arguments) => {
+                            write!(
+                                f,
+                                "{}{}{}",
+                                format_signature(
+                                    "",
+                                    &entry.name,
+                                    arguments,
+                                    None,
+                                    self.signature_max_width
+                                ),
+                                source_link,
+                                format_params("", &mut entry.text)
+                            )?;
                         }
-                        if let Some(getter) = getter {
-                            write!(f, "  \n{}**Getter**: {}", prefix, sanitize_markdown(getter))?;
+                        SymbolArgs::VariableArgs(VariableArgStruct {
+                            value_type,
+                            assignment,
+                            setter,
+                            getter,
+                        }) => {
+                            if let Some(value_type) = value_type {
+                                write!(f, ": {}", sanitize_markdown(value_type))?;
+                            }
+                            if let Some(assignment) = assignment {
+                                write!(f, " = `{}`", format_value(assignment))?;
+                            }
+                            write!(f, "{}", source_link)?;
+                            if let Some(getter) = getter {
+                                write!(f, "  \n**Getter**: {}", sanitize_markdown(getter))?;
+                            }
+                            if let Some(setter) = setter {
+                                write!(f, "  \n**Setter**: {}", sanitize_markdown(setter))?;
+                            }
+                        }
+                        SymbolArgs::ExportArgs(ExportArgStruct {
+                            value_type,
+                            assignment,
+                            options,
+                            setter,
+                            getter,
+                            export_hint,
+                        }) => {
+                            if let Some(value_type) = value_type {
+                                write!(f, ": {}", sanitize_markdown(value_type))?;
+                            }
+                            if let Some(assignment) = assignment {
+                                write!(f, " = `{}`", format_value(assignment))?;
+                            }
+                            write!(f, "{}", source_link)?;
+                            if let Some((label, value)) =
+                                describe_export_options(export_hint.as_deref(), &options)
+                            {
+                                write!(f, "  \n**{}**: {}", label, sanitize_markdown(value))?;
+                            }
+                            if let Some(getter) = getter {
+                                write!(f, "  \n**Getter**: {}", sanitize_markdown(getter))?;
+                            }
+                            if let Some(setter) = setter {
+                                write!(f, "  \n**Setter**: {}", sanitize_markdown(setter))?;
+                            }
                         }
-                        if let Some(setter) = setter {
-                            write!(f, "  \n{}**Setter**: {}", prefix, sanitize_markdown(setter))?;
+                        SymbolArgs::EnumArgs(values) => {
+                            write!(f, "{}{}", source_link, format_enum_table("", values))?;
                         }
-                    }
-                    SymbolArgs::EnumArgs(values) => {
-                        write!(f, "  \n{}    **Values**:", prefix)?;
-                        for val in values {
+                        SymbolArgs::ClassArgs(ClassArgStruct { base, entries, .. }) => {
+                            write!(f, "{}", source_link)?;
+                            if let Some(base) = base {
+                                write!(f, "  \n**Extends**: {}", sanitize_markdown(base))?;
+                            }
                             write!(
                                 f,
-                                "  \n{}    * {} = {}",
-                                prefix,
-                                sanitize_markdown(val.name),
-                                val.value
+                                "  \n{}  \n{}",
+                                format_comments(&"".to_string(), entry.text),
+                                callouts
                             )?;
-                            write!(f, "{}", format_comments(&prefix, val.text))?;
+                            self.write_symbols("    ".to_string(), source_path, entries, f)?;
+                            continue;
                         }
                     }
-                    SymbolArgs::ClassArgs(entries) => {
-                        write!(f, "{}", format_comments(&prefix, entry.text))?;
-                        write_symbols(format!("{}{}", prefix, "        "), entries, f)?;
-                        continue;
-                    }
+                } else {
+                    write!(f, "{}", source_link)?;
                 }
+                write!(
+                    f,
+                    "  \n{}{}",
+                    format_comments(&"".to_string(), entry.text),
+                    callouts
+                )?;
             }
-            write!(f, "{}", format_comments(&prefix, entry.text))?;
+            writeln!(f, "  ")?;
         }
-    }
 
-    Ok(())
-}
-
-impl Backend for MarkdownBackend {
-    fn get_extension(&self) -> String {
-        "md".to_string()
+        Ok(())
     }
 
-    fn generate_output(&self, data: DocumentationData, f: &mut File) -> std::io::Result<()> {
-        write!(f, "## {}\n\n", sanitize_markdown(data.source_file))?;
+    fn write_symbols(
+        &self,
+        prefix: String,
+        source_path: &str,
+        entries: Vec<DocumentationEntry>,
+        f: &mut dyn Write,
+    ) -> std::io::Result<()> {
+        for entry in entries {
+            writeln!(f, "{}* **{}**:  ", prefix, entry.entry_type)?;
 
-        for entry in data.entries {
-            write!(f, "### {}:  \n", entry.entry_type)?;
+            for mut entry in entry.symbols {
+                let sanitized_name = sanitize_markdown(entry.name.clone());
 
-            for entry in entry.symbols {
-                let sanitized_name = sanitize_markdown(entry.name);
-
-                write!(f, "* {}", sanitized_name)?;
+                write!(f, "{}    * {}", prefix, sanitized_name)?;
+                let (source_link, callouts) = self.callouts(
+                    &prefix,
+                    self.source_link(source_path, entry.line),
+                    entry.since.as_deref(),
+                    entry.deprecated.as_deref(),
+                    &mut entry.text,
+                );
                 if let Some(args) = entry.arg {
                     match args {
                         SymbolArgs::FunctionArgs(FunctionArgStruct {
                             arguments,
                             super_arguments,
                             return_type,
+                            is_virtual,
+                            overrides,
                         }) => {
-                            write!(f, "({})", join(arguments, ", "))?;
-                            if let Some(return_type) = return_type {
-                                write!(f, " -> {}", sanitize_markdown(return_type))?;
+                            write!(
+                                f,
+                                "{}",
+                                format_signature(
+                                    &prefix,
+                                    &entry.name,
+                                    arguments,
+                                    return_type,
+                                    self.signature_max_width
+                                )
+                            )?;
+                            if is_virtual {
+                                write!(f, " `virtual`")?;
                             }
+                            write!(f, "{}", source_link)?;
                             if let Some(super_arguments) = super_arguments {
                                 write!(
                                     f,
-                                    "  \n**Calls**: super.{}({})",
+                                    "  \n{}**Calls**: super.{}({})",
+                                    prefix,
                                     sanitized_name,
                                     join(super_arguments, ", ")
                                 )?;
                             }
+                            write!(f, "{}", format_override(&prefix, &entry.name, overrides))?;
+                            write!(f, "{}", format_params(&prefix, &mut entry.text))?;
+                        }
+                        SymbolArgs::SignalArgs(arguments) => {
+                            write!(
+                                f,
+                                "{}{}{}",
+                                format_signature(
+                                    &prefix,
+                                    &entry.name,
+                                    arguments,
+                                    None,
+                                    self.signature_max_width
+                                ),
+                                source_link,
+                                format_params(&prefix, &mut entry.text)
+                            )?;
                         }
                         SymbolArgs::VariableArgs(VariableArgStruct {
                             value_type,
@@ -207,13 +372,24 @@ impl Backend for MarkdownBackend {
                                 write!(f, ": {}", sanitize_markdown(value_type))?;
                             }
                             if let Some(assignment) = assignment {
-                                write!(f, " = `{}`", sanitize_markdown_quoted(assignment))?;
+                                write!(f, " = `{}`", format_value(assignment))?;
                             }
+                            write!(f, "{}", source_link)?;
                             if let Some(getter) = getter {
-                                write!(f, "  \n**Getter**: {}", sanitize_markdown(getter))?;
+                                write!(
+                                    f,
+                                    "  \n{}**Getter**: {}",
+                                    prefix,
+                                    sanitize_markdown(getter)
+                                )?;
                             }
                             if let Some(setter) = setter {
-                                write!(f, "  \n**Setter**: {}", sanitize_markdown(setter))?;
+                                write!(
+                                    f,
+                                    "  \n{}**Setter**: {}",
+                                    prefix,
+                                    sanitize_markdown(setter)
+                                )?;
                             }
                         }
                         SymbolArgs::ExportArgs(ExportArgStruct {
@@ -222,61 +398,526 @@ impl Backend for MarkdownBackend {
                             options,
                             setter,
                             getter,
+                            export_hint,
                         }) => {
                             if let Some(value_type) = value_type {
-                                if options.len() == 0 {
-                                    write!(f, ": {}", sanitize_markdown(value_type))?;
-                                } else {
-                                    write!(
-                                        f,
-                                        ": ({}, {})",
-                                        sanitize_markdown(value_type),
-                                        sanitize_markdown(options.join(", "))
-                                    )?;
-                                }
+                                write!(f, ": {}", sanitize_markdown(value_type))?;
                             }
                             if let Some(assignment) = assignment {
-                                write!(f, " = `{}`", sanitize_markdown_quoted(assignment))?;
+                                write!(f, " = `{}`", format_value(assignment))?;
+                            }
+                            write!(f, "{}", source_link)?;
+                            if let Some((label, value)) =
+                                describe_export_options(export_hint.as_deref(), &options)
+                            {
+                                write!(
+                                    f,
+                                    "  \n{}**{}**: {}",
+                                    prefix,
+                                    label,
+                                    sanitize_markdown(value)
+                                )?;
                             }
                             if let Some(getter) = getter {
-                                write!(f, "  \n**Getter**: {}", sanitize_markdown(getter))?;
+                                write!(
+                                    f,
+                                    "  \n{}**Getter**: {}",
+                                    prefix,
+                                    sanitize_markdown(getter)
+                                )?;
                             }
                             if let Some(setter) = setter {
-                                write!(f, "  \n**Setter**: {}", sanitize_markdown(setter))?;
+                                write!(
+                                    f,
+                                    "  \n{}**Setter**: {}",
+                                    prefix,
+                                    sanitize_markdown(setter)
+                                )?;
                             }
                         }
                         SymbolArgs::EnumArgs(values) => {
-                            write!(f, "  \n    **Values**:")?;
-                            for val in values {
+                            write!(f, "{}{}", source_link, format_enum_table(&prefix, values))?;
+                        }
+                        SymbolArgs::ClassArgs(ClassArgStruct { base, entries, .. }) => {
+                            write!(f, "{}", source_link)?;
+                            if let Some(base) = base {
                                 write!(
                                     f,
-                                    "  \n    * {} = {}",
-                                    sanitize_markdown(val.name),
-                                    val.value
+                                    "  \n{}**Extends**: {}",
+                                    prefix,
+                                    sanitize_markdown(base)
                                 )?;
-                                if !val.text.is_empty() {
-                                    write!(
-                                        f,
-                                        "  \n    {}",
-                                        format_comments(&"".to_string(), val.text)
-                                    )?;
-                                }
                             }
-                        }
-                        SymbolArgs::ClassArgs(entries) => {
-                            write!(
+                            write!(f, "{}{}", format_comments(&prefix, entry.text), callouts)?;
+                            self.write_symbols(
+                                format!("{}{}", prefix, "        "),
+                                source_path,
+                                entries,
                                 f,
-                                "  \n{}  \n",
-                                format_comments(&"".to_string(), entry.text)
                             )?;
-                            write_symbols("    ".to_string(), entries, f)?;
                             continue;
                         }
                     }
+                } else {
+                    write!(f, "{}", source_link)?;
+                }
+                write!(f, "{}{}", format_comments(&prefix, entry.text), callouts)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Moves the private symbols of every entry into entries of their own, empty entries are dropped
+fn split_private(
+    entries: Vec<DocumentationEntry>,
+) -> (Vec<DocumentationEntry>, Vec<DocumentationEntry>) {
+    let mut public_entries = Vec::new();
+    let mut private_entries = Vec::new();
+    for entry in entries {
+        let (private, public): (Vec<_>, Vec<_>) =
+            entry.symbols.into_iter().partition(|x| x.is_private);
+        for (symbols, entries) in [
+            (public, &mut public_entries),
+            (private, &mut private_entries),
+        ] {
+            if !symbols.is_empty() {
+                entries.push(DocumentationEntry {
+                    entry_type: entry.entry_type.clone(),
+                    symbols,
+                });
+            }
+        }
+    }
+    (public_entries, private_entries)
+}
+
+/// Escapes every character, which Markdown would interpret
+pub fn sanitize_markdown(s: String) -> String {
+    s.replace("\\", "\\\\")
+        .replace("_", "\\_")
+        .replace("#", "\\#")
+        .replace("*", "\\*")
+        .replace("`", "\\`")
+        .replace("(", "\\(")
+        .replace(")", "\\)")
+        .replace("[", "\\[")
+        .replace("]", "\\]")
+}
+
+fn sanitize_markdown_quoted(s: String) -> String {
+    s.replace("*", "\\*").replace("`", "\\`")
+}
+
+/// Formats a value as inline code, values spanning multiple lines are cut after the first line
+fn format_value(value: String) -> String {
+    match value.split_once('\n') {
+        Some((first_line, _)) => sanitize_markdown_quoted(format!("{}...", first_line)),
+        None => sanitize_markdown_quoted(value),
+    }
+}
+
+/// Takes the `@param name description` tags out of the comments and formats them as a line
+/// per argument below the signature
+fn format_params(prefix: &str, text: &mut Vec<String>) -> String {
+    let mut params = String::new();
+    text.retain(|line| match line.trim().strip_prefix("@param") {
+        Some(param) if param.starts_with(char::is_whitespace) => {
+            let param = param.trim();
+            let (name, description) = param.split_once(char::is_whitespace).unwrap_or((param, ""));
+            params += &format!("  \n{}**Parameter** `{}`", prefix, name.replace('`', "'"));
+            if !description.trim().is_empty() {
+                params += &format!(": {}", sanitize_markdown(description.trim().to_string()));
+            }
+            false
+        }
+        _ => true,
+    });
+    params
+}
+
+/// A badge with the version from the `@since` tag, placed before the source link
+fn since_badge(since: Option<&str>) -> String {
+    match since {
+        Some(since) => format!(" `since {}`", since.replace('`', "'")),
+        None => String::new(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Callout {
+    Since,
+    Deprecated,
+    Note,
+    Warning,
+}
+
+impl Callout {
+    /// The label with the message, a deprecated symbol may have no message
+    fn describe(self, message: &str) -> String {
+        let label = match self {
+            Callout::Since => "Since",
+            Callout::Deprecated => "Deprecated",
+            Callout::Note => "Note",
+            Callout::Warning => "Warning",
+        };
+        match message {
+            "" => format!("**{}**", label),
+            message => format!("**{}:** {}", label, sanitize_markdown(message.to_string())),
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        match self {
+            Callout::Since => "🆕",
+            Callout::Deprecated => "⛔",
+            Callout::Note => "ℹ️",
+            Callout::Warning => "⚠️",
+        }
+    }
+
+    /// The type of the alert on GitHub
+    fn alert(self) -> &'static str {
+        match self {
+            Callout::Since | Callout::Note => "NOTE",
+            Callout::Deprecated => "CAUTION",
+            Callout::Warning => "WARNING",
+        }
+    }
+}
+
+/// Takes the `@note` and `@warning` tags out of the comments, in the order they were written
+fn take_notes(text: &mut Vec<String>) -> Vec<(Callout, String)> {
+    let mut notes = Vec::new();
+    text.retain(|line| {
+        let line = line.trim();
+        for (tag, callout) in [("@note", Callout::Note), ("@warning", Callout::Warning)] {
+            match line.strip_prefix(tag) {
+                Some(message) if message.is_empty() || message.starts_with(char::is_whitespace) => {
+                    notes.push((callout, message.trim().to_string()));
+                    return false;
+                }
+                _ => (),
+            }
+        }
+        true
+    });
+    notes
+}
+
+fn sanitize_markdown_format(f: &impl Display) -> String {
+    sanitize_markdown(format!("{}", f))
+}
+
+fn format_comments(prefix: &String, text: Vec<String>) -> String {
+    if text.is_empty() {
+        return "  \n".to_string();
+    }
+
+    format!(
+        "  \n{prefix}    ```\n{prefix}    {}\n{prefix}    ```\n\n",
+        text.join(format!("\n{}    ", prefix).as_str()),
+        prefix = prefix,
+    )
+}
+
+fn format_enum_table(prefix: &str, values: Vec<EnumValue>) -> String {
+    let mut table = format!(
+        "  \n\n{prefix}    | Name | Value | Description |\n{prefix}    | --- | --- | --- |\n",
+        prefix = prefix
+    );
+    for value in values {
+        table += &format!(
+            "{}    | {} | {} | {} |\n",
+            prefix,
+            sanitize_markdown(value.name),
+            match value.expression {
+                Some(expression) => sanitize_markdown(expression).replace("|", "\\|"),
+                None => value.value.to_string(),
+            },
+            sanitize_markdown(value.text.join(" ")).replace("|", "\\|")
+        );
+    }
+
+    table
+}
+
+/// Formats the arguments and return type of a function, signatures longer than max_width
+/// are written as a code block with one argument per line
+fn format_signature(
+    prefix: &str,
+    name: &str,
+    arguments: Vec<FunctionArgument>,
+    return_type: Option<String>,
+    max_width: usize,
+) -> String {
+    let return_type = return_type.map_or(String::new(), |x| format!(" -> {}", x));
+    let plain_arguments = arguments.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    let width = name.chars().count()
+        + plain_arguments.join(", ").chars().count()
+        + return_type.chars().count()
+        + 2;
+
+    if max_width == 0 || width <= max_width || arguments.is_empty() {
+        return format!(
+            "({}){}",
+            join(arguments, ", "),
+            sanitize_markdown(return_type)
+        );
+    }
+
+    format!(
+        "  \n\n{prefix}    ```\n{prefix}    func {}(\n{prefix}        {}\n{prefix}    ){}\n{prefix}    ```\n",
+        name,
+        plain_arguments.join(format!(",\n{}        ", prefix).as_str()),
+        return_type,
+        prefix = prefix,
+    )
+}
+
+fn join<T: Display>(v: Vec<T>, s: &str) -> String {
+    v.iter()
+        .map(|x| sanitize_markdown_format(x))
+        .collect::<Vec<_>>()
+        .join(s)
+}
+
+/// A line naming the method, which a function overrides, if it overrides one
+fn format_override(prefix: &str, name: &str, overrides: Option<Override>) -> String {
+    match overrides {
+        Some(Override::Script { title, link }) => {
+            let method = sanitize_markdown(format!("{}.{}", title, name));
+            match link {
+                Some(link) => format!("  \n{}Overrides [{}]({})", prefix, method, link),
+                None => format!("  \n{}Overrides {}", prefix, method),
+            }
+        }
+        Some(Override::Engine) => format!("  \n{}Engine virtual", prefix),
+        None => String::new(),
+    }
+}
+
+fn inheritance_link(page: &IndexPage) -> String {
+    format!("[{}]({})", sanitize_markdown(page.title.clone()), page.path)
+}
+
+/// Writes a nested list of the scripts, indented by `depth` levels
+fn write_inheritance_nodes(
+    nodes: &[InheritanceNode],
+    depth: usize,
+    f: &mut dyn Write,
+) -> std::io::Result<()> {
+    for node in nodes {
+        writeln!(f, "{}* {}", "  ".repeat(depth), inheritance_link(node.page))?;
+        write_inheritance_nodes(&node.children, depth + 1, f)?;
+    }
+    Ok(())
+}
+
+impl Backend for MarkdownBackend {
+    fn get_extension(&self) -> String {
+        "md".to_string()
+    }
+
+    fn generate_index(
+        &self,
+        title: &str,
+        pages: &[IndexPage],
+        f: &mut dyn Write,
+    ) -> Result<bool, BackendError> {
+        write!(
+            f,
+            "{}\n\n",
+            self.heading(1, sanitize_markdown(title.to_string()))
+        )?;
+        let mut autoloads = pages
+            .iter()
+            .filter_map(|page| Some((page.autoload.as_ref()?, page)))
+            .collect::<Vec<_>>();
+        if !autoloads.is_empty() {
+            autoloads.sort_by_key(|(name, _)| *name);
+            write!(f, "{}\n\n", self.heading(2, "Autoloads"))?;
+            for (name, page) in autoloads {
+                writeln!(
+                    f,
+                    "* [{}]({}), {}  ",
+                    sanitize_markdown(name.clone()),
+                    page.path,
+                    sanitize_markdown(page.title.clone())
+                )?;
+            }
+            // The pages without a group would look like autoloads
+            if pages.first().is_some_and(|x| x.group.is_none()) {
+                write!(f, "\n{}\n\n", self.heading(2, "Scripts"))?;
+            }
+        }
+        for (i, page) in pages.iter().enumerate() {
+            if page.group.is_some() && (i == 0 || pages[i - 1].group != page.group) {
+                write!(
+                    f,
+                    "\n{}\n\n",
+                    self.heading(2, sanitize_markdown(page.group.clone().unwrap()))
+                )?;
+            }
+            if page.failed {
+                writeln!(
+                    f,
+                    "* {} (failed to document)  ",
+                    sanitize_markdown(page.title.clone())
+                )?;
+                continue;
+            }
+            let mut line = format!(
+                "* [{}]({})",
+                sanitize_markdown(page.title.clone()),
+                page.path
+            );
+            if let Some(base) = &page.base {
+                line += &format!(", extends {}", sanitize_markdown(base.clone()));
+            }
+            writeln!(f, "{}  ", line)?;
+            if let Some(summary) = &page.summary {
+                writeln!(f, "  {}  ", sanitize_markdown(summary.clone()))?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn generate_inheritance(
+        &self,
+        title: &str,
+        tree: &InheritanceTree,
+        f: &mut dyn Write,
+    ) -> Result<bool, BackendError> {
+        write!(
+            f,
+            "{}\n\n",
+            self.heading(1, sanitize_markdown(format!("Inheritance of {}", title)))
+        )?;
+        for (base, nodes) in tree.roots.iter() {
+            match base.as_str() {
+                // Scripts without `extends` are roots themselves
+                "" => write_inheritance_nodes(nodes, 0, f)?,
+                base => {
+                    writeln!(f, "* {}", sanitize_markdown(base.to_string()))?;
+                    write_inheritance_nodes(nodes, 1, f)?;
+                }
+            }
+        }
+
+        if !tree.unknown.is_empty() {
+            write!(f, "\n{}\n\n", self.heading(2, "Unknown base".to_string()))?;
+            for (base, nodes) in tree.unknown.iter() {
+                writeln!(f, "* `{}`", base.replace('`', "'"))?;
+                write_inheritance_nodes(nodes, 1, f)?;
+            }
+        }
+
+        if !tree.cycles.is_empty() {
+            write!(f, "\n{}\n\n", self.heading(2, "Cycles".to_string()))?;
+            for cycle in tree.cycles.iter() {
+                let links = cycle
+                    .iter()
+                    .chain(cycle.first())
+                    .map(|x| inheritance_link(x.page))
+                    .collect::<Vec<_>>();
+                writeln!(f, "* {}", links.join(" extends "))?;
+                for node in cycle {
+                    write_inheritance_nodes(&node.children, 1, f)?;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        f: &mut dyn Write,
+    ) -> Result<(), BackendError> {
+        write!(
+            f,
+            "{}\n\n",
+            self.heading(2, sanitize_markdown(data.title().to_string()))
+        )?;
+        if let Some(parent) = data.parent {
+            write!(
+                f,
+                "Defined in [{}]({})  \n\n",
+                sanitize_markdown(parent.title),
+                parent.link
+            )?;
+        }
+        if let Some(autoload) = data.autoload {
+            write!(f, "> Autoload singleton, accessible as `{}`\n\n", autoload)?;
+        }
+        if !data.text.is_empty() {
+            let text = data
+                .text
+                .iter()
+                .map(|line| sanitize_markdown(line.clone()))
+                .collect::<Vec<_>>();
+            write!(f, "{}\n\n", text.join("\n"))?;
+        }
+
+        let (entries, private_entries) = match self.private_section {
+            PrivateSection::None => (data.entries, Vec::new()),
+            _ => split_private(data.entries),
+        };
+        self.write_entries(&data.source_path, entries, 3, f)?;
+        if !private_entries.is_empty() {
+            if self.private_section == PrivateSection::Details {
+                write!(f, "<details>\n<summary>Private API</summary>\n\n")?;
+            } else {
+                writeln!(f, "{}  ", self.heading(3, "Private API:"))?;
+            }
+            self.write_entries(&data.source_path, private_entries, 4, f)?;
+            if self.private_section == PrivateSection::Details {
+                write!(f, "</details>\n\n")?;
+            }
+        }
+
+        for class in data.inherited {
+            let title = match &class.link {
+                Some(link) => format!("[{}]({})", sanitize_markdown(class.title.clone()), link),
+                None => sanitize_markdown(class.title.clone()),
+            };
+            match self.inherited_members {
+                InheritedMembers::None => (),
+                InheritedMembers::Linked => {
+                    let names = class
+                        .entries
+                        .iter()
+                        .flat_map(|entry| entry.symbols.iter())
+                        .map(|symbol| sanitize_markdown(symbol.name.clone()))
+                        .collect::<Vec<_>>();
+                    write!(f, "Inherited from {}: {}  \n\n", title, names.join(", "))?;
+                }
+                InheritedMembers::Listed => {
+                    write!(
+                        f,
+                        "<details>\n<summary>Inherited from {}</summary>\n\n",
+                        class.title.replace('&', "&amp;").replace('<', "&lt;")
+                    )?;
+                    write!(f, "Defined in {}  \n\n", title)?;
+                    self.write_entries(&class.source_path, class.entries, 4, f)?;
+                    write!(f, "</details>\n\n")?;
+                }
+            }
+        }
+
+        if self.show_dependencies && !data.dependencies.is_empty() {
+            writeln!(f, "{}  ", self.heading(3, "Dependencies:"))?;
+            for dependency in data.dependencies {
+                match dependency.link {
+                    Some(link) => {
+                        writeln!(f, "* [{}]({})  ", sanitize_markdown(dependency.path), link)?
+                    }
+                    None => writeln!(f, "* {}  ", sanitize_markdown(dependency.path))?,
                 }
-                write!(f, "  \n{}", format_comments(&"".to_string(), entry.text))?;
             }
-            write!(f, "  \n")?;
+            writeln!(f, "  ")?;
         }
 
         Ok(())
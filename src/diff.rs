@@ -0,0 +1,143 @@
+//! Unified diffs of generated files for `--check --diff`
+
+/// Lines of context around every change
+const CONTEXT: usize = 3;
+
+/// Above this many cells the middle part is treated as replaced instead of searching for common lines
+const MAX_TABLE_SIZE: usize = 4_000_000;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Line<'a> {
+    Equal(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Compares two texts line by line, the result is empty if they are equal
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    let old_lines = old.lines().collect::<Vec<_>>();
+    let new_lines = new.lines().collect::<Vec<_>>();
+    let lines = diff_lines(&old_lines, &new_lines);
+    if lines.iter().all(|x| matches!(x, Line::Equal(_))) {
+        return String::new();
+    }
+
+    let mut result = format!("--- {}\n+++ {}\n", old_name, new_name);
+    let changed = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| !matches!(x, Line::Equal(_)))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    // Changes, which are close enough to share their context, are written as one hunk
+    let mut i = 0;
+    while i < changed.len() {
+        let start = changed[i].saturating_sub(CONTEXT);
+        let mut end = changed[i];
+        while i < changed.len() && changed[i] <= end + 2 * CONTEXT {
+            end = changed[i];
+            i += 1;
+        }
+        let end = (end + CONTEXT + 1).min(lines.len());
+
+        // Line numbers of the hunk start at the first line of each text, which is part of it
+        let old_start = lines[..start]
+            .iter()
+            .filter(|x| !matches!(x, Line::Added(_)))
+            .count();
+        let new_start = lines[..start]
+            .iter()
+            .filter(|x| !matches!(x, Line::Removed(_)))
+            .count();
+        let hunk = &lines[start..end];
+        let old_count = hunk.iter().filter(|x| !matches!(x, Line::Added(_))).count();
+        let new_count = hunk
+            .iter()
+            .filter(|x| !matches!(x, Line::Removed(_)))
+            .count();
+
+        result += &format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        );
+        for line in hunk {
+            match line {
+                Line::Equal(x) => result += &format!(" {}\n", x),
+                Line::Removed(x) => result += &format!("-{}\n", x),
+                Line::Added(x) => result += &format!("+{}\n", x),
+            }
+        }
+    }
+
+    result
+}
+
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+/// Finds the longest common subsequence of lines, after skipping the common start and end
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut lines = old[..prefix]
+        .iter()
+        .map(|x| Line::Equal(x))
+        .collect::<Vec<_>>();
+    if (old_middle.len() + 1) * (new_middle.len() + 1) > MAX_TABLE_SIZE {
+        lines.extend(old_middle.iter().map(|x| Line::Removed(x)));
+        lines.extend(new_middle.iter().map(|x| Line::Added(x)));
+    } else {
+        // lengths[i][j] is the length of the common subsequence of old_middle[i..] and new_middle[j..]
+        let width = new_middle.len() + 1;
+        let mut lengths = vec![0u32; (old_middle.len() + 1) * width];
+        for i in (0..old_middle.len()).rev() {
+            for j in (0..new_middle.len()).rev() {
+                lengths[i * width + j] = if old_middle[i] == new_middle[j] {
+                    lengths[(i + 1) * width + j + 1] + 1
+                } else {
+                    lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old_middle.len() || j < new_middle.len() {
+            if i < old_middle.len() && j < new_middle.len() && old_middle[i] == new_middle[j] {
+                lines.push(Line::Equal(old_middle[i]));
+                i += 1;
+                j += 1;
+            } else if j == new_middle.len()
+                || (i < old_middle.len()
+                    && lengths[(i + 1) * width + j] >= lengths[i * width + j + 1])
+            {
+                lines.push(Line::Removed(old_middle[i]));
+                i += 1;
+            } else {
+                lines.push(Line::Added(new_middle[j]));
+                j += 1;
+            }
+        }
+    }
+    lines.extend(old[old.len() - suffix..].iter().map(|x| Line::Equal(x)));
+
+    lines
+}
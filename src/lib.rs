@@ -0,0 +1,16 @@
+extern crate ansi_term;
+extern crate serde;
+
+pub mod backend;
+pub mod log;
+pub mod parser;
+
+pub use crate::parser::{
+    parse_source, parse_str, DocumentationData, DocumentationEntry, EntryType, InheritedClass,
+    Override, PageLink, ParseError, ParseErrorKind, ParseOptions, ShowPrefixed, SinceFilter,
+    Symbol, SymbolArgs, Version,
+};
+
+pub fn print_warning(message: &str) {
+    log::warning(message);
+}
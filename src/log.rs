@@ -0,0 +1,63 @@
+//! Messages on stderr, filtered by the verbosity and colored, if enabled
+
+use ansi_term::Colour::{Red, Yellow};
+
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only errors
+    Quiet,
+    /// Errors and warnings
+    Normal,
+    /// Everything, including progress and timing
+    Verbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+static COLOR: AtomicBool = AtomicBool::new(true);
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+pub fn set_color(color: bool) {
+    COLOR.store(color, Ordering::Relaxed);
+}
+
+pub fn is_verbose() -> bool {
+    VERBOSITY.load(Ordering::Relaxed) >= Verbosity::Verbose as u8
+}
+
+/// Errors are always printed
+pub fn error(message: &str) {
+    match COLOR.load(Ordering::Relaxed) {
+        true => eprintln!("{}", Red.paint(message)),
+        false => eprintln!("{}", message),
+    }
+}
+
+/// Warnings are counted even if they aren't printed
+pub fn warning(message: &str) {
+    WARNINGS.fetch_add(1, Ordering::Relaxed);
+    if VERBOSITY.load(Ordering::Relaxed) < Verbosity::Normal as u8 {
+        return;
+    }
+    let message = format!("Warning: {}", message);
+    match COLOR.load(Ordering::Relaxed) {
+        true => eprintln!("{}", Yellow.paint(message)),
+        false => eprintln!("{}", message),
+    }
+}
+
+/// Number of warnings so far
+pub fn warning_count() -> usize {
+    WARNINGS.load(Ordering::Relaxed)
+}
+
+pub fn verbose(message: &str) {
+    if is_verbose() {
+        eprintln!("{}", message);
+    }
+}
@@ -1,53 +1,544 @@
 extern crate ansi_term;
 extern crate clap;
 extern crate glob;
+extern crate rayon;
 extern crate serde;
 extern crate serde_json;
+extern crate toml;
 
-use crate::backend::markdownbackend::MarkdownBackend;
-use crate::backend::Backend;
+use godotdoc::backend::doxygenbackend::DoxygenBackend;
+use godotdoc::backend::markdownbackend::MarkdownBackend;
+use godotdoc::backend::{
+    group_pages, inheritance_tree, script_path, Backend, BackendError, CalloutStyle, GroupBy,
+    IndexPage, InheritedMembers, PrivateSection,
+};
 
-use ansi_term::Colour::Red;
-use clap::{App, Arg};
-use serde::Deserialize;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use serde::{Deserialize, Serialize};
 
-use glob::Pattern;
+use glob::{MatchOptions, Pattern};
+use rayon::prelude::*;
 
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::OsStr;
-use std::fs::File;
+use std::io::{IsTerminal, Read, Write};
+use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
 
-use std::fmt::Display;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Instant;
 
-mod backend;
-mod parser;
+use godotdoc::log::{self, Verbosity};
+use godotdoc::parser::{
+    parse_source, DocumentationData, DocumentationEntry, EntryType, FunctionArgStruct,
+    InheritedClass, Override, PageLink, ParseError, ParseErrorKind, ParseOptions, ShowPrefixed,
+    SinceFilter, Symbol, SymbolArgs,
+};
+use godotdoc::print_warning;
 
-use crate::parser::parse_file;
+mod diff;
+mod manifest;
+mod nav;
+mod output_names;
+mod patterns;
+mod project;
+mod sources;
 
-fn handle_error<T, R: Display>(x: Result<T, R>, message: &str) -> T {
-    match x {
-        Ok(y) => y,
-        Err(e) => {
-            eprintln!("{}", Red.paint(format!("{}: {}", message, e)));
-            ::std::process::exit(1);
+use crate::manifest::{Manifest, ManifestEntry};
+use crate::nav::Nav;
+use crate::output_names::OutputNames;
+use crate::patterns::{compile_patterns, match_options, matching_pattern, matching_pattern_with};
+use crate::sources::{Origin, Sources};
+
+/// The exit code of a run, which failed with the error
+const EXIT_CODES: &str = "EXIT CODES:
+    0    The documentation was generated
+    1    A script couldn't be documented, the documentation is out of date with --check,
+         or there were warnings with --fail-on-warning
+    2    Invalid arguments or config
+    3    Reading or writing a file failed";
+
+enum Error {
+    Parse(ParseError),
+    /// Invalid arguments, config files or settings
+    Usage(String),
+    /// Files, which couldn't be read or written
+    Io(String),
+    Other(String),
+}
+
+impl Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::Parse(e) if e.kind == ParseErrorKind::Io => 3,
+            Error::Parse(_) | Error::Other(_) => 1,
+            Error::Usage(_) => 2,
+            Error::Io(_) => 3,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Error {
+        Error::Parse(e)
+    }
+}
+
+impl From<String> for Error {
+    fn from(e: String) -> Error {
+        Error::Other(e)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "{}", e),
+            Error::Usage(e) | Error::Io(e) | Error::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Writes the parse errors as a JSON array to the error file or stderr
+fn write_json_errors(errors: &[ParseError], error_file: Option<&str>) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(errors).map_err(|e| e.to_string())?;
+    match error_file {
+        Some(path) => std::fs::write(path, json + "\n")
+            .map_err(|e| format!("Failed to write error file: {}, {}", path, e)),
+        None => {
+            eprintln!("{}", json);
+            Ok(())
         }
     }
 }
 
-#[derive(Default, Deserialize)]
+/// `show_prefixed` is either a single flag, a flag per entry type or `"collapsed"`
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+enum ShowPrefixedConfig {
+    All(bool),
+    PerType(BTreeMap<String, bool>),
+    Collapsed(Collapsed),
+}
+
+/// Shows every prefixed member in a collapsed private section, like `private_section = "details"`
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Collapsed {
+    Collapsed,
+}
+
+#[derive(Clone, Default, Deserialize)]
 struct Configuration {
     backend: Option<String>,
     excluded_files: Option<Vec<String>>,
-    show_prefixed: Option<bool>,
+    show_prefixed: Option<ShowPrefixedConfig>,
+    private_section: Option<String>,
+    callout_style: Option<String>,
+    inherited_members: Option<String>,
+    mark_overrides: Option<bool>,
+    show_dependencies: Option<bool>,
+    max_depth: Option<u32>,
+    follow_symlinks: Option<bool>,
+    resolve_constants: Option<bool>,
+    flat: Option<bool>,
+    split_classes: Option<bool>,
+    clean_output: Option<bool>,
+    default_excludes: Option<bool>,
+    heading_offset: Option<u32>,
+    signature_max_width: Option<usize>,
+    ignored_comment_prefixes: Option<Vec<String>>,
+    source_url_template: Option<String>,
+    root_url: Option<String>,
+    title: Option<String>,
+    index: Option<bool>,
+    group_by: Option<String>,
+    nav: Option<String>,
+    since_filter: Option<String>,
+    output_names: Option<String>,
+}
+
+impl Configuration {
+    /// Takes every key, which isn't set, from other
+    fn or(self, other: Configuration) -> Configuration {
+        Configuration {
+            backend: self.backend.or(other.backend),
+            excluded_files: self.excluded_files.or(other.excluded_files),
+            show_prefixed: self.show_prefixed.or(other.show_prefixed),
+            private_section: self.private_section.or(other.private_section),
+            callout_style: self.callout_style.or(other.callout_style),
+            inherited_members: self.inherited_members.or(other.inherited_members),
+            mark_overrides: self.mark_overrides.or(other.mark_overrides),
+            show_dependencies: self.show_dependencies.or(other.show_dependencies),
+            max_depth: self.max_depth.or(other.max_depth),
+            follow_symlinks: self.follow_symlinks.or(other.follow_symlinks),
+            resolve_constants: self.resolve_constants.or(other.resolve_constants),
+            flat: self.flat.or(other.flat),
+            split_classes: self.split_classes.or(other.split_classes),
+            clean_output: self.clean_output.or(other.clean_output),
+            default_excludes: self.default_excludes.or(other.default_excludes),
+            heading_offset: self.heading_offset.or(other.heading_offset),
+            signature_max_width: self.signature_max_width.or(other.signature_max_width),
+            ignored_comment_prefixes: self
+                .ignored_comment_prefixes
+                .or(other.ignored_comment_prefixes),
+            source_url_template: self.source_url_template.or(other.source_url_template),
+            root_url: self.root_url.or(other.root_url),
+            title: self.title.or(other.title),
+            index: self.index.or(other.index),
+            group_by: self.group_by.or(other.group_by),
+            nav: self.nav.or(other.nav),
+            since_filter: self.since_filter.or(other.since_filter),
+            output_names: self.output_names.or(other.output_names),
+        }
+    }
+}
+
+/// A directory or a single script given on the command line
+struct Input<'a> {
+    path: &'a Path,
+    /// The directory of the input, which contains its config file
+    dir: &'a Path,
+    /// Where the documentation of the input goes, relative to the output directory
+    prefix: PathBuf,
+}
+
+impl<'a> Input<'a> {
+    fn new(path: &'a Path, nested: bool) -> Result<Input<'a>, String> {
+        let dir = if path.is_file() {
+            path.parent().unwrap_or_else(|| Path::new("."))
+        } else {
+            path
+        };
+        if !nested {
+            return Ok(Input {
+                path,
+                dir,
+                prefix: PathBuf::from("."),
+            });
+        }
+
+        let current_dir = std::env::current_dir().map_err(|e| e.to_string())?;
+        let outside = || {
+            format!(
+                "{} is outside of the current directory, which is the root of the documentation for several inputs",
+                path.display()
+            )
+        };
+        let relative_dir = if dir.is_absolute() {
+            dir.strip_prefix(&current_dir).map_err(|_| outside())?
+        } else {
+            dir
+        };
+
+        let mut prefix = PathBuf::from(".");
+        for component in relative_dir.components() {
+            match component {
+                Component::Normal(x) => prefix.push(x),
+                Component::CurDir => (),
+                _ => return Err(outside()),
+            }
+        }
+
+        Ok(Input { path, dir, prefix })
+    }
+}
+
+/// Reads the config files of all inputs, keys set by earlier inputs take precedence.
+/// Excluded files are collected from every config file, relative to its own input.
+fn read_configurations(
+    inputs: &[Input],
+    config_path: Option<&Path>,
+) -> Result<Configuration, String> {
+    let explicit = config_path
+        .map(|path| {
+            let dir = path
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."));
+            Ok::<_, String>((read_config_file(path)?, dir.canonicalize().ok()))
+        })
+        .transpose()?;
+
+    let mut merged = Configuration::default();
+    let mut excluded_files: Option<Vec<String>> = None;
+    for input in inputs {
+        let (mut config, config_dir) = match &explicit {
+            Some((config, dir)) => (config.clone(), dir.clone()),
+            None => find_configuration(input.dir).map(|(config, dir)| (config, Some(dir)))?,
+        };
+
+        // Patterns are relative to the config, the config may be in an outer directory
+        let relative_dir = config_dir.and_then(|config_dir| {
+            let dir = input.dir.canonicalize().ok()?;
+            dir.strip_prefix(config_dir).ok().map(|relative| {
+                relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+        });
+        if let Some(patterns) = config.excluded_files.take() {
+            let prefix = input.prefix.to_string_lossy();
+            excluded_files.get_or_insert_with(Vec::new).extend(
+                patterns
+                    .iter()
+                    .map(|pattern| patterns::normalize(pattern))
+                    .filter_map(|pattern| match &relative_dir {
+                        Some(relative_dir) => rebase_pattern(pattern, relative_dir),
+                        None => Some(pattern),
+                    })
+                    .map(|pattern| {
+                        if prefix == "." {
+                            pattern
+                        } else {
+                            format!("{}/{}", prefix, pattern.trim_start_matches("./"))
+                        }
+                    }),
+            );
+        }
+        merged = merged.or(config);
+    }
+    merged.excluded_files = excluded_files;
+
+    Ok(merged)
+}
+
+/// Drops scripts, which were found more than once, e.g. in a directory and on their own,
+/// different scripts must not end up in the same output file and no output may replace a script,
+/// the index, the inheritance page, the navigation or the page of an inner class
+fn remove_duplicates(
+    files: &mut Vec<(PathBuf, PathBuf)>,
+    settings: &Settings,
+) -> Result<(), Error> {
+    let mut reserved = Vec::new();
+    if settings.title.is_some() {
+        for backend in settings.backends.iter() {
+            let extension = backend.get_extension();
+            reserved.push(PathBuf::from(format!("index.{}", extension)));
+            reserved.push(PathBuf::from(format!("inheritance.{}", extension)));
+        }
+    }
+    if let Some(nav) = settings.nav {
+        reserved.push(PathBuf::from(nav.file_name()));
+    }
+
+    // The script of every output, with how it is named in errors
+    let mut outputs: HashMap<PathBuf, (PathBuf, String)> = HashMap::new();
+    let mut unique = Vec::new();
+    'files: for (path, output) in files.drain(..) {
+        for backend in settings.backends.iter() {
+            let extension = backend.get_extension();
+            let target = output_name(&output, path.file_name().unwrap(), settings, &extension);
+            let target_path = settings.output_path.join(&target);
+            if target_path.extension() == Some(OsStr::new("gd")) && target_path.is_file() {
+                return Err(format!(
+                    "The documentation of {} would overwrite the script {}",
+                    path.display(),
+                    target_path.display()
+                )
+                .into());
+            }
+            if reserved.contains(&target) {
+                return Err(format!(
+                    "The documentation of {} would overwrite the generated {}",
+                    path.display(),
+                    target.display()
+                )
+                .into());
+            }
+            if let Some((other, name)) = outputs.get(&target) {
+                if other.canonicalize().ok() == path.canonicalize().ok() {
+                    continue 'files;
+                }
+                return Err(format!(
+                    "{} and {} would both be documented as {}",
+                    name,
+                    path.display(),
+                    target.display()
+                )
+                .into());
+            }
+            outputs.insert(target, (path.clone(), path.display().to_string()));
+        }
+        unique.push((path, output));
+    }
+    *files = unique;
+
+    // The pages of inner classes are registered after every script,
+    // so a collision is reported the same way in every order
+    if settings.split_classes {
+        for (path, output) in files.iter() {
+            for class in inner_classes(path, output, settings) {
+                let file_name =
+                    class_file_name(&path.file_name().unwrap().to_string_lossy(), &class);
+                for backend in settings.backends.iter() {
+                    let extension = backend.get_extension();
+                    let target = output_name(output, OsStr::new(&file_name), settings, &extension);
+                    let source = format!("the class {} of {}", class, path.display());
+                    if reserved.contains(&target) {
+                        return Err(format!(
+                            "The documentation of {} would overwrite the generated {}",
+                            source,
+                            target.display()
+                        )
+                        .into());
+                    }
+                    if let Some((_, name)) = outputs.get(&target) {
+                        return Err(format!(
+                            "{} and {} would both be documented as {}",
+                            name,
+                            source,
+                            target.display()
+                        )
+                        .into());
+                    }
+                    outputs.insert(target, (path.clone(), source));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Names of the inner classes, which get pages of their own with --split-classes.
+/// Scripts, which can't be parsed, are reported when they are documented.
+fn inner_classes(path: &Path, output: &Path, settings: &Settings) -> Vec<String> {
+    if exclusion_reason(&output.join(path.file_name().unwrap()), settings).is_some() {
+        return Vec::new();
+    }
+    let Ok(input) = std::fs::read(path) else {
+        return Vec::new();
+    };
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    let Ok(mut data) = parse_source(&file_name, input.as_slice(), &settings.parse_options) else {
+        return Vec::new();
+    };
+    if let Some(since_filter) = &settings.since_filter {
+        data.filter_since(since_filter);
+    }
+    data.entries
+        .iter()
+        .flat_map(|entry| entry.symbols.iter())
+        .filter(|symbol| matches!(symbol.arg, Some(SymbolArgs::ClassArgs(_))))
+        .map(|symbol| symbol.name.clone())
+        .collect()
+}
+
+/// The script an inner class would be, if it had a file of its own, like `player_Inventory.gd`
+fn class_file_name(source_file: &str, class: &str) -> String {
+    format!("{}_{}.gd", source_file.trim_end_matches(".gd"), class)
+}
+
+/// The path of the documentation of a script relative to the output directory, named by the
+/// output names. In flat mode the directories are joined into the name, like `ui__button.gd.md`.
+fn output_name(output: &Path, file_name: &OsStr, settings: &Settings, extension: &str) -> PathBuf {
+    let path = output.join(
+        settings
+            .output_names
+            .apply(&file_name.to_string_lossy(), extension),
+    );
+    if !settings.flat {
+        return path
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .collect();
+    }
+
+    PathBuf::from(
+        path.components()
+            .filter_map(|c| match c {
+                Component::Normal(x) => Some(x.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("__"),
+    )
+}
+
+fn show_prefixed_from_config(config: ShowPrefixedConfig) -> Result<ShowPrefixed, String> {
+    match config {
+        ShowPrefixedConfig::All(show) => Ok(ShowPrefixed::all(show)),
+        ShowPrefixedConfig::Collapsed(_) => Ok(ShowPrefixed::all(true)),
+        ShowPrefixedConfig::PerType(mut per_type) => {
+            let default = per_type.remove("default").unwrap_or(true);
+            ShowPrefixed::new(default, per_type.into_iter().collect())
+        }
+    }
 }
 
 pub struct Settings<'a> {
-    backend: Box<dyn Backend>,
+    backends: Vec<Box<dyn Backend>>,
+    /// The root of the project, `res://` paths are relative to it
+    input_path: &'a Path,
+    /// Output directories of the inputs, relative to the output path
+    input_prefixes: Vec<PathBuf>,
     output_path: &'a Path,
+    /// The output directory, if it exists, so it isn't documented when it is inside of an input
+    canonical_output_path: Option<PathBuf>,
 
     excluded_files: Vec<Pattern>,
-    show_prefixed: bool,
+    /// Hidden directories and third party plugins, unless disabled
+    default_excludes: Vec<Pattern>,
+    /// If there are any, only files matching one of them are documented
+    included_files: Vec<Pattern>,
+    parse_options: ParseOptions,
+    /// Only symbols with a `@since` version accepted by it are documented
+    since_filter: Option<SinceFilter>,
+    max_depth: Option<u32>,
+    follow_symlinks: bool,
+    /// Collects the constants of every script first, so enum values can refer to them
+    resolve_constants: bool,
+    /// Collects every script first, so pages can show the members of the scripts they extend
+    inherited_members: InheritedMembers,
+    /// Collects every script first, so functions can be compared with the ones of the scripts they extend
+    mark_overrides: bool,
+    /// The documented scripts by their `res://` path, if the inherited members or overrides are shown
+    scripts: BTreeMap<String, DocumentationData>,
+    /// Global names of the autoload singletons of the `project.godot` by their `res://` path
+    autoloads: BTreeMap<String, String>,
+    /// Writes every file directly into the output directory
+    flat: bool,
+    /// How the files of the documentation are named after their scripts
+    output_names: OutputNames,
+    /// Documents the inner classes of scripts on pages of their own
+    split_classes: bool,
+    print_ast: bool,
+    /// Lists the symbols without a description instead of generating the documentation
+    todo: bool,
+    /// Prints every deprecated symbol, every script is parsed for it
+    warn_deprecated: bool,
+    /// Removes the files of the last run, which weren't generated again
+    clean: bool,
+    force: bool,
+    /// Stops at the first script, which fails, instead of documenting the others
+    fail_fast: bool,
+    /// Warnings fail the run, so overrides with other arguments than the function they override are reported
+    fail_on_warning: bool,
+    /// Compares the documentation with the output directory instead of writing it
+    check: bool,
+    /// Prints what changed in every file, which is out of date, with --check
+    diff: bool,
+    /// Lists the files, which would be written, instead of writing them, implies `check`
+    dry_run: bool,
+    /// Number of threads, 0 uses one per core
+    jobs: usize,
+    /// Title of the whole documentation, the index page is only written if there is one
+    title: Option<String>,
+    /// How the index clusters the pages
+    group_by: GroupBy,
+    /// Pages link each other with absolute URLs below it, which end with a '/'
+    root_url: Option<String>,
+    /// Navigation file of a documentation host, which is written next to the pages
+    nav: Option<Nav>,
+    /// Everything, which affects the generated files, to detect when they need to be regenerated
+    fingerprint: String,
+    /// Where every setting was taken from, for `--print-config`
+    sources: Sources,
 }
 
 fn main() {
@@ -55,10 +546,17 @@ fn main() {
         .version("1.0")
         .author("Florian Kothmeier <floriankothmeier@web.de>")
         .about("Documentation generator for Gdscript")
+        .after_help(EXIT_CODES)
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name("list-backends")
+                .about("Lists every backend with its extension and the settings it uses"),
+        )
         .arg(
             Arg::with_name("backend")
-                .help("Sets the type of file, which will be generated")
+                .help("Sets the types of files, which will be generated, as a comma separated list or 'all'")
                 .long("backend")
+                .alias("output-format")
                 .takes_value(true),
         )
         .arg(
@@ -67,126 +565,2673 @@ fn main() {
                 .short("o")
                 .long("output")
                 .value_name("Directory")
-                .required(true),
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("config")
+                .help("Reads the config from this file instead of searching for one")
+                .long("config")
+                .value_name("File")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("title")
+                .help("Title of the index page, which links every page, the name of the project directory by default")
+                .long("title")
+                .value_name("Title")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("group_by")
+                .help("Lists the pages of the index in groups")
+                .long("group-by")
+                .value_name("Grouping")
+                .possible_values(&["none", "directory", "class_name_prefix"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("output_names")
+                .help("Names the files of the documentation: keep, strip_extension, slug or a template like '{stem}.{ext}'")
+                .long("output-names")
+                .value_name("Names")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("callout_style")
+                .help("Formats the @since, @deprecated, @note and @warning tags of the Markdown pages")
+                .long("callout-style")
+                .value_name("Style")
+                .possible_values(&["plain", "gfm", "emoji"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("inherited_members")
+                .help("Shows the members every script inherits from the documented scripts it extends on its Markdown page")
+                .long("inherited-members")
+                .value_name("Mode")
+                .possible_values(&["none", "linked", "listed"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("root_url")
+                .help("Links the pages with absolute URLs below this one instead of relative paths")
+                .long("root-url")
+                .value_name("URL")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("since_filter")
+                .help("Only documents symbols with a @since tag matching the version, like '>=1.2.0'")
+                .long("since-filter")
+                .value_name("Version")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("nav")
+                .help("Writes the navigation of a documentation host, which links every page")
+                .long("nav")
+                .value_name("Host")
+                .possible_values(&["docsify", "mkdocs"])
+                .takes_value(true),
         )
         .arg(
             Arg::with_name("show_prefixed")
                 .help("Show members prefixed with an '_'")
-                .long("show_prefixed"),
+                .long("show_prefixed")
+                .conflicts_with("hide_prefixed"),
         )
         .arg(
             Arg::with_name("hide_prefixed")
                 .help("Hide members prefixed with an '_'")
                 .long("hide_prefixed"),
         )
-        .arg(Arg::with_name("input directory").required(true).index(1))
-        .get_matches();
-
-    let input_dir = matches.value_of("input directory").unwrap();
-    let output_dir = matches.value_of("output").unwrap();
-    let show_prefixed = matches
-        .value_of("show_prefixed")
-        .map(|_| true)
-        .or(matches.value_of("hide_prefixed").map(|_| false));
-    let config;
-    if let Ok(f) = File::open(Path::new(input_dir).join("godotdoc_config.json")) {
-        config = handle_error(
-            serde_json::from_reader(f),
-            "Error while reading config file",
-        );
+        .arg(
+            Arg::with_name("private_section")
+                .help("Shows members prefixed with an '_' in a section of their own after the others")
+                .long("private-section")
+                .value_name("Style")
+                .possible_values(&["none", "heading", "details"])
+                .takes_value(true)
+                .conflicts_with("hide_prefixed"),
+        )
+        .arg(
+            Arg::with_name("exclude")
+                .help("Excludes files matching the pattern, can be given multiple times")
+                .long("exclude")
+                .value_name("Pattern")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("include")
+                .help("Only documents files matching the pattern, can be given multiple times")
+                .long("include")
+                .value_name("Pattern")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("max_depth")
+                .help("Only documents files up to N directory levels deep, 0 means unlimited")
+                .long("max-depth")
+                .value_name("N")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("follow_symlinks")
+                .help("Follows symbolic links to directories, which are skipped by default. Every directory is only visited once, so links can't form a loop. Links to scripts are always followed")
+                .long("follow-symlinks"),
+        )
+        .arg(
+            Arg::with_name("resolve_constants")
+                .help("Parses every script twice, so enum values can refer to the constants of other scripts by their class_name")
+                .long("resolve-constants"),
+        )
+        .arg(
+            Arg::with_name("mark_overrides")
+                .help("Parses every script twice, so functions, which override a function of the scripts they extend, are marked")
+                .long("mark-overrides"),
+        )
+        .arg(
+            Arg::with_name("flat")
+                .help("Writes every file directly into the output directory, named after its path")
+                .long("flat"),
+        )
+        .arg(
+            Arg::with_name("split_classes")
+                .help("Documents every inner class on a page of its own, named after its script and the class")
+                .long("split-classes"),
+        )
+        .arg(
+            Arg::with_name("heading_offset")
+                .help("Shifts every generated heading down by N levels")
+                .long("heading-offset")
+                .value_name("N")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("signature_max_width")
+                .help("Writes function signatures longer than N characters on multiple lines, 0 disables this")
+                .long("signature-max-width")
+                .value_name("N")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .help("Prints every file, why files are skipped, where output is written and timings")
+                .short("v")
+                .long("verbose"),
+        )
+        .arg(
+            Arg::with_name("quiet")
+                .help("Prints nothing but errors")
+                .short("q")
+                .long("quiet")
+                .conflicts_with("verbose"),
+        )
+        .arg(
+            Arg::with_name("color")
+                .help("Colors errors and warnings, 'auto' only colors them on a terminal unless NO_COLOR is set")
+                .long("color")
+                .value_name("When")
+                .takes_value(true)
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::with_name("error_format")
+                .help("Sets the format of parse errors")
+                .long("error-format")
+                .value_name("Format")
+                .possible_values(&["human", "json"])
+                .default_value("human"),
+        )
+        .arg(
+            Arg::with_name("error_file")
+                .help("Writes parse errors to this file instead of stderr, requires --error-format json")
+                .long("error-file")
+                .value_name("File")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("strict_encoding")
+                .help("Fails on files, which aren't valid UTF-8, instead of replacing invalid bytes")
+                .long("strict-encoding"),
+        )
+        .arg(
+            Arg::with_name("warn_unrecognized")
+                .help("Warns about declarations, which aren't recognized and therefore not documented")
+                .long("warn-unrecognized"),
+        )
+        .arg(
+            Arg::with_name("warn_deprecated")
+                .help("Warns about every symbol tagged with @deprecated")
+                .long("warn-deprecated"),
+        )
+        .arg(
+            Arg::with_name("fail_on_warning")
+                .help("Fails the run, if there were any warnings, even if the documentation was generated")
+                .long("fail-on-warning"),
+        )
+        .arg(
+            Arg::with_name("clean")
+                .help("Removes files generated by the last run, whose script doesn't exist or isn't documented anymore")
+                .long("clean"),
+        )
+        .arg(
+            Arg::with_name("force")
+                .help("Regenerates every file, even if it is up to date")
+                .long("force"),
+        )
+        .arg(
+            Arg::with_name("no_default_excludes")
+                .help("Documents hidden directories and the addons directory as well")
+                .long("no-default-excludes"),
+        )
+        .arg(
+            Arg::with_name("no_index")
+                .help("Doesn't write an index page")
+                .long("no-index")
+                .conflicts_with("title"),
+        )
+        .arg(
+            Arg::with_name("fail_fast")
+                .help("Stops at the first script, which can't be documented, instead of documenting the others")
+                .long("fail-fast"),
+        )
+        .arg(
+            Arg::with_name("check")
+                .help("Compares the documentation with the output directory without writing anything, fails if it is out of date")
+                .long("check"),
+        )
+        .arg(
+            Arg::with_name("diff")
+                .help("Prints a unified diff of every file, which is out of date")
+                .long("diff")
+                .requires("check"),
+        )
+        .arg(
+            Arg::with_name("dry_run")
+                .help("Lists the files, which would be written, with their sizes without writing anything")
+                .long("dry-run")
+                .conflicts_with_all(&["check", "print_ast"]),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .help("Sets the number of files, which are documented in parallel, defaults to the number of cores")
+                .short("j")
+                .long("jobs")
+                .value_name("N")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("print_ast")
+                .help("Prints the parsed documentation of every file instead of generating output")
+                .long("print-ast"),
+        )
+        .arg(
+            Arg::with_name("todo")
+                .help("Lists every documented symbol without a description instead of generating output, as JSON with --error-format json")
+                .long("todo")
+                .conflicts_with_all(&["print_ast", "check", "dry_run"]),
+        )
+        .arg(
+            Arg::with_name("print_config")
+                .help("Prints every setting with where it was taken from instead of generating output")
+                .long("print-config")
+                .value_name("Format")
+                .possible_values(&["json", "toml"])
+                .takes_value(true)
+                .conflicts_with("stdin"),
+        )
+        .arg(
+            Arg::with_name("stdin")
+                .help("Reads a script from stdin and writes its documentation to stdout")
+                .long("stdin")
+                .conflicts_with_all(&["input", "output"]),
+        )
+        .arg(
+            Arg::with_name("stdin_filename")
+                .help("Name of the script read from stdin, used in headings and errors")
+                .long("stdin-filename")
+                .value_name("Name")
+                .takes_value(true)
+                .requires("stdin"),
+        )
+        .arg(
+            Arg::with_name("input")
+                .help("Directories or single files to document")
+                .required_unless("stdin")
+                .multiple(true)
+                .index(1),
+        )
+        .get_matches_safe()
+        .unwrap_or_else(|e| {
+            // --help and --version aren't errors
+            if !e.use_stderr() {
+                e.exit();
+            }
+            eprintln!("{}", e.message);
+            std::process::exit(2);
+        });
+
+    log::set_verbosity(if matches.is_present("verbose") {
+        Verbosity::Verbose
+    } else if matches.is_present("quiet") {
+        Verbosity::Quiet
     } else {
-        config = Configuration::default();
+        Verbosity::Normal
+    });
+    log::set_color(match matches.value_of("color") {
+        Some("always") => true,
+        Some("never") => false,
+        _ => {
+            std::env::var_os("NO_COLOR").map_or(true, |x| x.is_empty())
+                && std::io::stderr().is_terminal()
+        }
+    });
+
+    // A typo in the backend is reported before anything is read
+    if let Some(names) = matches.value_of("backend") {
+        if let Some(name) = names
+            .split(',')
+            .map(|x| x.trim())
+            .find(|x| !is_backend_name(x))
+        {
+            log::error(&format!("Error: {}", unknown_backend(name)));
+            std::process::exit(2);
+        }
     }
 
-    let config_backend = config.backend.as_ref().map(|s| s.as_str());
-    let backend: Box<dyn Backend> = handle_error(
-        get_backend(matches.value_of("backend").or(config_backend)),
-        "Error",
-    );
+    if matches.subcommand_matches("list-backends").is_some() {
+        if let Err(e) = list_backends(&mut std::io::stdout()) {
+            log::error(&format!("Error: {}", e));
+            std::process::exit(3);
+        }
+        return;
+    }
 
-    let settings = Settings {
-        backend: backend,
-        output_path: Path::new(output_dir),
-
-        excluded_files: config
-            .excluded_files
-            .unwrap_or(Vec::new())
-            .drain(..)
-            .map(|s| {
-                handle_error(
-                    Pattern::new(s.as_str()).map_err(|e| e.to_string()),
-                    "Couldn't parse pattern",
-                )
-            })
-            .collect(),
-        show_prefixed: show_prefixed.or(config.show_prefixed).unwrap_or(true),
-    };
-    handle_error(
-        traverse_directory(
-            Path::new(input_dir).to_path_buf(),
-            Path::new(".").to_path_buf(),
-            &settings,
-        ),
-        "Error",
-    )
-}
+    let errors = if matches.is_present("stdin") {
+        document_stdin(&matches).err().into_iter().collect()
+    } else {
+        document_inputs(&matches)
+    };
 
-fn get_backend(name: Option<&str>) -> Result<Box<dyn Backend>, String> {
-    match name {
-        Some("markdown") | None => Ok(Box::new(MarkdownBackend::new())),
-        _ => Err("Unsupported backend".to_string()),
+    // Errors are reported in the order of the files, no matter which thread finished first
+    let mut parse_errors = Vec::new();
+    for error in errors.iter() {
+        match error {
+            Error::Parse(e) if matches.value_of("error_format") == Some("json") => {
+                parse_errors.push(e.clone())
+            }
+            e => log::error(&format!("Error: {}", e)),
+        }
+    }
+    let mut exit_code = errors.iter().map(Error::exit_code).max().unwrap_or(0);
+    if matches.value_of("error_format") == Some("json") {
+        if let Err(e) = write_json_errors(&parse_errors, matches.value_of("error_file")) {
+            log::error(&format!("Error: {}", e));
+            exit_code = exit_code.max(3);
+        }
+    }
+    if exit_code == 0 && matches.is_present("fail_on_warning") && log::warning_count() > 0 {
+        log::error(&format!(
+            "Error: Failing because of --fail-on-warning, warnings: {}",
+            log::warning_count()
+        ));
+        exit_code = 1;
+    }
+    if exit_code != 0 {
+        ::std::process::exit(exit_code);
     }
 }
 
-fn path_matches_any(path: &Path, patterns: &Vec<Pattern>) -> bool {
-    for pattern in patterns {
-        if pattern.matches_path(path) {
-            return true;
-        }
+/// Documents a script read from stdin with the first backend, nothing is written to the file system
+fn document_stdin(matches: &ArgMatches) -> Result<(), Error> {
+    let config = match matches.value_of("config") {
+        Some(path) => read_config_file(Path::new(path)),
+        None => find_configuration(Path::new(".")).map(|x| x.0),
+    }
+    .map_err(Error::Usage)?;
+    let settings = Settings::resolve(
+        matches,
+        config,
+        Path::new("."),
+        vec![PathBuf::from(".")],
+        Path::new("."),
+    )
+    .map_err(Error::Usage)?;
+    if settings.backends.len() != 1 {
+        return Err(Error::Usage(
+            "--stdin can only write the output of a single backend".to_string(),
+        ));
+    }
+
+    let file_name = matches.value_of("stdin_filename").unwrap_or("stdin.gd");
+    let mut input = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut input)
+        .map_err(|e| Error::Io(format!("Failed to read stdin: {}", e)))?;
+    let mut data = parse_source(file_name, input.as_slice(), &settings.parse_options)?;
+    data.source_path = file_name.to_string();
+    if settings.warn_deprecated {
+        warn_deprecated(&data);
+    }
+    if let Some(since_filter) = &settings.since_filter {
+        data.filter_since(since_filter);
     }
 
-    return false;
+    if settings.print_ast {
+        println!("{:#?}", data);
+        return Ok(());
+    }
+    if settings.todo {
+        print_todo(
+            &[TodoFile::new(&data)],
+            matches.value_of("error_format") == Some("json"),
+        );
+        return Ok(());
+    }
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
+    settings.backends[0]
+        .generate_output(data, &mut stdout)
+        .and_then(|()| stdout.flush().map_err(BackendError::from))
+        .map_err(|e| Error::Io(format!("Failed to write to stdout: {}", e)))
 }
 
-fn traverse_directory(src: PathBuf, output: PathBuf, settings: &Settings) -> Result<(), String> {
-    for entry in std::fs::read_dir(src).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
+/// Documents every script of the inputs given on the command line
+fn document_inputs(matches: &ArgMatches) -> Vec<Error> {
+    let inputs = matches
+        .values_of("input")
+        .unwrap()
+        .map(Path::new)
+        .collect::<Vec<_>>();
+    let output_dir = match output_option(matches) {
+        Ok(Some((output_dir, _))) => output_dir,
+        Ok(None)
+            if matches.is_present("print_ast")
+                || matches.is_present("print_config")
+                || matches.is_present("todo") =>
+        {
+            ".".to_string()
+        }
+        Ok(None) => {
+            return vec![Error::Usage(
+                "The output directory is missing, pass --output DIRECTORY or set GODOTDOC_OUTPUT"
+                    .to_string(),
+            )]
+        }
+        Err(e) => return vec![Error::Usage(e)],
+    };
 
-        let file_name = path.file_name().map(|e| e.to_str().unwrap());
+    // A single input is the root of the documentation. With several inputs the current
+    // directory is, so the documentation mirrors the structure of the project
+    let nested = inputs.len() > 1;
+    let inputs = match inputs
+        .into_iter()
+        .map(|path| Input::new(path, nested))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(inputs) => inputs,
+        Err(e) => return vec![Error::Usage(e)],
+    };
+    let project_dir = if nested {
+        Path::new(".")
+    } else {
+        inputs[0].dir
+    };
+    let config = match read_configurations(&inputs, matches.value_of("config").map(Path::new)) {
+        Ok(config) => config,
+        Err(e) => {
+            return vec![Error::Usage(format!(
+                "Error while reading config file: {}",
+                e
+            ))]
+        }
+    };
 
-        let new_output = Path::new(&output).join(file_name.unwrap());
-        if path_matches_any(&new_output, &settings.excluded_files) {
-            continue;
+    let prefixes = inputs.iter().map(|x| x.prefix.clone()).collect();
+    let mut settings = match Settings::resolve(
+        matches,
+        config,
+        project_dir,
+        prefixes,
+        Path::new(&output_dir),
+    ) {
+        Ok(settings) => settings,
+        Err(e) => return vec![Error::Usage(e)],
+    };
+    if let Some(format) = matches.value_of("print_config") {
+        let config = match format {
+            "toml" => settings.sources.to_toml(),
+            _ => Ok(settings.sources.to_json()),
+        };
+        return match config {
+            Ok(config) => {
+                println!("{}", config);
+                Vec::new()
+            }
+            Err(e) => vec![Error::Other(e)],
+        };
+    }
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    let collected = inputs
+        .iter()
+        .try_for_each(|input| {
+            if input.path.is_file() {
+                files.push((input.path.to_path_buf(), input.prefix.clone()));
+                Ok(())
+            } else {
+                collect_files(
+                    input.path.to_path_buf(),
+                    input.prefix.clone(),
+                    1,
+                    &settings,
+                    &mut visited,
+                    &mut files,
+                )
+            }
+        })
+        .and_then(|()| remove_duplicates(&mut files, &settings));
+
+    // Scripts are documented again, when a constant of another script changes
+    let collected = collected.and_then(|()| {
+        if settings.resolve_constants {
+            let constants = collect_constants(&files, &settings)?;
+            settings.fingerprint += &manifest::hash(format!("{:?}", constants).as_bytes());
+            settings.parse_options.constants = constants;
         }
+        // Pages of scripts, which become autoloads, get their banner
+        if !settings.prints_report() {
+            settings.autoloads = collect_autoloads(project_dir, &files, &settings)?;
+            settings.fingerprint += &manifest::hash(format!("{:?}", settings.autoloads).as_bytes());
+        }
+        // Scripts are documented again, when a script they extend changes
+        let collect_scripts_first =
+            settings.inherited_members != InheritedMembers::None || settings.mark_overrides;
+        if collect_scripts_first && !settings.prints_report() {
+            settings.scripts = collect_scripts(&files, &settings)?;
+            settings.fingerprint += &manifest::hash(format!("{:?}", settings.scripts).as_bytes());
+            warn_unknown_bases(&settings.scripts);
+            if settings.mark_overrides && settings.fail_on_warning {
+                warn_mismatched_overrides(&settings);
+            }
+        }
+        Ok(())
+    });
 
-        if path.is_dir() {
-            traverse_directory(path, new_output, settings)?;
-        } else if path.is_file() && path.extension() == Some(OsStr::new("gd")) {
-            let input = File::open(&path)
-                .map_err(|e| format!("Failed to open input file: {}, {}", path.display(), e))?;
-            let output_path = settings.output_path.join(&output).join(format!(
-                "{}.{}",
-                file_name.unwrap(),
-                settings.backend.get_extension()
+    let mut errors = Vec::new();
+    match collected {
+        Err(e) => errors.push(e),
+        Ok(()) => {
+            let mut generated = HashSet::new();
+            let mut changes = Vec::new();
+            let mut failed = Vec::new();
+            let mut todo = Vec::new();
+            let mut manifest = Manifest::new(settings.output_path, settings.fingerprint.clone());
+            let start = Instant::now();
+            if let Err(mut e) = document_files(
+                &files,
+                &settings,
+                &mut generated,
+                &mut manifest,
+                &mut changes,
+                &mut failed,
+                &mut todo,
+            ) {
+                if settings.fail_fast {
+                    return e;
+                }
+                errors.append(&mut e);
+            }
+            log::verbose(&format!(
+                "Processed {} scripts in {:.1?}",
+                files.len(),
+                start.elapsed()
             ));
+            warn_duplicate_class_names(&manifest);
+            if settings.todo {
+                print_todo(&todo, matches.value_of("error_format") == Some("json"));
+            }
+            if !settings.prints_report() {
+                if let Some(title) = &settings.title {
+                    match write_indices(title, &settings, &mut manifest, &failed, &mut generated) {
+                        Ok(mut c) => changes.append(&mut c),
+                        Err(e) => errors.push(e),
+                    }
+                }
+                if let Some(nav) = settings.nav {
+                    match write_nav(nav, &settings, &mut manifest, &failed, &mut generated) {
+                        Ok(mut c) => changes.append(&mut c),
+                        Err(e) => errors.push(e),
+                    }
+                }
+            }
 
-            std::fs::create_dir_all(&output_path.parent().unwrap()).map_err(|e| e.to_string())?;
-            let mut output = File::create(&output_path).map_err(|e| {
-                format!(
-                    "Failed to open output file: {}, {}",
-                    output_path.display(),
-                    e
-                )
-            })?;
-            settings
-                .backend
-                .generate_output(
-                    parse_file(file_name.unwrap(), input, settings)?,
-                    &mut output,
-                )
-                .map_err(|e| e.to_string())?;
+            // Files of scripts, which failed, would look stale
+            let stale = stale_files(&settings, &manifest, &generated);
+            if settings.check {
+                // A dry run only removes stale files, if they would be cleaned
+                if errors.is_empty() && (!settings.dry_run || settings.clean) {
+                    changes.extend(
+                        stale
+                            .into_iter()
+                            .map(|x| Change::Stale(settings.output_path.join(x))),
+                    );
+                }
+            } else if errors.is_empty() && settings.clean && !settings.prints_report() {
+                if let Err(e) = clean_output(&settings, &stale) {
+                    errors.push(e);
+                }
+            } else {
+                // They are still known to be generated, so a later run can clean them
+                for path in stale {
+                    manifest.insert_output(path);
+                }
+            }
+            if !failed.is_empty() {
+                errors.push(Error::from(format!(
+                    "Failed to document {} of {} scripts",
+                    failed.len(),
+                    failed.len() + manifest.entries().count()
+                )));
+            }
+            if settings.dry_run {
+                for change in changes.iter() {
+                    change.report_dry_run();
+                }
+            } else if !changes.is_empty() {
+                for change in changes.iter() {
+                    change.report(settings.diff);
+                }
+                errors.push(Error::from("The documentation is out of date".to_string()));
+            }
+            // Scripts, which failed, aren't in the manifest, so they are documented again next time
+            if !settings.check && !settings.prints_report() {
+                if let Err(e) = manifest.write(settings.output_path) {
+                    errors.push(Error::Io(e));
+                }
+            }
         }
     }
+    errors
+}
+
+impl<'a> Settings<'a> {
+    /// Whether the run only prints a report, like `--print-ast`, without touching the output directory
+    fn prints_report(&self) -> bool {
+        self.print_ast || self.todo
+    }
+
+    /// Combines all sources of configuration into the final settings.
+    ///
+    /// Every key is looked up in the following order, the first one found wins:
+    /// command line flag, environment variable, config file, default value.
+    fn resolve(
+        matches: &ArgMatches,
+        config: Configuration,
+        input_path: &'a Path,
+        input_prefixes: Vec<PathBuf>,
+        output_path: &'a Path,
+    ) -> Result<Settings<'a>, String> {
+        let mut sources = Sources::default();
+        let origin = |arg| match matches.is_present(arg) {
+            true => Origin::Cli,
+            false => Origin::Default,
+        };
+        sources.record("input", origin("input"), &input_path);
+        let output_origin = output_option(matches)?.map_or(Origin::Default, |x| x.1);
+        sources.record("output", output_origin, &output_path);
+
+        let backend = sources.pick_or(
+            "backend",
+            string_option(matches, "backend", "GODOTDOC_BACKEND")?,
+            config.backend,
+            "markdown".to_string(),
+        );
+
+        let show_prefixed = if matches.is_present("show_prefixed") {
+            Some(true)
+        } else if matches.is_present("hide_prefixed") {
+            Some(false)
+        } else {
+            None
+        };
+        let show_prefixed = sources.pick_or(
+            "show_prefixed",
+            (
+                show_prefixed.map(ShowPrefixedConfig::All),
+                env_bool("GODOTDOC_SHOW_PREFIXED")?.map(ShowPrefixedConfig::All),
+            ),
+            config.show_prefixed,
+            ShowPrefixedConfig::All(true),
+        );
+        // Collapsed prefixed members are listed in a private section, unless it is set explicitly
+        let collapsed = matches!(show_prefixed, ShowPrefixedConfig::Collapsed(_));
+        let show_prefixed = show_prefixed_from_config(show_prefixed)?;
+
+        let private_section = sources.pick_or(
+            "private_section",
+            string_option(matches, "private_section", "GODOTDOC_PRIVATE_SECTION")?,
+            config.private_section,
+            if collapsed { "details" } else { "none" }.to_string(),
+        );
+        let private_section = PrivateSection::from_name(&private_section)?;
+        let callout_style = sources.pick_or(
+            "callout_style",
+            string_option(matches, "callout_style", "GODOTDOC_CALLOUT_STYLE")?,
+            config.callout_style,
+            "plain".to_string(),
+        );
+        let callout_style = CalloutStyle::from_name(&callout_style)?;
+        let inherited_members = sources.pick_or(
+            "inherited_members",
+            string_option(matches, "inherited_members", "GODOTDOC_INHERITED_MEMBERS")?,
+            config.inherited_members,
+            "none".to_string(),
+        );
+        let inherited_members = InheritedMembers::from_name(&inherited_members)?;
+        // The private section needs every private member, whether it's shown or not
+        let show_prefixed = match private_section {
+            PrivateSection::None => show_prefixed,
+            _ => ShowPrefixed::all(true),
+        };
+
+        // Excludes from the command line or the environment are added to the ones of the config
+        let mut excluded_files = pattern_option(
+            matches,
+            &mut sources,
+            "excluded_files",
+            "exclude",
+            "GODOTDOC_EXCLUDE",
+        )?;
+        let config_excludes = config.excluded_files.unwrap_or_default();
+        if !config_excludes.is_empty() {
+            sources.record("excluded_files", Origin::Config, &config_excludes);
+        }
+        excluded_files.extend(compile_patterns(&config_excludes, "excluded_files")?);
+        let included_files = pattern_option(
+            matches,
+            &mut sources,
+            "included_files",
+            "include",
+            "GODOTDOC_INCLUDE",
+        )?;
+        let index = sources.pick_or(
+            "index",
+            (
+                Some(false).filter(|_| matches.is_present("no_index")),
+                env_bool("GODOTDOC_INDEX")?,
+            ),
+            config.index,
+            true,
+        );
+        let title = sources.pick_or_else(
+            "title",
+            string_option(matches, "title", "GODOTDOC_TITLE")?,
+            config.title,
+            || {
+                input_path
+                    .canonicalize()
+                    .ok()
+                    .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+                    .unwrap_or_else(|| "Documentation".to_string())
+            },
+        );
+        let title = Some(title).filter(|_| index);
+
+        let default_excludes = sources.pick_or(
+            "default_excludes",
+            (
+                Some(false).filter(|_| matches.is_present("no_default_excludes")),
+                env_bool("GODOTDOC_DEFAULT_EXCLUDES")?,
+            ),
+            config.default_excludes,
+            true,
+        );
+        let default_excludes = match default_excludes {
+            true => compile_patterns(&DEFAULT_EXCLUDES, "the default excludes")?,
+            false => Vec::new(),
+        };
+
+        let show_dependencies = sources.pick_or(
+            "show_dependencies",
+            (None, env_bool("GODOTDOC_SHOW_DEPENDENCIES")?),
+            config.show_dependencies,
+            true,
+        );
+
+        let follow_symlinks = sources.pick_or(
+            "follow_symlinks",
+            flag_option(matches, "follow_symlinks", "GODOTDOC_FOLLOW_SYMLINKS")?,
+            config.follow_symlinks,
+            false,
+        );
+        let resolve_constants = sources.pick_or(
+            "resolve_constants",
+            flag_option(matches, "resolve_constants", "GODOTDOC_RESOLVE_CONSTANTS")?,
+            config.resolve_constants,
+            false,
+        );
+        let mark_overrides = sources.pick_or(
+            "mark_overrides",
+            flag_option(matches, "mark_overrides", "GODOTDOC_MARK_OVERRIDES")?,
+            config.mark_overrides,
+            false,
+        );
+
+        let flat = sources.pick_or(
+            "flat",
+            flag_option(matches, "flat", "GODOTDOC_FLAT")?,
+            config.flat,
+            false,
+        );
+
+        let split_classes = sources.pick_or(
+            "split_classes",
+            flag_option(matches, "split_classes", "GODOTDOC_SPLIT_CLASSES")?,
+            config.split_classes,
+            false,
+        );
+
+        let clean = sources.pick_or(
+            "clean_output",
+            flag_option(matches, "clean", "GODOTDOC_CLEAN")?,
+            config.clean_output,
+            false,
+        );
+
+        let max_depth = sources
+            .pick(
+                "max_depth",
+                number_option(matches, "max_depth", "GODOTDOC_MAX_DEPTH", "maximum depth")?,
+                config.max_depth,
+            )
+            .filter(|depth| *depth != 0);
+
+        let heading_offset = sources.pick_or(
+            "heading_offset",
+            number_option(
+                matches,
+                "heading_offset",
+                "GODOTDOC_HEADING_OFFSET",
+                "heading offset",
+            )?,
+            config.heading_offset,
+            0,
+        );
+
+        let signature_max_width = sources.pick_or(
+            "signature_max_width",
+            number_option(
+                matches,
+                "signature_max_width",
+                "GODOTDOC_SIGNATURE_MAX_WIDTH",
+                "signature width",
+            )?,
+            config.signature_max_width,
+            100,
+        );
+
+        let root_url = sources
+            .pick(
+                "root_url",
+                string_option(matches, "root_url", "GODOTDOC_ROOT_URL")?,
+                config.root_url,
+            )
+            .filter(|x| !x.is_empty())
+            .map(|root_url| match root_url.ends_with('/') {
+                true => root_url,
+                false => root_url + "/",
+            });
+
+        // Relative source links are relative to the root of the documentation as well
+        let source_url_template = sources
+            .pick(
+                "source_url_template",
+                (None, env_var("GODOTDOC_SOURCE_URL_TEMPLATE")?),
+                config.source_url_template,
+            )
+            .map(|template| match &root_url {
+                Some(root_url) if !template.contains("://") => {
+                    format!("{}{}", root_url, template.trim_start_matches("./"))
+                }
+                _ => template,
+            });
+
+        let parse_options = ParseOptions {
+            show_prefixed,
+            ignored_comment_prefixes: sources.pick_or(
+                "ignored_comment_prefixes",
+                (None, None),
+                config.ignored_comment_prefixes,
+                Vec::new(),
+            ),
+            strict_encoding: matches.is_present("strict_encoding"),
+            warn_unrecognized: matches.is_present("warn_unrecognized"),
+            constants: BTreeMap::new(),
+        };
+        let since_filter = sources
+            .pick(
+                "since_filter",
+                string_option(matches, "since_filter", "GODOTDOC_SINCE_FILTER")?,
+                config.since_filter,
+            )
+            .map(|since_filter| SinceFilter::parse(&since_filter))
+            .transpose()?;
+
+        let output_names = sources.pick_or(
+            "output_names",
+            string_option(matches, "output_names", "GODOTDOC_OUTPUT_NAMES")?,
+            config.output_names,
+            "keep".to_string(),
+        );
+        let output_names = OutputNames::from_name(&output_names)?;
+
+        let fingerprint = format!(
+            "{:?}",
+            (
+                &backend,
+                show_dependencies,
+                heading_offset,
+                signature_max_width,
+                (&source_url_template, &root_url),
+                (
+                    private_section,
+                    callout_style,
+                    inherited_members,
+                    mark_overrides
+                ),
+                &input_prefixes,
+                (flat, &output_names, split_classes),
+                &parse_options,
+                &since_filter,
+                excluded_files
+                    .iter()
+                    .chain(default_excludes.iter())
+                    .map(|x| x.as_str())
+                    .collect::<Vec<_>>(),
+                included_files
+                    .iter()
+                    .map(|x| x.as_str())
+                    .collect::<Vec<_>>(),
+            )
+        );
+
+        let backends = get_backends(
+            &backend,
+            &BackendOptions {
+                show_dependencies,
+                heading_offset,
+                signature_max_width,
+                source_url_template,
+                private_section,
+                callout_style,
+                inherited_members,
+            },
+        )?;
+
+        let group_by = sources.pick_or(
+            "group_by",
+            string_option(matches, "group_by", "GODOTDOC_GROUP_BY")?,
+            config.group_by,
+            "directory".to_string(),
+        );
+        let group_by = GroupBy::from_name(&group_by)?;
+
+        let nav = sources
+            .pick(
+                "nav",
+                string_option(matches, "nav", "GODOTDOC_NAV")?,
+                config.nav,
+            )
+            .map(|nav| Nav::from_name(&nav))
+            .transpose()?;
+        if nav.is_some() && !backends.iter().any(|x| x.get_extension() == "md") {
+            return Err(
+                "The navigation links the Markdown pages, it requires the markdown backend"
+                    .to_string(),
+            );
+        }
+
+        let jobs = sources.pick_or(
+            "jobs",
+            number_option(matches, "jobs", "GODOTDOC_JOBS", "number of jobs")?,
+            None,
+            0,
+        );
+
+        Ok(Settings {
+            backends,
+            input_path,
+            input_prefixes,
+            output_path,
+            canonical_output_path: output_path.canonicalize().ok(),
+
+            excluded_files,
+            default_excludes,
+            included_files,
+            parse_options,
+            since_filter,
+            max_depth,
+            follow_symlinks,
+            resolve_constants,
+            inherited_members,
+            mark_overrides,
+            scripts: BTreeMap::new(),
+            autoloads: BTreeMap::new(),
+            flat,
+            output_names,
+            split_classes,
+            print_ast: matches.is_present("print_ast"),
+            todo: matches.is_present("todo"),
+            warn_deprecated: matches.is_present("warn_deprecated"),
+            clean,
+            force: matches.is_present("force"),
+            fail_fast: matches.is_present("fail_fast"),
+            fail_on_warning: matches.is_present("fail_on_warning"),
+            check: matches.is_present("check") || matches.is_present("dry_run"),
+            diff: matches.is_present("diff"),
+            dry_run: matches.is_present("dry_run"),
+            jobs,
+            title,
+            group_by,
+            root_url,
+            nav,
+            fingerprint,
+            sources,
+        })
+    }
+}
+
+/// Directories nobody wants to document: hidden ones like `.git` and `.godot`, and third party plugins
+const DEFAULT_EXCLUDES: [&str; 2] = ["**/.*", "./addons"];
+
+/// Reads the patterns of a repeatable flag or, if it isn't given, of the environment,
+/// where they are separated like `PATH`
+fn pattern_option(
+    matches: &ArgMatches,
+    sources: &mut Sources,
+    name: &'static str,
+    arg: &str,
+    env: &str,
+) -> Result<Vec<Pattern>, String> {
+    let (origin, patterns, source) = match matches.values_of(arg) {
+        Some(values) => (
+            Origin::Cli,
+            values.map(str::to_string).collect::<Vec<_>>(),
+            format!("--{}", arg),
+        ),
+        None => match env_var(env)? {
+            Some(value) => (
+                Origin::Env,
+                std::env::split_paths(&value)
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .collect(),
+                env.to_string(),
+            ),
+            None => return Ok(Vec::new()),
+        },
+    };
+    sources.record(name, origin, &patterns);
+    compile_patterns(&patterns, &source)
+}
+
+fn env_var(name: &str) -> Result<Option<String>, String> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(format!("{}: {}", name, e)),
+    }
+}
+
+/// The output directory from the command line or else the environment
+fn output_option(matches: &ArgMatches) -> Result<Option<(String, Origin)>, String> {
+    let (cli, env) = string_option(matches, "output", "GODOTDOC_OUTPUT")?;
+    Ok(match (cli, env.filter(|x| !x.is_empty())) {
+        (Some(output), _) => Some((output, Origin::Cli)),
+        (None, Some(output)) => Some((output, Origin::Env)),
+        (None, None) => None,
+    })
+}
+
+/// Reads an option from the command line and from the environment
+fn string_option(
+    matches: &ArgMatches,
+    arg: &str,
+    env: &str,
+) -> Result<(Option<String>, Option<String>), String> {
+    Ok((matches.value_of(arg).map(str::to_string), env_var(env)?))
+}
+
+/// Reads a flag from the command line and a boolean from the environment
+fn flag_option(
+    matches: &ArgMatches,
+    arg: &str,
+    env: &str,
+) -> Result<(Option<bool>, Option<bool>), String> {
+    Ok((
+        Some(true).filter(|_| matches.is_present(arg)),
+        env_bool(env)?,
+    ))
+}
+
+/// Reads a numeric option from the command line and from the environment
+fn number_option<T: FromStr>(
+    matches: &ArgMatches,
+    arg: &str,
+    env: &str,
+    description: &str,
+) -> Result<(Option<T>, Option<T>), String>
+where
+    T::Err: Display,
+{
+    let parse = |value: Option<String>| {
+        value
+            .map(|value| {
+                value
+                    .parse::<T>()
+                    .map_err(|e| format!("Invalid {} '{}': {}", description, value, e))
+            })
+            .transpose()
+    };
+    let (cli, env) = string_option(matches, arg, env)?;
+    Ok((parse(cli)?, parse(env)?))
+}
+
+fn env_bool(name: &str) -> Result<Option<bool>, String> {
+    match env_var(name)? {
+        Some(value) => match value.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(Some(true)),
+            "0" | "false" | "no" | "off" => Ok(Some(false)),
+            _ => Err(format!("{}: expected a boolean, got '{}'", name, value)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Names of the config files, the first one found in a directory takes precedence
+const CONFIG_NAMES: [&str; 3] = [
+    "godotdoc.toml",
+    "godotdoc_config.toml",
+    "godotdoc_config.json",
+];
+
+/// Keys of the config files, any other key is reported as an error
+const CONFIG_KEYS: [&str; 26] = [
+    "backend",
+    "excluded_files",
+    "show_prefixed",
+    "private_section",
+    "callout_style",
+    "inherited_members",
+    "mark_overrides",
+    "show_dependencies",
+    "max_depth",
+    "follow_symlinks",
+    "resolve_constants",
+    "flat",
+    "split_classes",
+    "clean_output",
+    "default_excludes",
+    "heading_offset",
+    "signature_max_width",
+    "ignored_comment_prefixes",
+    "source_url_template",
+    "root_url",
+    "title",
+    "index",
+    "group_by",
+    "nav",
+    "since_filter",
+    "output_names",
+];
+
+/// Reads a config file, its format is detected by the extension
+fn read_config_file(path: &Path) -> Result<Configuration, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let with_path = |e: &dyn Display| format!("{}: {}", path.display(), e);
+    // The keys are checked first, so a typo is reported instead of being silently dropped
+    let value = match path.extension().and_then(OsStr::to_str) {
+        Some("toml") => toml::from_str::<toml::Value>(&content)
+            .map_err(|e| with_path(&e))
+            .map(|value| serde_json::to_value(value).unwrap_or_default())?,
+        Some("json") => serde_json::from_str(&content).map_err(|e| with_path(&e))?,
+        _ => {
+            return Err(with_path(
+                &"Unknown config format, expected a .toml or .json file",
+            ))
+        }
+    };
+    let errors = validate_config(&value);
+    if !errors.is_empty() {
+        let errors = errors.iter().map(|e| with_path(e)).collect::<Vec<_>>();
+        return Err(errors.join("\n"));
+    }
+
+    match path.extension().and_then(OsStr::to_str) {
+        Some("toml") => toml::from_str(&content).map_err(|e| with_path(&e)),
+        _ => serde_json::from_str(&content).map_err(|e| with_path(&e)),
+    }
+}
+
+/// Checks the keys of a config and the values, which would only fail later on, every error
+/// names the JSON pointer of the bad value
+fn validate_config(config: &serde_json::Value) -> Vec<String> {
+    let config = match config.as_object() {
+        Some(config) => config,
+        None => return vec!["Expected a table of settings".to_string()],
+    };
+
+    let mut errors = Vec::new();
+    for key in config.keys() {
+        if !CONFIG_KEYS.contains(&key.as_str()) {
+            let suggestion = match closest_name(key, &CONFIG_KEYS) {
+                Some(closest) => format!(", did you mean '{}'?", closest),
+                None => String::new(),
+            };
+            errors.push(format!(
+                "Unknown key '{}' at {}{}",
+                key,
+                json_pointer(&[key]),
+                suggestion
+            ));
+        }
+    }
+
+    for key in &["excluded_files", "ignored_comment_prefixes"] {
+        match config.get(*key) {
+            Some(serde_json::Value::Array(values)) => {
+                for (i, value) in values.iter().enumerate() {
+                    if !value.is_string() {
+                        errors.push(format!(
+                            "Expected a string at {}, got {}",
+                            json_pointer(&[key, &i.to_string()]),
+                            value
+                        ));
+                    }
+                }
+            }
+            Some(value) => errors.push(format!(
+                "Expected an array of strings at {}, got {}",
+                json_pointer(&[key]),
+                value
+            )),
+            None => (),
+        }
+    }
+
+    match config.get("backend") {
+        Some(serde_json::Value::String(names)) => {
+            for name in names.split(',').map(|x| x.trim()) {
+                if !is_backend_name(name) {
+                    errors.push(format!("{} at /backend", unknown_backend(name)));
+                }
+            }
+        }
+        Some(value) => errors.push(format!("Expected a string at /backend, got {}", value)),
+        None => (),
+    }
+    errors
+}
+
+/// A JSON pointer (RFC 6901) to a value in a config
+fn json_pointer(keys: &[&str]) -> String {
+    keys.iter()
+        .map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// Reads the config files in a directory, if there are any
+fn read_configuration(dir: &Path) -> Result<Option<Configuration>, String> {
+    let mut configs = Vec::new();
+    for name in CONFIG_NAMES.iter() {
+        let path = dir.join(name);
+        if path.is_file() {
+            let config = read_config_file(&path)?;
+            configs.push((path, config));
+        }
+    }
+
+    // The first file takes precedence, but silently picking one backend over
+    // the other would be confusing, so conflicting backends are an error
+    for (path, config) in configs.iter().skip(1) {
+        match (&configs[0].1.backend, &config.backend) {
+            (Some(first_backend), Some(backend)) if first_backend != backend => {
+                return Err(format!(
+                    "{} and {} define conflicting backends ('{}' and '{}')",
+                    configs[0].0.display(),
+                    path.display(),
+                    first_backend,
+                    backend
+                ))
+            }
+            _ => (),
+        }
+    }
+
+    Ok(configs.into_iter().next().map(|(_, config)| config))
+}
+
+/// Searches the config of a directory in it and all of its parents. Returns the config,
+/// together with the directory it was found in, relative to which its patterns are given.
+fn find_configuration(dir: &Path) -> Result<(Configuration, PathBuf), String> {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    for ancestor in dir.ancestors() {
+        if let Some(config) = read_configuration(ancestor)? {
+            return Ok((config, ancestor.to_path_buf()));
+        }
+    }
+    Ok((Configuration::default(), dir))
+}
+
+/// Makes a pattern of a config in an outer directory relative to the input directory `dir`,
+/// which is `relative_dir` below the config. Patterns pointing outside of the input are dropped.
+fn rebase_pattern(pattern: String, relative_dir: &str) -> Option<String> {
+    if relative_dir.is_empty() {
+        return Some(pattern);
+    }
+
+    let path = pattern.strip_prefix("./").unwrap_or(&pattern);
+    match path.strip_prefix(relative_dir) {
+        Some(rest) if rest.starts_with('/') => Some(format!(".{}", rest)),
+        // Patterns like `**/*.gd` or `*.gd` match in every directory
+        _ if path.starts_with('*') => Some(pattern),
+        _ => None,
+    }
+}
+
+/// The settings, which are passed to the backends
+struct BackendOptions {
+    show_dependencies: bool,
+    heading_offset: u32,
+    signature_max_width: usize,
+    source_url_template: Option<String>,
+    private_section: PrivateSection,
+    callout_style: CalloutStyle,
+    inherited_members: InheritedMembers,
+}
+
+/// A backend, which can be selected with `--backend`
+struct BackendInfo {
+    name: &'static str,
+    description: &'static str,
+    /// Settings, which change the output of the backend
+    options: &'static [&'static str],
+    create: fn(&BackendOptions) -> Box<dyn Backend>,
+}
+
+/// All backends, in the order they are generated for `--backend all`
+const BACKENDS: [BackendInfo; 2] = [
+    BackendInfo {
+        name: "markdown",
+        description: "A Markdown page for every script and an index linking them",
+        options: &[
+            "show_dependencies",
+            "heading_offset",
+            "signature_max_width",
+            "source_url_template",
+            "private_section",
+            "callout_style",
+            "inherited_members",
+            "nav",
+        ],
+        create: |options| {
+            Box::new(MarkdownBackend::new(
+                options.show_dependencies,
+                options.heading_offset,
+                options.signature_max_width,
+                options.source_url_template.clone(),
+                options.private_section,
+                options.callout_style,
+                options.inherited_members,
+            ))
+        },
+    },
+    BackendInfo {
+        name: "doxygen",
+        description: "A C++ header for every script with a class for Doxygen to index",
+        options: &[],
+        create: |_| Box::new(DoxygenBackend::new()),
+    },
+];
+
+fn get_backend(name: &str, options: &BackendOptions) -> Result<Box<dyn Backend>, BackendError> {
+    match BACKENDS.iter().find(|x| x.name == name) {
+        Some(backend) => Ok((backend.create)(options)),
+        None => Err(unknown_backend(name)),
+    }
+}
+
+/// Whether `name` is a backend or `all`, regardless of its case
+fn is_backend_name(name: &str) -> bool {
+    name.eq_ignore_ascii_case("all") || BACKENDS.iter().any(|x| x.name.eq_ignore_ascii_case(name))
+}
+
+fn unknown_backend(name: &str) -> BackendError {
+    let names = BACKENDS.iter().map(|x| x.name).collect::<Vec<_>>();
+    BackendError::UnsupportedBackend {
+        name: name.to_string(),
+        suggestion: closest_name(&name.to_lowercase(), &names).map(str::to_string),
+        available: names.into_iter().map(str::to_string).collect(),
+    }
+}
+
+/// The name with the fewest edits from `name`, if it's close enough to be a typo
+fn closest_name<'a>(name: &str, names: &[&'a str]) -> Option<&'a str> {
+    names
+        .iter()
+        .map(|x| (edit_distance(name, x), *x))
+        .filter(|(distance, x)| *distance <= 2.max(x.len() / 3))
+        .min()
+        .map(|(_, x)| x)
+}
+
+/// Number of inserted, removed or replaced characters, which turn a into b
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, x) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let replaced = previous + usize::from(x != *y);
+            previous = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Prints every backend with its extension, what it generates and the settings it uses
+fn list_backends(f: &mut dyn Write) -> std::io::Result<()> {
+    let options = BackendOptions {
+        show_dependencies: true,
+        heading_offset: 0,
+        signature_max_width: 100,
+        source_url_template: None,
+        private_section: PrivateSection::None,
+        callout_style: CalloutStyle::Plain,
+        inherited_members: InheritedMembers::None,
+    };
+    for backend in BACKENDS.iter() {
+        writeln!(
+            f,
+            "{:<10} .{:<4} {}",
+            backend.name,
+            (backend.create)(&options).get_extension(),
+            backend.description
+        )?;
+        if !backend.options.is_empty() {
+            writeln!(f, "{:16}Options: {}", "", backend.options.join(", "))?;
+        }
+    }
+    Ok(())
+}
+
+/// Creates every backend of a comma separated list, `all` selects every available backend
+fn get_backends(names: &str, options: &BackendOptions) -> Result<Vec<Box<dyn Backend>>, String> {
+    let mut selected: Vec<&str> = Vec::new();
+    for name in names.split(',').map(|x| x.trim()) {
+        let names = if name.eq_ignore_ascii_case("all") {
+            BACKENDS.iter().map(|x| x.name).collect()
+        } else {
+            // Backends are matched regardless of case, `Markdown` is `markdown`
+            let known = BACKENDS.iter().find(|x| x.name.eq_ignore_ascii_case(name));
+            vec![known.map_or(name, |x| x.name)]
+        };
+        for name in names {
+            if !selected.contains(&name) {
+                selected.push(name);
+            }
+        }
+    }
+
+    let backends = selected
+        .into_iter()
+        .map(|name| get_backend(name, options))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // Every backend writes next to the others, so they need distinct extensions
+    for (i, backend) in backends.iter().enumerate() {
+        if backends[..i]
+            .iter()
+            .any(|other| other.get_extension() == backend.get_extension())
+        {
+            return Err(format!(
+                "Multiple backends write files with the extension '{}'",
+                backend.get_extension()
+            ));
+        }
+    }
+
+    Ok(backends)
+}
+
+/// Why a file or directory is excluded, if it is
+fn excluded_by(path: &Path, settings: &Settings) -> Option<String> {
+    // Unlike in user patterns, '*' doesn't match '/' here, so '.*' only matches hidden names
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..match_options()
+    };
+    if let Some(pattern) = matching_pattern_with(path, &settings.default_excludes, options) {
+        return Some(format!(
+            "it matches the default exclude pattern '{}', pass --no-default-excludes to document it",
+            pattern
+        ));
+    }
+    matching_pattern(path, &settings.excluded_files)
+        .map(|pattern| format!("it matches the exclude pattern '{}'", pattern))
+}
+
+/// Why a script isn't documented according to the excludes and includes, if it isn't
+fn exclusion_reason(path: &Path, settings: &Settings) -> Option<String> {
+    if let Some(reason) = excluded_by(path, settings) {
+        return Some(reason);
+    }
+    if !settings.included_files.is_empty()
+        && matching_pattern(path, &settings.included_files).is_none()
+    {
+        return Some("it matches no include pattern".to_string());
+    }
+    None
+}
+
+fn is_included(path: &Path, settings: &Settings) -> bool {
+    exclusion_reason(path, settings).is_none()
+}
+
+/// The path of a dependency relative to the input directory, if it is a script, which is documented as well
+fn documented_dependency<'a>(path: &'a str, settings: &Settings) -> Option<&'a str> {
+    let relative_path = path.strip_prefix("res://").filter(|x| x.ends_with(".gd"))?;
+
+    let output_path = Path::new(".").join(relative_path);
+    let documented = settings.input_path.join(relative_path).is_file()
+        && settings
+            .input_prefixes
+            .iter()
+            .any(|prefix| output_path.starts_with(prefix))
+        && is_included(&output_path, settings);
+    Some(relative_path).filter(|_| documented)
+}
+
+/// Links every dependency, which refers to a script that is documented as well
+fn resolve_dependencies(
+    data: &mut DocumentationData,
+    output: &Path,
+    settings: &Settings,
+    extension: &str,
+) {
+    for dependency in data.dependencies.iter_mut() {
+        if let Some(relative_path) = documented_dependency(&dependency.path, settings) {
+            dependency.link = Some(script_link(relative_path, output, settings, extension));
+        }
+    }
+}
+
+/// Link to the page of a documented script from a page in `output`,
+/// `relative_path` is relative to the input directory
+fn script_link(relative_path: &str, output: &Path, settings: &Settings, extension: &str) -> String {
+    let depth = match settings.flat {
+        true => 0,
+        false => output
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .count(),
+    };
+    let path = Path::new(".").join(relative_path);
+    let name = output_name(
+        path.parent().unwrap(),
+        path.file_name().unwrap(),
+        settings,
+        extension,
+    );
+    let relative = format!("{}{}", "../".repeat(depth), web_path(&name));
+    page_link(&name, relative, settings)
+}
+
+/// Godot refuses to load a project, in which two scripts have the same `class_name`
+fn warn_duplicate_class_names(manifest: &Manifest) {
+    let mut scripts: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (key, entry) in manifest.entries() {
+        if let Some(class_name) = &entry.class_name {
+            scripts.entry(class_name).or_default().push(key);
+        }
+    }
+    for (class_name, scripts) in scripts.into_iter().filter(|x| x.1.len() > 1) {
+        print_warning(&format!(
+            "The class_name {} is declared by several scripts: {}",
+            class_name,
+            scripts.join(", ")
+        ));
+    }
+}
+
+fn warn_deprecated(data: &DocumentationData) {
+    for symbol in data.deprecated_symbols() {
+        let message = match symbol.deprecated.as_deref() {
+            Some("") | None => String::new(),
+            Some(message) => format!(": {}", message),
+        };
+        print_warning(&format!(
+            "{}, line {}: {} is deprecated{}",
+            data.source_path, symbol.line, symbol.name, message
+        ));
+    }
+}
+
+/// The link to the page at the path relative to the output directory, the relative link
+/// unless there is a root URL
+fn page_link(path: &Path, relative: String, settings: &Settings) -> String {
+    match &settings.root_url {
+        Some(root_url) => format!("{}{}", root_url, web_path(path).replace(' ', "%20")),
+        None => relative,
+    }
+}
+
+/// The path separated by '/', without any '.' or '..'
+fn web_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(x) => Some(x.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Whether every dependency is documented just like it was in the last run
+fn dependencies_unchanged(entry: &ManifestEntry, settings: &Settings) -> bool {
+    entry
+        .dependencies
+        .iter()
+        .all(|(path, documented)| documented_dependency(path, settings).is_some() == *documented)
+}
+
+/// Collects every script below src, together with the directory of its output
+fn collect_files(
+    src: PathBuf,
+    output: PathBuf,
+    depth: u32,
+    settings: &Settings,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<(), Error> {
+    // Keep track of the directories we've been to, so symbolic links can't send us in circles
+    let canonical_path = src
+        .canonicalize()
+        .map_err(|e| Error::Io(format!("Failed to resolve {}: {}", src.display(), e)))?;
+    if visited.contains(&canonical_path) {
+        print_warning(&format!(
+            "Skipping {}: it links back to {}, which was already documented",
+            src.display(),
+            canonical_path.display()
+        ));
+        return Ok(());
+    }
+    visited.insert(canonical_path);
+
+    // The order of read_dir depends on the platform, sort it to get reproducible results
+    let mut entries = std::fs::read_dir(&src)
+        .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| Error::Io(format!("Failed to read {}: {}", src.display(), e)))?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+
+        let new_output = output.join(entry.file_name());
+        if let Some(reason) = excluded_by(&new_output, settings) {
+            log::verbose(&format!("Skipping {}: {}", path.display(), reason));
+            continue;
+        }
+
+        let is_symlink = entry
+            .file_type()
+            .map_err(|e| Error::Io(format!("Failed to read {}: {}", path.display(), e)))?
+            .is_symlink();
+        if path.is_dir() {
+            if settings.canonical_output_path.is_some()
+                && path.canonicalize().ok() == settings.canonical_output_path
+            {
+                log::verbose(&format!(
+                    "Skipping {}: it is the output directory",
+                    path.display()
+                ));
+                continue;
+            }
+            if is_symlink && !settings.follow_symlinks {
+                log::verbose(&format!(
+                    "Skipping symbolic link to directory: {}",
+                    path.display()
+                ));
+                continue;
+            }
+
+            if settings
+                .max_depth
+                .map_or(true, |max_depth| depth < max_depth)
+            {
+                collect_files(path, new_output, depth + 1, settings, visited, files)?;
+            }
+        } else if path.is_file() && path.extension() == Some(OsStr::new("gd")) {
+            match exclusion_reason(&new_output, settings) {
+                Some(reason) => log::verbose(&format!("Skipping {}: {}", path.display(), reason)),
+                None => files.push((path, output.clone())),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Documents every script on the configured number of threads, the results are
+/// collected in the order of the files, so they don't depend on the scheduling
+fn document_files(
+    files: &[(PathBuf, PathBuf)],
+    settings: &Settings,
+    generated: &mut HashSet<PathBuf>,
+    manifest: &mut Manifest,
+    changes: &mut Vec<Change>,
+    failed: &mut Vec<(PathBuf, PathBuf)>,
+    todo: &mut Vec<TodoFile>,
+) -> Result<(), Vec<Error>> {
+    let pool = thread_pool(settings).map_err(|e| vec![e])?;
+    let previous: &Manifest = manifest;
+    let results: Vec<_> = if settings.fail_fast {
+        // Stops at the first error, the scripts, which are still running, are finished
+        pool.install(|| {
+            files
+                .par_iter()
+                .map(|(path, output)| document_file(path, output, settings, previous))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| vec![e])?
+        .into_iter()
+        .map(Ok)
+        .collect()
+    } else {
+        pool.install(|| {
+            files
+                .par_iter()
+                .map(|(path, output)| document_file(path, output, settings, previous))
+                .collect()
+        })
+    };
+
+    let mut errors = Vec::new();
+    for (result, file) in results.into_iter().zip(files) {
+        match result {
+            Ok(Some(Documented::Files(key, entry, mut file_changes))) => {
+                generated.extend(entry.outputs.iter().map(|x| settings.output_path.join(x)));
+                manifest.insert(key, entry);
+                changes.append(&mut file_changes);
+            }
+            Ok(Some(Documented::Ast(ast))) => println!("{}", ast),
+            Ok(Some(Documented::Todo(file))) => todo.push(file),
+            Ok(None) => (),
+            Err(e) => {
+                errors.push(e);
+                failed.push(file.clone());
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn thread_pool(settings: &Settings) -> Result<rayon::ThreadPool, Error> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(settings.jobs)
+        .build()
+        .map_err(|e| Error::from(format!("Failed to start threads: {}", e)))
+}
+
+/// Parses every script for the constants of its `class_name`, so enum values can refer to the
+/// constants of other scripts. Scripts, which can't be parsed, are reported when they are documented.
+fn collect_constants(
+    files: &[(PathBuf, PathBuf)],
+    settings: &Settings,
+) -> Result<BTreeMap<String, String>, Error> {
+    let constants = thread_pool(settings)?.install(|| {
+        files
+            .par_iter()
+            .filter(|(path, output)| {
+                exclusion_reason(&output.join(path.file_name().unwrap()), settings).is_none()
+            })
+            .filter_map(|(path, _)| {
+                let input = std::fs::read(path).ok()?;
+                let file_name = path.file_name().unwrap().to_string_lossy();
+                parse_source(&file_name, input.as_slice(), &settings.parse_options).ok()
+            })
+            .map(|data| data.qualified_constants())
+            .collect::<Vec<_>>()
+    });
+    Ok(constants.into_iter().flatten().collect())
+}
+
+/// Parses every script, so pages can show the members of the scripts they extend.
+/// Scripts, which can't be parsed, are reported when they are documented.
+fn collect_scripts(
+    files: &[(PathBuf, PathBuf)],
+    settings: &Settings,
+) -> Result<BTreeMap<String, DocumentationData>, Error> {
+    let scripts = thread_pool(settings)?.install(|| {
+        files
+            .par_iter()
+            .filter(|(path, output)| {
+                exclusion_reason(&output.join(path.file_name().unwrap()), settings).is_none()
+            })
+            .filter_map(|(path, output)| {
+                let input = std::fs::read(path).ok()?;
+                let file_name = path.file_name().unwrap().to_string_lossy();
+                let mut data =
+                    parse_source(&file_name, input.as_slice(), &settings.parse_options).ok()?;
+                data.source_path = web_path(&output.join(path.file_name().unwrap()));
+                if let Some(since_filter) = &settings.since_filter {
+                    data.filter_since(since_filter);
+                }
+                Some((format!("res://{}", data.source_path), data))
+            })
+            .collect::<Vec<_>>()
+    });
+    Ok(scripts.into_iter().collect())
+}
+
+/// Reads the autoloads of the `project.godot` in the project directory or above it.
+/// Autoloads, which aren't documented, are reported.
+fn collect_autoloads(
+    project_dir: &Path,
+    files: &[(PathBuf, PathBuf)],
+    settings: &Settings,
+) -> Result<BTreeMap<String, String>, Error> {
+    let Some(root) = project::find_project(project_dir) else {
+        return Ok(BTreeMap::new());
+    };
+    let path = root.join("project.godot");
+    let source = std::fs::read_to_string(&path)
+        .map_err(|e| Error::Io(format!("Failed to read {}, {}", path.display(), e)))?;
+    // `res://` paths are relative to the project, the documentation to the project directory
+    let relative_dir = project_dir
+        .canonicalize()
+        .ok()
+        .and_then(|dir| Some(web_path(dir.strip_prefix(&root).ok()?)))
+        .unwrap_or_default();
+
+    let documented = files
+        .iter()
+        .map(|(path, output)| output.join(path.file_name().unwrap()))
+        .filter(|path| exclusion_reason(path, settings).is_none())
+        .map(|path| web_path(&path))
+        .collect::<HashSet<_>>();
+    let mut autoloads = BTreeMap::new();
+    for (name, path) in project::parse_autoloads(&source) {
+        // Scenes aren't documented, only their scripts
+        if !path.ends_with(".gd") {
+            continue;
+        }
+        let relative_path = path
+            .strip_prefix("res://")
+            .and_then(|x| match relative_dir.as_str() {
+                "" => Some(x),
+                dir => x.strip_prefix(dir)?.strip_prefix('/'),
+            });
+        match relative_path.filter(|x| documented.contains(*x)) {
+            Some(relative_path) => {
+                autoloads.insert(format!("res://{}", relative_path), name);
+            }
+            None => print_warning(&format!(
+                "The autoload {} refers to {}, which isn't documented",
+                name, path
+            )),
+        }
+    }
+    Ok(autoloads)
+}
+
+/// The `res://` path of the documented script, which the script at `script` extends with `base`.
+/// `base` is either a `class_name` or a path, in quotes.
+fn resolve_base(
+    base: &str,
+    script: &str,
+    scripts: &BTreeMap<String, DocumentationData>,
+) -> Option<String> {
+    match base.strip_prefix('"').or_else(|| base.strip_prefix('\'')) {
+        Some(path) => Some(script_path(
+            path.trim_end_matches(['"', '\'']),
+            Some(script),
+        ))
+        .filter(|path| scripts.contains_key(path)),
+        None => scripts
+            .iter()
+            .find(|(_, data)| data.class_name.as_deref() == Some(base))
+            .map(|(path, _)| path.clone()),
+    }
+}
+
+/// Warns once about every base, which is neither a documented script nor an engine class
+fn warn_unknown_bases(scripts: &BTreeMap<String, DocumentationData>) {
+    let mut unknown = BTreeSet::new();
+    for (path, data) in scripts {
+        let Some(base) = &data.base else { continue };
+        // Other identifiers are engine classes, the chain ends there
+        let engine_class = base.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !engine_class && resolve_base(base, path, scripts).is_none() && unknown.insert(base) {
+            print_warning(&format!(
+                "Can't find the script {}, which {} extends, its members are unknown",
+                base, path
+            ));
+        }
+    }
+}
+
+/// The documented scripts, which the script extends, the nearest first
+fn ancestors<'a>(data: &DocumentationData, settings: &'a Settings) -> Vec<&'a DocumentationData> {
+    let mut script = format!("res://{}", data.source_path);
+    let mut base = data.base.clone();
+    let mut ancestors: Vec<&DocumentationData> = Vec::new();
+    while let Some(path) = base.and_then(|base| resolve_base(&base, &script, &settings.scripts)) {
+        let ancestor = &settings.scripts[&path];
+        // A script, which extends itself in a cycle, can't be loaded by Godot anyway
+        if ancestors
+            .iter()
+            .any(|x| x.source_path == ancestor.source_path)
+        {
+            break;
+        }
+        ancestors.push(ancestor);
+        base = ancestor.base.clone();
+        script = path;
+    }
+    ancestors
+}
+
+/// The functions of a script by their name
+fn functions(data: &DocumentationData) -> impl Iterator<Item = (&Symbol, &FunctionArgStruct)> {
+    data.entries
+        .iter()
+        .flat_map(|entry| entry.symbols.iter())
+        .filter_map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::FunctionArgs(function)) => Some((symbol, function)),
+            _ => None,
+        })
+}
+
+/// The members, which the script inherits from the documented scripts it extends, the nearest first.
+/// Members, which the script or a nearer script declares again, are overridden, so they are left out.
+fn inherited_members(
+    data: &DocumentationData,
+    output: &Path,
+    settings: &Settings,
+    extension: &str,
+) -> Vec<InheritedClass> {
+    let mut declared = data
+        .entries
+        .iter()
+        .flat_map(|entry| entry.symbols.iter().map(|symbol| symbol.name.clone()))
+        .collect::<HashSet<_>>();
+    let mut inherited = Vec::new();
+    for ancestor in ancestors(data, settings) {
+        let entries = ancestor
+            .entries
+            .iter()
+            .map(|entry| DocumentationEntry {
+                entry_type: entry.entry_type.clone(),
+                symbols: entry
+                    .symbols
+                    .iter()
+                    .filter(|symbol| !declared.contains(&symbol.name))
+                    .cloned()
+                    .collect(),
+            })
+            .filter(|entry| !entry.symbols.is_empty())
+            .collect::<Vec<_>>();
+        declared.extend(
+            ancestor
+                .entries
+                .iter()
+                .flat_map(|entry| entry.symbols.iter().map(|symbol| symbol.name.clone())),
+        );
+        if !entries.is_empty() {
+            inherited.push(InheritedClass {
+                title: ancestor.title().to_string(),
+                link: Some(script_link(
+                    &ancestor.source_path,
+                    output,
+                    settings,
+                    extension,
+                )),
+                source_path: ancestor.source_path.clone(),
+                entries,
+            });
+        }
+    }
+    inherited
+}
+
+/// Virtual methods of the engine, which scripts implement to be called by it
+const ENGINE_VIRTUALS: [&str; 28] = [
+    "_init",
+    "_static_init",
+    "_ready",
+    "_enter_tree",
+    "_exit_tree",
+    "_process",
+    "_physics_process",
+    "_input",
+    "_unhandled_input",
+    "_unhandled_key_input",
+    "_shortcut_input",
+    "_gui_input",
+    "_notification",
+    "_draw",
+    "_get",
+    "_set",
+    "_get_property_list",
+    "_validate_property",
+    "_property_can_revert",
+    "_property_get_revert",
+    "_to_string",
+    "_integrate_forces",
+    "_get_configuration_warning",
+    "_get_configuration_warnings",
+    "_can_drop_data",
+    "_drop_data",
+    "_get_drag_data",
+    "_make_custom_tooltip",
+];
+
+/// Marks the functions of the script, which override a function of a documented script it extends
+/// or a virtual method of the engine
+fn mark_overrides(
+    data: &mut DocumentationData,
+    output: &Path,
+    settings: &Settings,
+    extension: &str,
+) {
+    let ancestors = ancestors(data, settings);
+    for symbol in data
+        .entries
+        .iter_mut()
+        .flat_map(|entry| entry.symbols.iter_mut())
+    {
+        let Symbol {
+            name,
+            arg: Some(SymbolArgs::FunctionArgs(function)),
+            ..
+        } = symbol
+        else {
+            continue;
+        };
+        let ancestor = ancestors
+            .iter()
+            .find(|ancestor| functions(ancestor).any(|(x, _)| x.name == *name));
+        function.overrides = match ancestor {
+            Some(ancestor) => Some(Override::Script {
+                title: ancestor.title().to_string(),
+                link: Some(script_link(
+                    &ancestor.source_path,
+                    output,
+                    settings,
+                    extension,
+                )),
+            }),
+            None if ENGINE_VIRTUALS.contains(&name.as_str()) => Some(Override::Engine),
+            None => None,
+        };
+    }
+}
+
+/// Warns about every function, which overrides a function of a documented script with another
+/// number of arguments
+fn warn_mismatched_overrides(settings: &Settings) {
+    for data in settings.scripts.values() {
+        let ancestors = ancestors(data, settings);
+        for (symbol, function) in functions(data) {
+            let overridden = ancestors.iter().find_map(|ancestor| {
+                functions(ancestor)
+                    .find(|(x, _)| x.name == symbol.name)
+                    .map(|(_, x)| (ancestor, x))
+            });
+            if let Some((ancestor, overridden)) = overridden {
+                if overridden.arguments.len() != function.arguments.len() {
+                    print_warning(&format!(
+                        "{}:{}: {} takes {} arguments, but the function it overrides in {} takes {}",
+                        data.source_path,
+                        symbol.line,
+                        symbol.name,
+                        function.arguments.len(),
+                        ancestor.title(),
+                        overridden.arguments.len()
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// The result of documenting a single script
+enum Documented {
+    /// The key and the entry of the script in the manifest and, with --check,
+    /// the outputs, which differ from the files on disk
+    Files(String, ManifestEntry, Vec<Change>),
+    /// The parsed documentation, if `--print-ast` is given
+    Ast(String),
+    /// The symbols without a description, if `--todo` is given
+    Todo(TodoFile),
+}
+
+/// The symbols of a script, which have no description, for `--todo`
+#[derive(Serialize)]
+struct TodoFile {
+    file: String,
+    symbols: Vec<TodoSymbol>,
+}
+
+#[derive(Serialize)]
+struct TodoSymbol {
+    #[serde(rename = "type")]
+    entry_type: EntryType,
+    name: String,
+    line: u32,
+}
+
+impl TodoFile {
+    /// Symbols, which are hidden by `show_prefixed`, aren't documented, so they aren't listed
+    fn new(data: &DocumentationData) -> TodoFile {
+        let mut symbols = data
+            .symbols()
+            .into_iter()
+            .filter(|(_, symbol)| symbol.text.is_empty())
+            .map(|(entry_type, symbol)| TodoSymbol {
+                entry_type: entry_type.clone(),
+                name: symbol.name.clone(),
+                line: symbol.line,
+            })
+            .collect::<Vec<_>>();
+        // Grouped by their type, in the order of the page
+        let mut types = Vec::new();
+        for symbol in symbols.iter() {
+            if !types.contains(&symbol.entry_type) {
+                types.push(symbol.entry_type.clone());
+            }
+        }
+        symbols.sort_by_key(|x| (types.iter().position(|t| *t == x.entry_type), x.line));
+        TodoFile {
+            file: data.source_path.clone(),
+            symbols,
+        }
+    }
+}
+
+/// Prints the symbols without a description of every script, which has any, and their total
+fn print_todo(files: &[TodoFile], json: bool) {
+    let total = files.iter().map(|x| x.symbols.len()).sum::<usize>();
+    let undocumented = files.iter().filter(|x| !x.symbols.is_empty());
+    if json {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            files: Vec<&'a TodoFile>,
+            total: usize,
+        }
+        let report = Report {
+            files: undocumented.collect(),
+            total,
+        };
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return;
+    }
+
+    for file in undocumented {
+        println!("{}", file.file);
+        let mut last = None;
+        for symbol in file.symbols.iter() {
+            if last != Some(&symbol.entry_type) {
+                println!("  {}:", symbol.entry_type);
+                last = Some(&symbol.entry_type);
+            }
+            println!("    {} (line {})", symbol.name, symbol.line);
+        }
+    }
+    println!(
+        "{} symbols without a description in {} of {} scripts",
+        total,
+        files.iter().filter(|x| !x.symbols.is_empty()).count(),
+        files.len()
+    );
+}
+
+/// A difference between the documentation and the output directory, found by --check
+enum Change {
+    /// The path and the contents of a file, which doesn't exist
+    Missing(PathBuf, Vec<u8>),
+    /// The path, the existing and the generated contents of a file
+    Changed(PathBuf, Vec<u8>, Vec<u8>),
+    /// A file with a backend's extension, which wasn't generated
+    Stale(PathBuf),
+}
+
+impl Change {
+    /// Prints what a run would do to the file, the output directory isn't touched by --dry-run
+    fn report_dry_run(&self) {
+        match self {
+            Change::Missing(path, content) => {
+                println!("Would create {} ({} bytes)", path.display(), content.len())
+            }
+            Change::Changed(path, _, content) => {
+                println!(
+                    "Would overwrite {} ({} bytes)",
+                    path.display(),
+                    content.len()
+                )
+            }
+            Change::Stale(path) => println!("Would remove {}", path.display()),
+        }
+    }
+
+    fn report(&self, diff: bool) {
+        let (message, path) = match self {
+            Change::Missing(path, _) => ("Missing", path),
+            Change::Changed(path, _, _) => ("Would change", path),
+            Change::Stale(path) => ("Stale extra file", path),
+        };
+        println!("{} {}", message, path.display());
+        if !diff {
+            return;
+        }
+
+        let (old, new) = match self {
+            Change::Missing(_, new) => (&[][..], &new[..]),
+            Change::Changed(_, old, new) => (&old[..], &new[..]),
+            Change::Stale(_) => return,
+        };
+        let name = path.display().to_string();
+        print!(
+            "{}",
+            diff::unified_diff(
+                &String::from_utf8_lossy(old),
+                &String::from_utf8_lossy(new),
+                &name,
+                &name
+            )
+        );
+    }
+}
+
+/// Writes a generated file, unless it didn't change, or, with --check, compares it with the existing one
+fn write_output(
+    path: &Path,
+    content: Vec<u8>,
+    settings: &Settings,
+) -> Result<Option<Change>, Error> {
+    if settings.check {
+        return Ok(match std::fs::read(path) {
+            Ok(old) if old == content => None,
+            Ok(old) => Some(Change::Changed(path.to_path_buf(), old, content)),
+            Err(_) => Some(Change::Missing(path.to_path_buf(), content)),
+        });
+    }
+
+    // Rewriting identical files would make site generators and file watchers rebuild everything
+    if std::fs::read(path).is_ok_and(|old| old == content) {
+        log::verbose(&format!("Unchanged {}", path.display()));
+        return Ok(None);
+    }
+
+    // Other threads may create the same directory, create_dir_all doesn't fail if they win
+    std::fs::create_dir_all(path.parent().unwrap())
+        .and_then(|()| std::fs::write(path, content))
+        .map_err(|e| {
+            Error::Io(format!(
+                "Failed to write output file: {}, {}",
+                path.display(),
+                e
+            ))
+        })?;
+    log::verbose(&format!("Wrote {}", path.display()));
+    Ok(None)
+}
+
+/// Parses a single script and writes its documentation into the output directory
+fn document_file(
+    path: &Path,
+    output: &Path,
+    settings: &Settings,
+    manifest: &Manifest,
+) -> Result<Option<Documented>, Error> {
+    // Names, which aren't valid UTF-8, are only used for display and the output file name
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Not a file: {}", path.display()))?
+        .to_string_lossy();
+    if let Some(reason) = exclusion_reason(&output.join(path.file_name().unwrap()), settings) {
+        log::verbose(&format!("Skipping {}: {}", path.display(), reason));
+        return Ok(None);
+    }
+
+    let input = std::fs::read(path).map_err(|e| {
+        Error::Io(format!(
+            "Failed to open input file: {}, {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    // Scripts, which didn't change since the last run, don't need to be parsed again.
+    // --check compares every file, the outputs might have been edited since
+    let key = output.join(path.file_name().unwrap()).display().to_string();
+    let hash = manifest::hash(&input);
+    if !settings.force && !settings.prints_report() && !settings.check && !settings.warn_deprecated
+    {
+        if let Some(entry) = manifest.unchanged(&key, &hash) {
+            let outputs = entry
+                .outputs
+                .iter()
+                .map(|x| settings.output_path.join(x))
+                .collect::<Vec<_>>();
+            if dependencies_unchanged(entry, settings) && outputs.iter().all(|x| x.is_file()) {
+                log::verbose(&format!("Skipping {}: it is up to date", path.display()));
+                return Ok(Some(Documented::Files(key, entry.clone(), Vec::new())));
+            }
+        }
+    }
+
+    log::verbose(&format!("Documenting {}", path.display()));
+    let start = Instant::now();
+    let mut data =
+        parse_source(&file_name, input.as_slice(), &settings.parse_options).map_err(|mut e| {
+            e.file = path.display().to_string();
+            e
+        })?;
+    data.source_path = web_path(&output.join(path.file_name().unwrap()));
+    data.autoload = settings
+        .autoloads
+        .get(&format!("res://{}", data.source_path))
+        .cloned();
+    if settings.warn_deprecated {
+        warn_deprecated(&data);
+    }
+    if let Some(since_filter) = &settings.since_filter {
+        data.filter_since(since_filter);
+    }
+
+    if settings.todo {
+        return Ok(Some(Documented::Todo(TodoFile::new(&data))));
+    }
+    if settings.print_ast {
+        resolve_dependencies(
+            &mut data,
+            output,
+            settings,
+            &settings.backends[0].get_extension(),
+        );
+        return Ok(Some(Documented::Ast(format!("{:#?}", data))));
+    }
+
+    // The file is parsed only once, every backend gets its own copy of the result
+    let mut outputs = Vec::new();
+    let mut changes = Vec::new();
+    for backend in settings.backends.iter() {
+        let extension = backend.get_extension();
+        let mut data = data.clone();
+        resolve_dependencies(&mut data, output, settings, &extension);
+        if settings.inherited_members != InheritedMembers::None {
+            data.inherited = inherited_members(&data, output, settings, &extension);
+        }
+        if settings.mark_overrides {
+            mark_overrides(&mut data, output, settings, &extension);
+        }
+
+        let relative_output = output_name(output, path.file_name().unwrap(), settings, &extension);
+        let mut pages = match settings.split_classes {
+            true => split_classes(&mut data, &relative_output, output, settings, &extension),
+            false => Vec::new(),
+        };
+        pages.insert(0, (relative_output, data));
+
+        for (relative_output, data) in pages {
+            let output_path = settings.output_path.join(&relative_output);
+            outputs.push(relative_output);
+
+            let mut content = Vec::new();
+            backend
+                .generate_output(data, &mut content)
+                .map_err(|e| e.to_string())?;
+            changes.extend(write_output(&output_path, content, settings)?);
+        }
+    }
+    log::verbose(&format!(
+        "Documented {} in {:.1?}",
+        path.display(),
+        start.elapsed()
+    ));
+
+    let dependencies = data
+        .dependencies
+        .iter()
+        .filter(|x| x.path.ends_with(".gd"))
+        .map(|x| {
+            let documented = documented_dependency(&x.path, settings).is_some();
+            (x.path.clone(), documented)
+        })
+        .collect();
+    Ok(Some(Documented::Files(
+        key,
+        ManifestEntry {
+            hash,
+            dependencies,
+            outputs,
+            title: data.title().to_string(),
+            class_name: data.class_name.clone(),
+            base: data.base.clone(),
+            summary: data.summary().map(str::to_string),
+        },
+        changes,
+    )))
+}
+
+/// Moves the inner classes of the script onto pages of their own, named like `player_Inventory.gd`
+/// would be. The pages are next to the page of the script, which links to them.
+fn split_classes(
+    data: &mut DocumentationData,
+    relative_output: &Path,
+    output: &Path,
+    settings: &Settings,
+    extension: &str,
+) -> Vec<(PathBuf, DocumentationData)> {
+    let parent = PageLink {
+        title: data.title().to_string(),
+        link: page_link(relative_output, link_name(relative_output), settings),
+    };
+
+    let mut pages = Vec::new();
+    for entry in data.entries.iter_mut() {
+        for symbol in entry.symbols.iter_mut() {
+            if let Some(SymbolArgs::ClassArgs(class)) = &mut symbol.arg {
+                let file_name = class_file_name(&data.source_file, &symbol.name);
+                let page_output = output_name(output, OsStr::new(&file_name), settings, extension);
+                class.link = Some(page_link(&page_output, link_name(&page_output), settings));
+
+                let page = DocumentationData {
+                    source_file: data.source_file.clone(),
+                    source_path: data.source_path.clone(),
+                    class_name: Some(symbol.name.clone()),
+                    base: class.base.clone(),
+                    text: symbol.text.clone(),
+                    entries: std::mem::take(&mut class.entries),
+                    dependencies: Vec::new(),
+                    end_line: symbol.end_line,
+                    parent: Some(parent.clone()),
+                    inherited: Vec::new(),
+                    autoload: None,
+                };
+                pages.push((page_output, page));
+            }
+        }
+    }
+    pages
+}
+
+/// The file name of a page, as a link from a page in the same directory
+fn link_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap()
+        .to_string_lossy()
+        .replace(' ', "%20")
+}
+
+/// Lists the pages of a backend in the order of their scripts. Scripts, which failed,
+/// are listed as well, so they don't go missing silently
+fn collect_pages(
+    settings: &Settings,
+    manifest: &Manifest,
+    failed: &[(PathBuf, PathBuf)],
+    extension: &str,
+) -> Vec<IndexPage> {
+    let mut pages = manifest
+        .entries()
+        .flat_map(|(key, entry)| {
+            // Pages of inner classes are linked from the page of their script
+            let key_path = Path::new(key);
+            let page = output_name(
+                key_path.parent().unwrap(),
+                key_path.file_name().unwrap(),
+                settings,
+                extension,
+            );
+            entry
+                .outputs
+                .iter()
+                .filter(move |x| **x == page)
+                .map(move |output| {
+                    let page = IndexPage {
+                        path: web_path(output),
+                        title: entry.title.clone(),
+                        class_name: entry.class_name.clone(),
+                        base: entry.base.clone(),
+                        summary: entry.summary.clone(),
+                        script: Some(format!("res://{}", web_path(key_path))),
+                        autoload: settings
+                            .autoloads
+                            .get(&format!("res://{}", web_path(key_path)))
+                            .cloned(),
+                        failed: false,
+                        group: None,
+                    };
+                    (key.clone(), page)
+                })
+        })
+        .chain(failed.iter().map(|(path, output)| {
+            let file_name = path.file_name().unwrap();
+            let page = IndexPage {
+                path: web_path(&output_name(output, file_name, settings, extension)),
+                title: file_name.to_string_lossy().into_owned(),
+                failed: true,
+                ..IndexPage::default()
+            };
+            (output.join(file_name).display().to_string(), page)
+        }))
+        .collect::<Vec<_>>();
+    pages.sort_by(|a, b| a.0.cmp(&b.0));
+    pages.into_iter().map(|(_, page)| page).collect()
+}
+
+/// Writes the index page and the inheritance page of every backend, which has them,
+/// into the root of the output directory
+fn write_indices(
+    title: &str,
+    settings: &Settings,
+    manifest: &mut Manifest,
+    failed: &[(PathBuf, PathBuf)],
+    generated: &mut HashSet<PathBuf>,
+) -> Result<Vec<Change>, Error> {
+    let mut changes = Vec::new();
+    for backend in settings.backends.iter() {
+        let extension = backend.get_extension();
+        let mut pages = collect_pages(settings, manifest, failed, &extension);
+        group_pages(&mut pages, settings.group_by);
+        for page in pages.iter_mut() {
+            page.path = page_link(Path::new(&page.path), page.path.clone(), settings);
+        }
+
+        let mut index = Vec::new();
+        let has_index = backend
+            .generate_index(title, &pages, &mut index)
+            .map_err(|e| e.to_string())?;
+        if has_index {
+            let name = PathBuf::from(format!("index.{}", extension));
+            let path = settings.output_path.join(&name);
+            changes.extend(write_output(&path, index, settings)?);
+            generated.insert(path);
+            manifest.insert_output(name);
+        }
+
+        let tree = inheritance_tree(&pages);
+        let mut inheritance = Vec::new();
+        let has_inheritance = backend
+            .generate_inheritance(title, &tree, &mut inheritance)
+            .map_err(|e| e.to_string())?;
+        if has_inheritance {
+            for cycle in tree.cycles.iter() {
+                let titles = cycle
+                    .iter()
+                    .chain(cycle.first())
+                    .map(|x| x.page.title.as_str());
+                log::warning(&format!(
+                    "Scripts extend each other in a cycle: {}",
+                    titles.collect::<Vec<_>>().join(" extends ")
+                ));
+            }
+            let name = PathBuf::from(format!("inheritance.{}", extension));
+            let path = settings.output_path.join(&name);
+            changes.extend(write_output(&path, inheritance, settings)?);
+            generated.insert(path);
+            manifest.insert_output(name);
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Writes the navigation of the documentation host into the root of the output directory,
+/// it links the Markdown pages
+fn write_nav(
+    nav: Nav,
+    settings: &Settings,
+    manifest: &mut Manifest,
+    failed: &[(PathBuf, PathBuf)],
+    generated: &mut HashSet<PathBuf>,
+) -> Result<Vec<Change>, Error> {
+    let pages = collect_pages(settings, manifest, failed, "md");
+    let index = settings
+        .title
+        .as_deref()
+        .filter(|_| generated.contains(&settings.output_path.join("index.md")))
+        .map(|title| (title, "index.md"));
+    let content = nav.generate(index, &pages);
+
+    let name = PathBuf::from(nav.file_name());
+    let path = settings.output_path.join(&name);
+    let changes = write_output(&path, content.into_bytes(), settings)?;
+    generated.insert(path);
+    manifest.insert_output(name);
+    Ok(changes.into_iter().collect())
+}
+
+/// Lists the files generated by the last run, which weren't generated again,
+/// relative to the output directory
+fn stale_files(
+    settings: &Settings,
+    manifest: &Manifest,
+    generated: &HashSet<PathBuf>,
+) -> Vec<PathBuf> {
+    manifest
+        .previous_outputs()
+        // A manifest, which was edited by hand, can't point outside of the output directory
+        .filter(|x| x.components().all(|c| matches!(c, Component::Normal(_))))
+        .filter(|x| {
+            let path = settings.output_path.join(x);
+            !generated.contains(&path) && path.is_file()
+        })
+        .cloned()
+        .collect()
+}
+
+/// Removes the stale files and all directories, which are empty afterwards.
+/// Files, which weren't generated by GodotDoc, are never touched.
+fn clean_output(settings: &Settings, stale: &[PathBuf]) -> Result<(), Error> {
+    for relative_path in stale {
+        let path = settings.output_path.join(relative_path);
+        std::fs::remove_file(&path)
+            .map_err(|e| Error::Io(format!("Failed to remove {}: {}", path.display(), e)))?;
+        log::verbose(&format!("Removed {}", path.display()));
+
+        let mut dir = relative_path.parent();
+        while let Some(relative_dir) = dir.filter(|x| !x.as_os_str().is_empty()) {
+            let dir_path = settings.output_path.join(relative_dir);
+            let empty = std::fs::read_dir(&dir_path)
+                .map(|mut entries| entries.next().is_none())
+                .unwrap_or(false);
+            if !empty {
+                break;
+            }
+            std::fs::remove_dir(&dir_path).map_err(|e| {
+                Error::Io(format!("Failed to remove {}: {}", dir_path.display(), e))
+            })?;
+            log::verbose(&format!("Removed {}", dir_path.display()));
+            dir = relative_dir.parent();
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Name of the manifest in the output directory
+pub const MANIFEST_NAME: &str = ".godotdoc-manifest.json";
+
+/// Records the state of every documented script, so unchanged scripts can be skipped
+#[derive(Default, Serialize, Deserialize)]
+pub struct Manifest {
+    version: String,
+    settings: String,
+    files: BTreeMap<String, ManifestEntry>,
+    /// Generated files, which don't belong to a single script like the index,
+    /// and stale files, which weren't removed yet
+    #[serde(default)]
+    other_outputs: BTreeSet<PathBuf>,
+
+    /// The entries of the last run, only those which are still valid
+    #[serde(skip)]
+    previous: BTreeMap<String, ManifestEntry>,
+    /// Every file generated by the last run, whatever its version and settings
+    #[serde(skip)]
+    previous_outputs: BTreeSet<PathBuf>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub hash: String,
+    /// Every script this script depends on, with whether it was documented,
+    /// because that decides whether the dependency is linked
+    pub dependencies: Vec<(String, bool)>,
+    pub outputs: Vec<PathBuf>,
+    /// Title of the script's page, so the index doesn't need to parse skipped scripts
+    pub title: String,
+    #[serde(default)]
+    pub class_name: Option<String>,
+    #[serde(default)]
+    pub base: Option<String>,
+    /// First line of the script's comments, for the index
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+impl Manifest {
+    /// Creates a manifest for this run, entries of the last run are only kept,
+    /// if it was generated by the same version with the same settings
+    pub fn new(output_path: &Path, settings: String) -> Manifest {
+        let version = env!("CARGO_PKG_VERSION").to_string();
+        let last = std::fs::read_to_string(output_path.join(MANIFEST_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Manifest>(&content).ok())
+            .unwrap_or_default();
+        let previous_outputs = last
+            .files
+            .values()
+            .flat_map(|entry| entry.outputs.iter().cloned())
+            .chain(last.other_outputs)
+            .collect();
+        let previous = if last.version == version && last.settings == settings {
+            last.files
+        } else {
+            BTreeMap::new()
+        };
+
+        Manifest {
+            version,
+            settings,
+            files: BTreeMap::new(),
+            other_outputs: BTreeSet::new(),
+            previous,
+            previous_outputs,
+        }
+    }
+
+    /// The entry of the last run, if the script wasn't changed since then
+    pub fn unchanged(&self, key: &str, hash: &str) -> Option<&ManifestEntry> {
+        self.previous.get(key).filter(|entry| entry.hash == hash)
+    }
+
+    pub fn insert(&mut self, key: String, entry: ManifestEntry) {
+        self.files.insert(key, entry);
+    }
+
+    /// Records a file, which doesn't belong to a single script, relative to the output directory
+    pub fn insert_output(&mut self, path: PathBuf) {
+        self.other_outputs.insert(path);
+    }
+
+    /// Every file generated by the last run, relative to the output directory
+    pub fn previous_outputs(&self) -> impl Iterator<Item = &PathBuf> {
+        self.previous_outputs.iter()
+    }
+
+    /// Every script documented in this run with its key, sorted by its path
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &ManifestEntry)> {
+        self.files.iter()
+    }
+
+    /// Writes the manifest, scripts which weren't documented in this run are dropped
+    pub fn write(&self, output_path: &Path) -> Result<(), String> {
+        let path = output_path.join(MANIFEST_NAME);
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if std::fs::read_to_string(&path).is_ok_and(|old| old == content) {
+            return Ok(());
+        }
+        std::fs::create_dir_all(output_path).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Hashes the content of a script with 64 bit FNV-1a, which is stable across platforms and versions
+pub fn hash(content: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
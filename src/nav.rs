@@ -0,0 +1,104 @@
+//! Navigation files of documentation hosts, which list every generated page
+
+use godotdoc::backend::markdownbackend::sanitize_markdown;
+use godotdoc::backend::IndexPage;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Nav {
+    /// `_sidebar.md` for docsify
+    Docsify,
+    /// A `nav:` block to include into `mkdocs.yml`
+    Mkdocs,
+}
+
+impl Nav {
+    pub fn from_name(name: &str) -> Result<Nav, String> {
+        match name {
+            "docsify" => Ok(Nav::Docsify),
+            "mkdocs" => Ok(Nav::Mkdocs),
+            _ => Err(format!(
+                "Unknown navigation '{}', expected docsify or mkdocs",
+                name
+            )),
+        }
+    }
+
+    /// Name of the file in the root of the output directory
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Nav::Docsify => "_sidebar.md",
+            Nav::Mkdocs => "mkdocs_nav.yml",
+        }
+    }
+
+    /// Lists the index first, if there is one, followed by the pages in the tree of their directories.
+    /// Pages of scripts, which failed, don't exist and are left out.
+    pub fn generate(self, index: Option<(&str, &str)>, pages: &[IndexPage]) -> String {
+        let mut pages = pages.iter().filter(|x| !x.failed).collect::<Vec<_>>();
+        pages.sort_by(|a, b| a.path.split('/').cmp(b.path.split('/')));
+
+        let mut result = match self {
+            Nav::Docsify => String::new(),
+            Nav::Mkdocs => "nav:\n".to_string(),
+        };
+        if let Some((title, path)) = index {
+            result += &self.entry(0, title, Some(path));
+        }
+
+        let mut open_directories: Vec<&str> = Vec::new();
+        for page in pages {
+            let directories = page.path.split('/').collect::<Vec<_>>();
+            let directories = &directories[..directories.len() - 1];
+
+            let common = open_directories
+                .iter()
+                .zip(directories.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            open_directories.truncate(common);
+            for directory in &directories[common..] {
+                result += &self.entry(open_directories.len(), directory, None);
+                open_directories.push(directory);
+            }
+            result += &self.entry(open_directories.len(), &page.title, Some(&page.path));
+        }
+
+        result
+    }
+
+    /// A single line, directories have no path
+    fn entry(self, depth: usize, title: &str, path: Option<&str>) -> String {
+        match self {
+            Nav::Docsify => {
+                let indentation = "  ".repeat(depth);
+                match path {
+                    Some(path) => format!(
+                        "{}* [{}]({})\n",
+                        indentation,
+                        sanitize_markdown(title.to_string()),
+                        path.replace(' ', "%20")
+                    ),
+                    None => format!(
+                        "{}* {}\n",
+                        indentation,
+                        sanitize_markdown(title.to_string())
+                    ),
+                }
+            }
+            Nav::Mkdocs => {
+                // JSON strings are valid YAML and escape everything, which YAML would interpret
+                let indentation = "  ".repeat(depth + 1);
+                let title = serde_json::to_string(title).unwrap();
+                match path {
+                    Some(path) => format!(
+                        "{}- {}: {}\n",
+                        indentation,
+                        title,
+                        serde_json::to_string(path).unwrap()
+                    ),
+                    None => format!("{}- {}:\n", indentation, title),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! How the documentation of a script is named after the script
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutputNames {
+    /// `player.gd.md`
+    Keep,
+    /// `player.md`
+    StripExtension,
+    /// `player-controller.md` for `Player_Controller.gd`
+    Slug,
+    /// A file name with the placeholders `{name}`, `{stem}`, `{slug}` and `{ext}`
+    Template(String),
+}
+
+impl OutputNames {
+    /// Names with a placeholder are templates, others must be one of the mappings
+    pub fn from_name(name: &str) -> Result<OutputNames, String> {
+        match name {
+            "keep" => Ok(OutputNames::Keep),
+            "strip_extension" => Ok(OutputNames::StripExtension),
+            "slug" => Ok(OutputNames::Slug),
+            _ if name.contains('{') => {
+                if name.contains('/') || name.contains('\\') {
+                    return Err(format!(
+                        "The output name template '{}' must not contain a path separator",
+                        name
+                    ));
+                }
+                Ok(OutputNames::Template(name.to_string()))
+            }
+            _ => Err(format!(
+                "Unknown output names '{}', expected keep, strip_extension, slug or a template like '{{stem}}.{{ext}}'",
+                name
+            )),
+        }
+    }
+
+    /// The file name of the documentation of the script with the extension of the backend
+    pub fn apply(&self, file_name: &str, extension: &str) -> String {
+        let stem = file_name.strip_suffix(".gd").unwrap_or(file_name);
+        match self {
+            OutputNames::Keep => format!("{}.{}", file_name, extension),
+            OutputNames::StripExtension => format!("{}.{}", stem, extension),
+            OutputNames::Slug => format!("{}.{}", slug(stem), extension),
+            OutputNames::Template(template) => template
+                .replace("{name}", file_name)
+                .replace("{stem}", stem)
+                .replace("{slug}", &slug(stem))
+                .replace("{ext}", extension),
+        }
+    }
+}
+
+/// Lowercase with hyphens instead of underscores and spaces
+fn slug(stem: &str) -> String {
+    stem.to_lowercase().replace(['_', ' '], "-")
+}
@@ -1,13 +1,76 @@
-use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
-use std::io::Lines;
 use std::io::Read;
 
 use std::fmt::{Display, Formatter};
 
-use crate::Settings;
+use serde::{Deserialize, Serialize};
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+/// What went wrong, so library users can react to errors without matching on the message
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseErrorKind {
+    /// A statement, which doesn't follow the GDScript grammar
+    Syntax,
+    /// A closing bracket, which doesn't match the open one
+    MismatchedParentheses,
+    /// The file ended inside of a statement or a string
+    UnexpectedEof,
+    /// Invalid UTF-8 with `strict_encoding`
+    Encoding,
+    /// The source couldn't be read
+    Io,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub file: String,
+    pub line: u32,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Creates a syntax error, other kinds are set with `with_kind`
+    pub fn new(file: &str, line: u32, message: impl Into<String>) -> ParseError {
+        ParseError {
+            kind: ParseErrorKind::Syntax,
+            file: file.to_string(),
+            line,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn with_kind(mut self, kind: ParseErrorKind) -> ParseError {
+        self.kind = kind;
+        self
+    }
+
+    fn with_column(mut self, column: usize) -> ParseError {
+        self.column = Some(column);
+        self
+    }
+}
 
+impl std::error::Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "Failed to parse {}, line {}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ", column {}", column)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum EntryType {
     CLASS,
     SIGNAL,
@@ -32,6 +95,7 @@ impl Display for EntryType {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FunctionArgument {
     pub name: String,
     pub value_type: Option<String>,
@@ -52,12 +116,27 @@ impl Display for FunctionArgument {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FunctionArgStruct {
     pub arguments: Vec<FunctionArgument>,
     pub super_arguments: Option<Vec<FunctionArgument>>,
     pub return_type: Option<String>,
+    /// Tagged with `@virtual`, meant to be overridden
+    pub is_virtual: bool,
+    /// The method of a base class, which the function overrides, see `--mark-overrides`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<Override>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Override {
+    /// A function of a documented script, which the script extends, with the title of its page
+    Script { title: String, link: Option<String> },
+    /// A virtual method of the engine like `_ready`, which no script it extends declares
+    Engine,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct VariableArgStruct {
     pub value_type: Option<String>,
     pub assignment: Option<String>,
@@ -65,78 +144,522 @@ pub struct VariableArgStruct {
     pub getter: Option<String>,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ExportArgStruct {
     pub value_type: Option<String>,
     pub assignment: Option<String>,
+    /// Arguments of the export hint or of the `@export_*` annotation
     pub options: Vec<String>,
     pub setter: Option<String>,
     pub getter: Option<String>,
+    /// Suffix of the `@export_*` annotation, like `range` for `@export_range`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_hint: Option<String>,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct EnumValue {
     pub name: String,
+    /// 0, if the value is only known as an expression
     pub value: isize,
+    /// The value as written, if it refers to a constant of another script, which isn't known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
     pub text: Vec<String>,
+    pub line: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClassArgStruct {
+    pub base: Option<String>,
+    pub entries: Vec<DocumentationEntry>,
+    /// The page of the class, if it is documented on a page of its own instead of with its script
+    #[serde(default)]
+    pub link: Option<String>,
 }
 
+/// Tagged with its `kind`, the arguments are in `args`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "args")]
 pub enum SymbolArgs {
     FunctionArgs(FunctionArgStruct),
     VariableArgs(VariableArgStruct),
     ExportArgs(ExportArgStruct),
     EnumArgs(Vec<EnumValue>),
-    ClassArgs(Vec<DocumentationEntry>),
+    ClassArgs(ClassArgStruct),
+    SignalArgs(Vec<FunctionArgument>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub name: String,
+    pub arguments: Vec<String>,
 }
 
+/// Annotations, which arrange the exports in the inspector, instead of exporting a variable
+const EXPORT_LAYOUT_ANNOTATIONS: [&str; 3] = ["export_category", "export_group", "export_subgroup"];
+
+impl Annotation {
+    /// `@export` or any `@export_*` annotation, including ones of newer Godot versions
+    fn is_export(&self) -> bool {
+        self.export_hint().is_some()
+    }
+
+    /// The suffix of an `@export_*` annotation, empty for `@export`
+    fn export_hint(&self) -> Option<&str> {
+        match self.name.strip_prefix("export") {
+            Some("") => Some(""),
+            Some(suffix) if !EXPORT_LAYOUT_ANNOTATIONS.contains(&self.name.as_str()) => {
+                suffix.strip_prefix('_').filter(|x| !x.is_empty())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Annotations, which are attached to the following symbol, all others are dropped
+const KNOWN_ANNOTATIONS: [&str; 3] = ["onready", "rpc", "static_unload"];
+
+/// Statements at class level, which don't declare anything to document
+const UNDOCUMENTED_STATEMENTS: [&str; 2] = ["tool", "pass"];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Symbol {
     pub name: String,
     pub arg: Option<SymbolArgs>,
     pub text: Vec<String>,
+    pub annotations: Vec<Annotation>,
+    /// Version, which introduced the symbol, from a `@since` tag in its comments
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Why the symbol shouldn't be used anymore, from a `@deprecated` tag, empty without a message
+    #[serde(default)]
+    pub deprecated: Option<String>,
+    /// The name is prefixed with an '_', which marks the symbol as private
+    #[serde(default)]
+    pub is_private: bool,
+    /// First line of the declaration
+    pub line: u32,
+    /// Last line of the declaration, for classes this includes the whole body
+    pub end_line: u32,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DocumentationEntry {
     pub entry_type: EntryType,
     pub symbols: Vec<Symbol>,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dependency {
+    pub path: String,
+    pub link: Option<String>,
+}
+
+/// A link to the page of another script or class
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PageLink {
+    pub title: String,
+    pub link: String,
+}
+
+/// Members, which a script inherits from another documented script
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InheritedClass {
+    /// Title of the page of the script, which declares the members
+    pub title: String,
+    pub link: Option<String>,
+    /// Path of the script, which declares the members, like `source_path`
+    pub source_path: String,
+    pub entries: Vec<DocumentationEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DocumentationData {
     pub source_file: String,
+    /// Path of the source file relative to the documented directory, separated by '/'
+    pub source_path: String,
+    /// Global name of the script, set with `class_name`
+    pub class_name: Option<String>,
+    /// Class the script extends
+    pub base: Option<String>,
+    /// Comments of the script, above its `extends` and `class_name` or below them up to a blank line
+    #[serde(default)]
+    pub text: Vec<String>,
     pub entries: Vec<DocumentationEntry>,
+    pub dependencies: Vec<Dependency>,
+    /// Number of lines in the source file
+    pub end_line: u32,
+    /// The page of the script, which contains the class, if this is the page of an inner class
+    #[serde(default)]
+    pub parent: Option<PageLink>,
+    /// Members of the scripts it extends, the nearest first, see `--inherited-members`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inherited: Vec<InheritedClass>,
+    /// Global name of the script, if the project loads it as autoload singleton
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autoload: Option<String>,
+}
+
+impl DocumentationData {
+    /// The title of the script's page, its `class_name` or else its file name
+    pub fn title(&self) -> &str {
+        self.class_name.as_deref().unwrap_or(&self.source_file)
+    }
+
+    /// The first line of the script's comments, which isn't a tag
+    pub fn summary(&self) -> Option<&str> {
+        self.text
+            .iter()
+            .map(|x| x.trim())
+            .find(|x| !x.is_empty() && !x.starts_with('@'))
+    }
+
+    /// Removes every symbol, which wasn't introduced in a version accepted by the filter.
+    /// Classes are kept with their matching members, if they don't match themselves.
+    pub fn filter_since(&mut self, filter: &SinceFilter) {
+        filter_entries(&mut self.entries, filter);
+    }
+
+    /// Every symbol with the type of its entry, the members of classes follow their class
+    pub fn symbols(&self) -> Vec<(&EntryType, &Symbol)> {
+        let mut symbols = Vec::new();
+        collect_symbols(&self.entries, &mut symbols);
+        symbols
+    }
+
+    /// Every symbol tagged with `@deprecated`, including the members of classes
+    pub fn deprecated_symbols(&self) -> Vec<&Symbol> {
+        self.symbols()
+            .into_iter()
+            .map(|(_, symbol)| symbol)
+            .filter(|symbol| symbol.deprecated.is_some())
+            .collect()
+    }
+
+    /// The top level constants and enum values of a script with a `class_name`, by the name
+    /// other scripts refer to them with, like `Player.MAX_HEALTH` or `Player.State.IDLE`
+    pub fn qualified_constants(&self) -> Vec<(String, String)> {
+        let class_name = match &self.class_name {
+            Some(class_name) => class_name,
+            None => return Vec::new(),
+        };
+
+        let mut constants = Vec::new();
+        for entry in self.entries.iter() {
+            for symbol in entry.symbols.iter() {
+                match &symbol.arg {
+                    Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                        assignment: Some(assignment),
+                        ..
+                    })) if entry.entry_type == EntryType::CONST => constants.push((
+                        format!("{}.{}", class_name, symbol.name),
+                        assignment.clone(),
+                    )),
+                    Some(SymbolArgs::EnumArgs(values)) => {
+                        let scope = match symbol.name.as_str() {
+                            "" => class_name.clone(),
+                            name => format!("{}.{}", class_name, name),
+                        };
+                        constants.extend(
+                            values
+                                .iter()
+                                .filter(|v| v.expression.is_none())
+                                .map(|v| (format!("{}.{}", scope, v.name), v.value.to_string())),
+                        );
+                    }
+                    _ => (),
+                }
+            }
+        }
+        constants
+    }
+}
+
+fn collect_symbols<'a>(
+    entries: &'a [DocumentationEntry],
+    symbols: &mut Vec<(&'a EntryType, &'a Symbol)>,
+) {
+    for entry in entries.iter() {
+        for symbol in entry.symbols.iter() {
+            symbols.push((&entry.entry_type, symbol));
+            if let Some(SymbolArgs::ClassArgs(class)) = &symbol.arg {
+                collect_symbols(&class.entries, symbols);
+            }
+        }
+    }
+}
+
+fn filter_entries(entries: &mut Vec<DocumentationEntry>, filter: &SinceFilter) {
+    for entry in entries.iter_mut() {
+        entry.symbols.retain_mut(|symbol| {
+            if filter.accepts(symbol.since.as_deref()) {
+                return true;
+            }
+            match &mut symbol.arg {
+                Some(SymbolArgs::ClassArgs(class)) => {
+                    filter_entries(&mut class.entries, filter);
+                    !class.entries.is_empty()
+                }
+                _ => false,
+            }
+        });
+    }
+    entries.retain(|x| !x.symbols.is_empty());
+}
+
+/// A version like `1.2.0`, compared by its numeric components and then its pre-release
+#[derive(Clone, Debug)]
+pub struct Version {
+    numbers: Vec<u64>,
+    pre_release: Option<String>,
+}
+
+impl Version {
+    /// Accepts a leading 'v', missing components count as 0 and build metadata after '+' is ignored
+    pub fn parse(text: &str) -> Result<Version, String> {
+        let version = text.trim();
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let version = version.split('+').next().unwrap();
+        let (numbers, pre_release) = match version.find('-') {
+            Some(i) => (&version[..i], Some(version[i + 1..].to_string())),
+            None => (version, None),
+        };
+        let numbers = numbers
+            .split('.')
+            .map(|x| x.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Invalid version '{}'", text))?;
+
+        Ok(Version {
+            numbers,
+            pre_release,
+        })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        let length = self.numbers.len().max(other.numbers.len());
+        let number = |version: &Version, i: usize| version.numbers.get(i).copied().unwrap_or(0);
+        (0..length)
+            .map(|i| number(self, i).cmp(&number(other, i)))
+            .find(|x| *x != Ordering::Equal)
+            .unwrap_or_else(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Version) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Selects symbols by the version in their `@since` tag, like `>=1.2.0`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SinceFilter {
+    operator: &'static str,
+    version: Version,
+}
+
+impl SinceFilter {
+    pub const OPERATORS: [&'static str; 5] = [">=", "<=", ">", "<", "="];
+
+    /// Without an operator, the filter accepts the version and all later ones
+    pub fn parse(text: &str) -> Result<SinceFilter, String> {
+        let text = text.trim();
+        let operator = SinceFilter::OPERATORS
+            .iter()
+            .find(|x| text.starts_with(*x))
+            .copied();
+        let version = Version::parse(&text[operator.map_or(0, str::len)..])?;
+
+        Ok(SinceFilter {
+            operator: operator.unwrap_or(">="),
+            version,
+        })
+    }
+
+    /// Symbols without a version or with an invalid one are never accepted
+    pub fn accepts(&self, since: Option<&str>) -> bool {
+        let version = match since.map(Version::parse) {
+            Some(Ok(version)) => version,
+            _ => return false,
+        };
+        let ordering = version.cmp(&self.version);
+        match self.operator {
+            ">=" => ordering != Ordering::Less,
+            "<=" => ordering != Ordering::Greater,
+            ">" => ordering == Ordering::Greater,
+            "<" => ordering == Ordering::Less,
+            _ => ordering == Ordering::Equal,
+        }
+    }
+}
+
+/// Controls, which members prefixed with an '_' are documented
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShowPrefixed {
+    default: bool,
+    per_type: BTreeMap<String, bool>,
+}
+
+impl ShowPrefixed {
+    pub const KEYS: [&'static str; 7] =
+        ["class", "signal", "func", "var", "const", "export", "enum"];
+
+    pub fn all(show: bool) -> ShowPrefixed {
+        ShowPrefixed {
+            default: show,
+            per_type: BTreeMap::new(),
+        }
+    }
+
+    /// Uses `default` for every type, which isn't listed in `per_type`
+    pub fn new(default: bool, per_type: HashMap<String, bool>) -> Result<ShowPrefixed, String> {
+        if let Some(key) = per_type.keys().find(|k| !Self::KEYS.contains(&k.as_str())) {
+            return Err(format!(
+                "Unknown entry type '{}' in show_prefixed, expected one of default, {}",
+                key,
+                Self::KEYS.join(", ")
+            ));
+        }
+
+        Ok(ShowPrefixed {
+            default,
+            per_type: per_type.into_iter().collect(),
+        })
+    }
+
+    /// Whether members of this type, which are prefixed with an '_', are documented
+    pub fn shows(&self, entry_type: EntryType) -> bool {
+        let key = match entry_type {
+            EntryType::CLASS => "class",
+            EntryType::SIGNAL => "signal",
+            EntryType::FUNC => "func",
+            EntryType::VAR => "var",
+            EntryType::CONST => "const",
+            EntryType::EXPORT => "export",
+            EntryType::ENUM => "enum",
+        };
+        *self.per_type.get(key).unwrap_or(&self.default)
+    }
+}
+
+impl Default for ShowPrefixed {
+    fn default() -> ShowPrefixed {
+        ShowPrefixed::all(true)
+    }
+}
+
+/// The settings, which affect parsing
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParseOptions {
+    pub show_prefixed: ShowPrefixed,
+    /// Comments starting with one of these are skipped
+    pub ignored_comment_prefixes: Vec<String>,
+    /// Treat invalid UTF-8 as an error instead of replacing it
+    pub strict_encoding: bool,
+    /// Warn about declarations, which aren't recognized and therefore not documented
+    pub warn_unrecognized: bool,
+    /// Constants of other scripts by their qualified name, like `Player.MAX_HEALTH`,
+    /// enum values can refer to them
+    pub constants: BTreeMap<String, String>,
 }
 
 struct FileIterator<R: Read> {
-    reader: Lines<BufReader<R>>,
+    reader: BufReader<R>,
+    filename: String,
     lineno: u32,
+    strict_encoding: bool,
 }
 
 impl<R: Read> FileIterator<R> {
-    fn new(r: R) -> FileIterator<R> {
+    fn new(filename: &str, r: R, strict_encoding: bool) -> FileIterator<R> {
         FileIterator {
-            reader: BufReader::new(r).lines(),
+            reader: BufReader::new(r),
+            filename: filename.to_string(),
             lineno: 0,
+            strict_encoding,
         }
     }
 
     fn lineno(&self) -> u32 {
         self.lineno
     }
+
+    /// Decodes a line, invalid UTF-8 is replaced unless the encoding is strict
+    fn decode(&self, bytes: Vec<u8>) -> Result<String, ParseError> {
+        match String::from_utf8(bytes) {
+            Ok(line) => Ok(line),
+            Err(_) if self.strict_encoding => {
+                Err(
+                    ParseError::new(&self.filename, self.lineno, "invalid UTF-8")
+                        .with_kind(ParseErrorKind::Encoding),
+                )
+            }
+            Err(e) => {
+                crate::print_warning(&format!(
+                    "{}, line {}: invalid UTF-8 was replaced",
+                    self.filename, self.lineno
+                ));
+                Ok(String::from_utf8_lossy(e.as_bytes()).into_owned())
+            }
+        }
+    }
 }
 
 impl<R: Read> Iterator for FileIterator<R> {
-    type Item = Result<String, String>;
+    type Item = Result<String, ParseError>;
+
+    fn next(&mut self) -> Option<Result<String, ParseError>> {
+        let mut bytes = Vec::new();
+        match self.reader.read_until(b'\n', &mut bytes) {
+            Ok(0) => return None,
+            Ok(_) => (),
+            Err(e) => {
+                return Some(Err(ParseError::new(
+                    &self.filename,
+                    self.lineno + 1,
+                    e.to_string(),
+                )
+                .with_kind(ParseErrorKind::Io)))
+            }
+        }
+        self.lineno += 1;
 
-    fn next(&mut self) -> Option<Result<String, String>> {
-        if let Some(x) = self.reader.next() {
-            self.lineno += 1;
-            return Some(x.map_err(|e| e.to_string()));
+        if bytes.last() == Some(&b'\n') {
+            bytes.pop();
+        }
+        // Windows editors may add a byte order mark and '\r' to the line endings
+        if self.lineno == 1 && bytes.starts_with("\u{feff}".as_bytes()) {
+            bytes.drain(..3);
         }
-        None
+        while bytes.last() == Some(&b'\r') {
+            bytes.pop();
+        }
+
+        Some(self.decode(bytes))
     }
 }
 
 fn get_indentation_level(s: &str) -> u32 {
     let mut i = 0;
     for c in s.chars() {
-        if c != '\t' {
+        if c != '\t' && c != ' ' {
             return i;
         }
         i += 1;
@@ -150,11 +673,14 @@ fn get_comment<'a>(
     lineno: u32,
     line: &'a str,
     parentheses: &mut Vec<char>,
-) -> Result<(&'a str, Option<&'a str>), String> {
+) -> Result<(&'a str, Option<&'a str>), ParseError> {
     let pos = find(filename, lineno, line, '#', parentheses)?;
 
     if let Some(pos) = pos {
-        return Ok((line[..pos].trim_end(), Some(line[pos + 1..].trim())));
+        // Godot 4 doc comments start with '##'
+        let comment = &line[pos + 1..];
+        let comment = comment.strip_prefix('#').unwrap_or(comment);
+        return Ok((line[..pos].trim_end(), Some(comment.trim())));
     }
 
     Ok((line, None))
@@ -169,93 +695,272 @@ struct ClassFrame {
     constants: Vec<Symbol>,
     exports: Vec<Symbol>,
     enums: Vec<Symbol>,
+    dependencies: Vec<String>,
+    base: Option<String>,
+    class_name: Option<String>,
 }
 
 #[derive(Default)]
 struct EnumFrame {
     last_value: isize,
+    /// The last value, which is only known as an expression, and the number of values since
+    last_expression: Option<(String, isize)>,
     values: Vec<EnumValue>,
+    /// Comments of the enum itself, comments after its first line belong to the values
+    text: Vec<String>,
+    line: u32,
 }
 
 enum Mode {
     Normal(ClassFrame),
     Enum(String, EnumFrame),
-    Class(String, (u32, Option<u32>), ClassFrame, Vec<String>),
-}
-
-fn get_constant(stack: &Vec<Mode>, raw: &str) -> Option<String> {
-    for frame in stack.iter().rev() {
-        match frame {
-            Mode::Class(_, _, class_frame, _) | Mode::Normal(class_frame) => {
-                for v in &class_frame.constants {
-                    if v.name == raw {
-                        if let Some(SymbolArgs::VariableArgs(VariableArgStruct {
-                            assignment,
-                            ..
-                        })) = &v.arg
-                        {
-                            return assignment.clone();
-                        }
+    // The last tuple holds the first and the last line of the class
+    Class(
+        String,
+        (u32, Option<u32>),
+        ClassFrame,
+        Vec<String>,
+        (u32, u32),
+    ),
+    // Indented block belonging to a declaration, which is not documented (e.g. a lambda body)
+    Block(u32),
+}
+
+/// The statement, which is parsed, with the comments and annotations before it
+struct ParseState<'a> {
+    filename: &'a str,
+    lineno: u32,
+    end_lineno: u32,
+    options: &'a ParseOptions,
+    comment_buffer: &'a mut Vec<String>,
+    annotations: &'a mut Vec<Annotation>,
+    override_visibility: &'a mut Option<bool>,
+}
+
+fn get_enum_value(symbol: &Symbol, name: &str) -> Option<String> {
+    match &symbol.arg {
+        Some(SymbolArgs::EnumArgs(values)) => values
+            .iter()
+            .find(|v| v.name == name)
+            .map(|v| v.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Resolves a constant, which may be qualified by the names of inner classes and enums
+fn lookup_constant(
+    constants: &[Symbol],
+    enums: &[Symbol],
+    classes: &[Symbol],
+    path: &[&str],
+) -> Option<String> {
+    match path {
+        [name] => constants
+            .iter()
+            .find(|c| c.name == *name)
+            .and_then(|c| match &c.arg {
+                Some(SymbolArgs::VariableArgs(VariableArgStruct { assignment, .. })) => {
+                    assignment.clone()
+                }
+                _ => None,
+            })
+            // Values of unnamed enums are constants of the surrounding class
+            .or_else(|| {
+                enums
+                    .iter()
+                    .filter(|e| e.name.is_empty())
+                    .find_map(|e| get_enum_value(e, name))
+            }),
+        [scope, name] if enums.iter().any(|e| e.name == *scope) => enums
+            .iter()
+            .find(|e| e.name == *scope)
+            .and_then(|e| get_enum_value(e, name)),
+        [scope, rest @ ..] => {
+            classes
+                .iter()
+                .find(|c| c.name == *scope)
+                .and_then(|c| match &c.arg {
+                    Some(SymbolArgs::ClassArgs(ClassArgStruct { entries, .. })) => {
+                        let symbols = |entry_type: EntryType| {
+                            entries
+                                .iter()
+                                .find(|e| e.entry_type == entry_type)
+                                .map_or(&[][..], |e| &e.symbols[..])
+                        };
+                        lookup_constant(
+                            symbols(EntryType::CONST),
+                            symbols(EntryType::ENUM),
+                            symbols(EntryType::CLASS),
+                            rest,
+                        )
                     }
+                    _ => None,
+                })
+        }
+        [] => None,
+    }
+}
+
+/// Searches the current class and all enclosing classes for a constant
+fn get_constant(stack: &[Mode], current: Option<&ClassFrame>, raw: &str) -> Option<String> {
+    let path = raw.split('.').map(|x| x.trim()).collect::<Vec<_>>();
+
+    let enclosing = stack.iter().rev().filter_map(|mode| match mode {
+        Mode::Class(name, _, frame, _, _) => Some((Some(name), frame)),
+        Mode::Normal(frame) => Some((None, frame)),
+        _ => None,
+    });
+    for (name, frame) in current
+        .map(|frame| (None, frame))
+        .into_iter()
+        .chain(enclosing)
+    {
+        let value = lookup_constant(&frame.constants, &frame.enums, &frame.classes, &path);
+        if value.is_some() {
+            return value;
+        }
+
+        // Classes, which are still being parsed, can be referred to by their name as well
+        if let (Some(name), [scope, rest @ ..]) = (name, path.as_slice()) {
+            if name == scope {
+                let value = lookup_constant(&frame.constants, &frame.enums, &frame.classes, rest);
+                if value.is_some() {
+                    return value;
                 }
             }
-            _ => {}
         }
     }
 
     None
 }
 
+fn parent_frame<'a>(
+    filename: &str,
+    lineno: u32,
+    stack: &'a mut [Mode],
+) -> Result<&'a mut ClassFrame, ParseError> {
+    match stack.last_mut() {
+        Some(Mode::Normal(ref mut frame)) | Some(Mode::Class(_, _, ref mut frame, _, _)) => {
+            Ok(frame)
+        }
+        Some(Mode::Enum(_, _)) => Err(ParseError::new(
+            filename,
+            lineno,
+            "Unexpected declaration inside an enum",
+        )),
+        Some(Mode::Block(_)) => Err(ParseError::new(
+            filename,
+            lineno,
+            "Unexpected declaration inside a block",
+        )),
+        None => Err(ParseError::new(
+            filename,
+            lineno,
+            "Unexpected end of the parsing stack",
+        )),
+    }
+}
+
+/// Resolves the explicit value of an enum value, a constant of another script, which isn't
+/// known, is returned as the expression
+fn enum_value(
+    filename: &str,
+    lineno: u32,
+    options: &ParseOptions,
+    stack: &[Mode],
+    current: Option<&ClassFrame>,
+    enum_frame: &EnumFrame,
+    raw: &str,
+) -> Result<(isize, Option<String>), ParseError> {
+    if let Ok(value) = raw.parse() {
+        return Ok((value, None));
+    }
+
+    // Earlier values of the same enum can be referenced as well
+    if let Some(earlier) = enum_frame.values.iter().find(|v| v.name == raw) {
+        return Ok((earlier.value, earlier.expression.clone()));
+    }
+    let constant = get_constant(stack, current, raw).or_else(|| {
+        let path = raw.split('.').map(|x| x.trim()).collect::<Vec<_>>();
+        options.constants.get(&path.join(".")).cloned()
+    });
+    match constant {
+        Some(constant) => constant.parse().map(|value| (value, None)).map_err(|_| {
+            ParseError::new(
+                filename,
+                lineno,
+                format!(
+                    "Constant '{}' of value '{}' is not a valid enum value",
+                    raw, constant
+                ),
+            )
+        }),
+        None if raw.contains('.') => Ok((0, Some(raw.to_string()))),
+        None => Err(ParseError::new(
+            filename,
+            lineno,
+            format!(
+                "'{}' is not a valid enum value, no constant of that name was found",
+                raw
+            ),
+        )),
+    }
+}
+
 fn parse_enum(
-    settings: &Settings,
-    stack: &Vec<Mode>,
+    state: &mut ParseState,
+    stack: &[Mode],
+    current: Option<&ClassFrame>,
     values: &str,
     enum_frame: &mut EnumFrame,
-    override_visibility: &mut Option<bool>,
-    comment_buffer: &mut Vec<String>,
-) -> Result<(), String> {
-    for v in values.split(',') {
+) -> Result<(), ParseError> {
+    let ParseState {
+        filename,
+        lineno,
+        options,
+        ..
+    } = *state;
+    for v in split_top_level(filename, lineno, values, ',')? {
         let mut arg_iterator = v.split('=');
 
         let name = arg_iterator
             .next()
-            .ok_or("Expected name for enum value")?
+            .ok_or_else(|| ParseError::new(filename, lineno, "Expected name for enum value"))?
             .trim();
         if name.is_empty() {
             continue;
         }
-        let value = arg_iterator
-            .next()
-            .and_then(|x| {
-                let raw = x.trim();
-                let res = raw.parse();
-                if let Err(_) = res {
-                    let val = get_constant(stack, raw);
-
-                    if let Some(v) = val {
-                        return Some(v.parse().map_err(|_| {
-                            format!(
-                                "Constant '{}' of value '{}' is not a valid enum value",
-                                raw, v
-                            )
-                        }));
-                    }
-
-                    return Some(Err(format!("'{}' is not a valid enum value", raw)));
+        let (value, expression) = match arg_iterator.next().map(|x| x.trim()) {
+            Some(raw) => {
+                let (value, expression) =
+                    enum_value(filename, lineno, options, stack, current, enum_frame, raw)?;
+                enum_frame.last_value = value + 1;
+                enum_frame.last_expression = expression.clone().map(|x| (x, 1));
+                (value, expression)
+            }
+            // Values after an expression count up from it
+            None => match &mut enum_frame.last_expression {
+                Some((expression, offset)) => {
+                    let value = format!("{} + {}", expression, offset);
+                    *offset += 1;
+                    (0, Some(value))
                 }
+                None => {
+                    enum_frame.last_value += 1;
+                    (enum_frame.last_value - 1, None)
+                }
+            },
+        };
 
-                Some(Ok(res.unwrap()))
-            })
-            .unwrap_or(Ok(enum_frame.last_value))?;
-
-        enum_frame.last_value = value + 1;
-
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
+        if (!name.starts_with("_") || options.show_prefixed.shows(EntryType::ENUM))
+            && state.override_visibility.unwrap_or(true)
         {
             enum_frame.values.push(EnumValue {
                 name: name.to_string(),
                 value: value,
-                text: comment_buffer.drain(..).collect(),
+                expression,
+                text: state.comment_buffer.drain(..).collect(),
+                line: lineno,
             });
         }
     }
@@ -264,129 +969,93 @@ fn parse_enum(
 }
 
 fn parse_line(
-    filename: &str,
-    lineno: u32,
-    settings: &Settings,
+    state: &mut ParseState,
     mut mode: Mode,
     stack: &mut Vec<Mode>,
     line: String,
-    override_visibility: &mut Option<bool>,
-    comment_buffer: &mut Vec<String>,
     indentation_level: u32,
-) -> Result<(), String> {
+) -> Result<(), ParseError> {
+    let ParseState {
+        filename,
+        lineno,
+        end_lineno,
+        ..
+    } = *state;
     match mode {
         Mode::Enum(ref name, ref mut enum_frame) => {
-            let end = line.find('}');
+            let end = find(filename, lineno, &line, '}', &mut Vec::new())?;
             let slice = match end {
                 Some(x) => &line[..x],
                 None => &line,
             };
 
-            parse_enum(
-                settings,
-                stack,
-                slice,
-                enum_frame,
-                override_visibility,
-                comment_buffer,
-            )?;
+            parse_enum(state, stack, None, slice, enum_frame)?;
 
             if end.is_some() {
                 let name_string = name.to_string();
                 let values = enum_frame.values.drain(..).collect();
-                match stack.last_mut() {
-                    Some(Mode::Normal(ref mut frame))
-                    | Some(Mode::Class(_, _, ref mut frame, _)) => frame.enums.push(Symbol {
-                        name: name_string,
-                        arg: Some(SymbolArgs::EnumArgs(values)),
-                        text: comment_buffer.drain(..).collect(),
-                    }),
-                    Some(Mode::Enum(_, _)) => {
-                        panic!("[parser.rs] Unexpected Enum value after completed enum")
-                    }
-                    None => panic!("[parser.rs] Unexpected end of parsing_mode stack"),
-                }
+                let text = std::mem::take(&mut enum_frame.text);
+                let line = enum_frame.line;
+                state.comment_buffer.clear();
+                parent_frame(filename, lineno, stack)?.enums.push(Symbol {
+                    name: name_string,
+                    arg: Some(SymbolArgs::EnumArgs(values)),
+                    text,
+                    annotations: Vec::new(),
+                    since: None,
+                    deprecated: None,
+                    is_private: false,
+                    line,
+                    end_line: end_lineno,
+                });
             } else {
                 stack.push(mode);
             }
         }
 
-        Mode::Class(ref mut name, (ref old_indent, ref mut indent), ref mut frame, _) => {
+        Mode::Class(_, (ref old_indent, ref mut indent), ref mut frame, _, _) => {
             if indent.is_none() {
                 if indentation_level > *old_indent {
                     *indent = Some(indentation_level);
                 } else {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: Indented block expected",
-                        filename, lineno
-                    ));
+                    return Err(ParseError::new(filename, lineno, "Indented block expected"));
                 }
             }
             let indent = indent.unwrap();
             if indentation_level == indent {
-                let new_frame = parse_class_content(
-                    filename,
-                    lineno,
-                    &line.trim(),
-                    indentation_level,
-                    frame,
-                    comment_buffer,
-                    settings,
-                    override_visibility,
-                    &stack,
-                )?;
+                let new_frame =
+                    parse_class_content(state, line.trim(), indentation_level, frame, stack)?;
                 stack.push(mode);
                 if let Some(new_frame) = new_frame {
                     stack.push(new_frame);
                 }
             } else if indentation_level < indent {
-                let mut entries = Vec::new();
-                let name = name.to_string();
-                let (frame, comments) = match mode {
-                    Mode::Class(_, _, frame, comments) => (frame, comments),
-                    _ => panic!(),
-                };
-                add_entries(&mut entries, frame);
+                close_class(filename, lineno, stack, mode)?;
 
-                match stack.last_mut() {
-                    Some(Mode::Normal(ref mut frame))
-                    | Some(Mode::Class(_, _, ref mut frame, _)) => frame.classes.push(Symbol {
-                        name: name,
-                        arg: Some(SymbolArgs::ClassArgs(entries)),
-                        text: comments,
-                    }),
-                    Some(Mode::Enum(_, _)) => {
-                        panic!("[parser.rs] Unexpected Enum value after completed class")
-                    }
-                    None => panic!("[parser.rs] Unexpected end of parsing_mode stack"),
-                }
+                let parent = stack.pop().ok_or_else(|| {
+                    ParseError::new(filename, lineno, "Unexpected end of the parsing stack")
+                })?;
+                return parse_line(state, parent, stack, line, indentation_level);
+            } else {
+                // Lines indented deeper than the class body belong to a function body
+                stack.push(mode);
+            }
+        }
 
-                return parse_line(
-                    filename,
-                    lineno,
-                    settings,
-                    stack.pop().unwrap(),
-                    stack,
-                    line,
-                    override_visibility,
-                    comment_buffer,
-                    indentation_level,
-                );
+        Mode::Block(block_indent) => {
+            if indentation_level > block_indent {
+                stack.push(mode);
+            } else {
+                let parent = stack.pop().ok_or_else(|| {
+                    ParseError::new(filename, lineno, "Unexpected end of the parsing stack")
+                })?;
+                return parse_line(state, parent, stack, line, indentation_level);
             }
         }
 
         Mode::Normal(ref mut frame) => {
-            let new_frame = parse_class_content(
-                filename,
-                lineno,
-                line.as_str(),
-                indentation_level,
-                frame,
-                comment_buffer,
-                settings,
-                override_visibility,
-                &stack,
-            )?;
+            let new_frame =
+                parse_class_content(state, line.as_str(), indentation_level, frame, stack)?;
             stack.push(mode);
             if let Some(new_frame) = new_frame {
                 stack.push(new_frame);
@@ -397,136 +1066,508 @@ fn parse_line(
     Ok(())
 }
 
-pub fn parse_file(
+/// Parses GDScript source code held in memory
+pub fn parse_str(
     filename: &str,
-    f: File,
-    settings: &Settings,
-) -> Result<DocumentationData, String> {
+    source: &str,
+    options: &ParseOptions,
+) -> Result<DocumentationData, ParseError> {
+    parse_source(filename, source.as_bytes(), options)
+}
+
+/// Parses a GDScript source, `filename` is used for error messages and the output
+pub fn parse_source(
+    filename: &str,
+    reader: impl Read,
+    options: &ParseOptions,
+) -> Result<DocumentationData, ParseError> {
     let mut parsing_mode = vec![Mode::Normal(ClassFrame::default())];
 
     let mut comment_buffer: Vec<String> = Vec::new();
+    // The comments of the script and whether the last statement was `extends` or `class_name`
+    let mut script_text: Vec<String> = Vec::new();
+    let mut after_header = false;
+    let mut annotations = Vec::new();
     let mut override_visibility = None;
     let mut open_parentheses = Vec::new();
 
-    let mut lines = FileIterator::new(f);
+    let mut lines = FileIterator::new(filename, reader, options.strict_encoding);
     while let Some(mut current_line) = lines.next() {
         let mut full_line: String = String::new();
-
-        // Parse the full statement with normal opening parentheses '(' all closed
+        // Joined statements are reported at their first physical line
+        let lineno = lines.lineno();
+        let comments_start = comment_buffer.len();
+        let mut has_comment = false;
+        // Comments after code, with the length of the statement up to them
+        let mut trailing_comments = Vec::new();
+
+        // Parse the full statement with all opening brackets '(', '[' and '{' closed
         loop {
-            let mut partial_line = current_line?;
-
-            // Backslashes at the end of a line ignore the newline
-            while partial_line.ends_with("\\") && !partial_line.contains('#') {
-                partial_line.remove(partial_line.len() - 1);
-                partial_line += &lines
-                    .next()
-                    .ok_or("Unexpected eof, expected newline after \\".to_string())??
-                    .as_str()
-                    .trim()
+            let mut physical_line = current_line?;
+            loop {
+                let in_string = matches!(open_parentheses.last(), Some('"') | Some('\''));
+                let (partial_line, comment) = get_comment(
+                    filename,
+                    lines.lineno(),
+                    &physical_line,
+                    &mut open_parentheses,
+                )?;
+
+                let mut trailing = false;
+                if let Some(comment) = comment {
+                    has_comment = true;
+                    override_visibility = match comment {
+                        "[Show]" => Some(true),
+                        "[Hide]" => Some(false),
+                        _ => override_visibility,
+                    };
+                    if !is_ignored_comment(comment, options) {
+                        comment_buffer.push(comment.to_string());
+                        trailing = !partial_line.trim().is_empty();
+                    }
+                }
+
+                // Backslashes at the end of a line ignore the newline
+                // Continuation lines keep their indentation until here, so error columns stay accurate
+                let continued = partial_line.strip_suffix('\\');
+                let partial_line = continued.unwrap_or(partial_line);
+                if full_line.is_empty() {
+                    full_line += partial_line;
+                } else if in_string {
+                    // Lines inside of a triple quoted string are kept as they are
+                    full_line += "\n";
+                    full_line += partial_line;
+                } else {
+                    full_line += partial_line.trim_start();
+                }
+                if trailing {
+                    trailing_comments.push((comment_buffer.len() - 1, full_line.len()));
+                }
+                if continued.is_none() {
+                    break;
+                }
+
+                let lineno = lines.lineno();
+                physical_line = lines.next().ok_or_else(|| {
+                    ParseError::new(
+                        filename,
+                        lineno,
+                        "Unexpected eof, expected newline after \\",
+                    )
+                    .with_kind(ParseErrorKind::UnexpectedEof)
+                })??;
             }
-            let (partial_line, comment) = get_comment(
-                filename,
-                lines.lineno(),
-                &partial_line,
-                &mut open_parentheses,
-            )?;
 
-            if let Some(comment) = comment {
-                override_visibility = match comment {
-                    "[Show]" => Some(true),
-                    "[Hide]" => Some(false),
-                    _ => override_visibility,
+            // Enums are parsed value by value, so every value keeps its own comments
+            let statement = full_line.trim_start();
+            let in_enum = matches!(parsing_mode.last(), Some(Mode::Enum(_, _)))
+                || statement.starts_with("enum ")
+                || statement.starts_with("enum{");
+            if open_parentheses.is_empty() || in_enum {
+                break;
+            }
+
+            current_line = lines.next().ok_or_else(|| {
+                let message = match open_parentheses.last() {
+                    Some('"') | Some('\'') => "Unexpected eof, unterminated string",
+                    _ => "Unexpected eof, mismatched parentheses",
                 };
-                if !comment.starts_with("warning-ignore:") {
-                    comment_buffer.push(comment.to_string());
+                ParseError::new(filename, lines.lineno(), message)
+                    .with_kind(ParseErrorKind::UnexpectedEof)
+            })?;
+        }
+
+        // Comments after an argument of a signature, which spans several lines, document the argument
+        let mut last_argument: Option<(usize, &str)> = None;
+        let mut merged = Vec::new();
+        for (index, offset) in trailing_comments {
+            match commented_argument(filename, lineno, &full_line[..offset])? {
+                // Values spanning several lines may have a comment on each of them
+                Some(name) if last_argument.is_some_and(|(_, last)| last == name) => {
+                    let comment = std::mem::take(&mut comment_buffer[index]);
+                    let previous = last_argument.unwrap().0;
+                    comment_buffer[previous] = format!("{} {}", comment_buffer[previous], comment);
+                    merged.push(index);
+                }
+                Some(name) => {
+                    comment_buffer[index] = format!("@param {} {}", name, comment_buffer[index]);
+                    last_argument = Some((index, name));
                 }
+                None => last_argument = None,
             }
+        }
+        for index in merged.into_iter().rev() {
+            comment_buffer.remove(index);
+        }
 
-            full_line += &partial_line;
+        // Statements separated by ';' are parsed one after another, the comments before
+        // the line belong to the first one and the comments on the line to the last one
+        let indentation = full_line[..full_line.len() - full_line.trim_start().len()].to_string();
+        let statements = if matches!(parsing_mode.last(), Some(Mode::Enum(_, _))) {
+            vec![full_line.as_str()]
+        } else {
+            split_statements(filename, lineno, &full_line)?
+                .into_iter()
+                .filter(|x| !x.trim().is_empty())
+                .collect()
+        };
+        let mut own_comments = if statements.len() > 1 {
+            comment_buffer.split_off(comments_start.min(comment_buffer.len()))
+        } else {
+            Vec::new()
+        };
 
-            if !open_parentheses.contains(&'(') {
-                break;
+        // Only the comments directly above a statement document it, blank lines separate them.
+        // Comments right below `extends` and `class_name` document the script, like in Godot 4
+        if statements.is_empty() && !has_comment {
+            if after_header && script_text.is_empty() {
+                script_text = std::mem::take(&mut comment_buffer);
             }
-
-            current_line = lines
-                .next()
-                .ok_or("Unexpected eof, mismatched parentheses".to_string())?
-                .map(|x| x.trim().to_string());
+            after_header = false;
+            comment_buffer.clear();
+            override_visibility = None;
         }
 
-        let indentation_level = get_indentation_level(full_line.as_str());
-        if !full_line.trim().is_empty() {
-            parse_line(
+        let count = statements.len();
+        for (i, statement) in statements.into_iter().enumerate() {
+            if i + 1 == count {
+                comment_buffer.append(&mut own_comments);
+            }
+
+            // Annotations on their own line belong to the next statement, just like comments
+            let statement = parse_annotations(filename, lineno, statement, &mut annotations)?;
+            if statement.is_empty() {
+                continue;
+            }
+            let statement = format!("{}{}", indentation, statement);
+
+            let indentation_level = get_indentation_level(statement.as_str());
+            // Every class, which contains this statement, extends at least up to its last line
+            for mode in parsing_mode.iter_mut() {
+                if let Mode::Class(_, (class_indent, _), _, _, (_, ref mut end_line)) = mode {
+                    if indentation_level > *class_indent {
+                        *end_line = lines.lineno();
+                    }
+                }
+            }
+
+            let mode = parsing_mode.pop().ok_or_else(|| {
+                ParseError::new(filename, lineno, "Unexpected end of the parsing stack")
+            })?;
+            after_header = matches!(mode, Mode::Normal(_))
+                && (statement.starts_with("extends ") || statement.starts_with("class_name "));
+            if after_header && script_text.is_empty() {
+                script_text = std::mem::take(&mut comment_buffer);
+            }
+            let mut state = ParseState {
                 filename,
-                lines.lineno(),
-                settings,
-                parsing_mode.pop().unwrap(),
+                lineno,
+                end_lineno: lines.lineno(),
+                options,
+                comment_buffer: &mut comment_buffer,
+                annotations: &mut annotations,
+                override_visibility: &mut override_visibility,
+            };
+            parse_line(
+                &mut state,
+                mode,
                 &mut parsing_mode,
-                full_line,
-                &mut override_visibility,
-                &mut comment_buffer,
+                statement,
                 indentation_level,
             )?;
             comment_buffer.clear();
+            annotations.clear();
             override_visibility = None;
         }
     }
 
-    while parsing_mode.len() > 0 {
-        match parsing_mode.pop().unwrap() {
-            Mode::Class(name, _, frame, text) => {
-                let class_name = name;
+    let lineno = lines.lineno();
+    while let Some(mode) = parsing_mode.pop() {
+        match mode {
+            Mode::Class(ref name, (_, indent), _, _, (line, _)) => {
+                if indent.is_none() {
+                    crate::print_warning(&format!(
+                        "{}, line {}: Class '{}' has no body",
+                        filename, line, name
+                    ));
+                }
+                close_class(filename, lineno, &mut parsing_mode, mode)?;
+            }
+            Mode::Enum(name, enum_frame) => {
+                crate::print_warning(&format!(
+                    "{}, line {}: Enum '{}' is not terminated",
+                    filename, enum_frame.line, name
+                ));
+                parent_frame(filename, lineno, &mut parsing_mode)?
+                    .enums
+                    .push(Symbol {
+                        name,
+                        arg: Some(SymbolArgs::EnumArgs(enum_frame.values)),
+                        text: enum_frame.text,
+                        annotations: Vec::new(),
+                        since: None,
+                        deprecated: None,
+                        is_private: false,
+                        line: enum_frame.line,
+                        end_line: lineno,
+                    });
+            }
+
+            Mode::Block(_) => (),
+
+            Mode::Normal(mut frame) => {
+                let dependencies = frame
+                    .dependencies
+                    .drain(..)
+                    .map(|path| Dependency { path, link: None })
+                    .collect();
+                let class_name = frame.class_name.take();
+                let base = frame.base.take();
                 let mut entries = Vec::new();
                 add_entries(&mut entries, frame);
 
-                let comments = text;
-                match parsing_mode.last_mut() {
-                    Some(Mode::Normal(ref mut frame))
-                    | Some(Mode::Class(_, _, ref mut frame, _)) => frame.classes.push(Symbol {
-                        name: class_name,
-                        arg: Some(SymbolArgs::ClassArgs(entries)),
-                        text: comments,
-                    }),
-                    Some(Mode::Enum(_, _)) => {
-                        panic!("[parser.rs] Unexpected Enum value after completed class")
-                    }
-                    None => panic!("[parser.rs] Unexpected end of parsing_mode stack"),
-                }
+                return Ok(DocumentationData {
+                    source_file: filename.to_string(),
+                    source_path: filename.to_string(),
+                    class_name,
+                    base,
+                    text: script_text,
+                    entries: entries,
+                    dependencies,
+                    end_line: lineno,
+                    parent: None,
+                    inherited: Vec::new(),
+                    autoload: None,
+                });
             }
-            Mode::Enum(name, enum_frame) => {
-                let name_string = name.to_string();
-                let values = enum_frame.values;
-                match parsing_mode.last_mut() {
-                    Some(Mode::Normal(ref mut frame))
-                    | Some(Mode::Class(_, _, ref mut frame, _)) => frame.enums.push(Symbol {
-                        name: name_string,
-                        arg: Some(SymbolArgs::EnumArgs(values)),
-                        text: comment_buffer.drain(..).collect(),
-                    }),
-                    Some(Mode::Enum(_, _)) => {
-                        panic!("[parser.rs] Unexpected Enum value after completed enum")
+        }
+    }
+
+    Err(ParseError::new(
+        filename,
+        lineno,
+        "Unexpected end of the parsing stack",
+    ))
+}
+
+/// The name of the argument of a `func` or `signal` declaration, which a comment after `prefix`
+/// follows. Comments before the first argument or after the closing ')' follow none.
+fn commented_argument<'a>(
+    filename: &str,
+    lineno: u32,
+    prefix: &'a str,
+) -> Result<Option<&'a str>, ParseError> {
+    let keyword = prefix
+        .split_whitespace()
+        .find(|x| !x.starts_with('@') && *x != "static");
+    let open = match keyword {
+        Some(keyword @ ("func" | "signal")) => prefix
+            .find(&format!("{} ", keyword))
+            .and_then(|start| prefix[start..].find('(').map(|x| start + x + 1)),
+        _ => None,
+    };
+    let Some(open) = open else {
+        return Ok(None);
+    };
+
+    let arguments = &prefix[open..];
+    let mut parentheses = vec!['('];
+    let mut depth = 0;
+    let mut start = 0;
+    let mut last = None;
+    scan(filename, lineno, arguments, &mut parentheses, |i| {
+        match arguments.as_bytes()[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b',' if depth == 0 => {
+                last = Some(&arguments[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+        false
+    })?;
+    if parentheses.is_empty() {
+        return Ok(None);
+    }
+
+    // A comment after a ',' follows the argument before it
+    let argument = match arguments[start..].trim() {
+        "" => last,
+        _ => Some(&arguments[start..]),
+    };
+    Ok(argument
+        .and_then(|x| x.split([':', '=']).next())
+        .map(str::trim)
+        .filter(|x| !x.is_empty()))
+}
+
+/// Whether a comment is a directive for the editor or a linter, instead of documentation
+fn is_ignored_comment(comment: &str, options: &ParseOptions) -> bool {
+    comment.starts_with("warning-ignore:")
+        || comment.starts_with("warning-ignore-all:")
+        || options
+            .ignored_comment_prefixes
+            .iter()
+            .any(|prefix| comment.starts_with(prefix.as_str()))
+}
+
+/// Strips all annotations from the start of the line, only known annotations are kept
+fn parse_annotations<'a>(
+    filename: &str,
+    lineno: u32,
+    line: &'a str,
+    annotations: &mut Vec<Annotation>,
+) -> Result<&'a str, ParseError> {
+    let mut rest = line.trim_start();
+    while let Some(annotation) = rest.strip_prefix('@') {
+        let end = annotation
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(annotation.len());
+        let name = &annotation[..end];
+        if name.is_empty() {
+            return Err(ParseError::new(
+                filename,
+                lineno,
+                format!("invalid annotation '{}'", rest),
+            ));
+        }
+        rest = annotation[end..].trim_start();
+
+        let mut arguments = Vec::new();
+        if rest.starts_with('(') {
+            let mut parentheses = vec!['('];
+            let mut pos = 1;
+            let close = loop {
+                match find(filename, lineno, &rest[pos..], ')', &mut parentheses)? {
+                    Some(offset) if parentheses.len() == 1 => break pos + offset,
+                    Some(offset) => {
+                        parentheses.pop();
+                        pos += offset + 1;
+                    }
+                    None => {
+                        return Err(ParseError::new(
+                            filename,
+                            lineno,
+                            format!("unterminated arguments of annotation '@{}'", name),
+                        ))
                     }
-                    None => panic!("[parser.rs] Unexpected end of parsing_mode stack"),
                 }
+            };
+            arguments = split_top_level(filename, lineno, &rest[1..close], ',')?
+                .into_iter()
+                .map(|x| x.trim().to_string())
+                .filter(|x| !x.is_empty())
+                .collect();
+            rest = rest[close + 1..].trim_start();
+        }
+
+        let annotation = Annotation {
+            name: name.to_string(),
+            arguments,
+        };
+        if annotation.is_export() || KNOWN_ANNOTATIONS.contains(&name) {
+            annotations.push(annotation);
+        }
+    }
+
+    Ok(rest)
+}
+
+/// Removes the lines starting with the tag from the comments, returns the value of the last one,
+/// which is empty if it has none
+fn take_value_tag(comment_buffer: &mut Vec<String>, tag: &str) -> Option<String> {
+    let mut value = None;
+    comment_buffer.retain(|line| {
+        let line = line.trim();
+        match line.strip_prefix(tag) {
+            Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => {
+                value = Some(rest.trim().to_string());
+                false
             }
+            _ => true,
+        }
+    });
+    value
+}
 
-            Mode::Normal(frame) => {
-                let mut entries = Vec::new();
-                add_entries(&mut entries, frame);
+/// Removes the lines consisting of only the tag from the comments, returns whether there were any
+fn take_tag(comment_buffer: &mut Vec<String>, tag: &str) -> bool {
+    let count = comment_buffer.len();
+    comment_buffer.retain(|line| line.trim() != tag);
+    comment_buffer.len() != count
+}
+
+fn close_class(
+    filename: &str,
+    lineno: u32,
+    stack: &mut [Mode],
+    mode: Mode,
+) -> Result<(), ParseError> {
+    if let Mode::Class(name, _, mut frame, comments, (line, end_line)) = mode {
+        let dependencies = std::mem::take(&mut frame.dependencies);
+        let base = frame.base.take();
+        let mut entries = Vec::new();
+        add_entries(&mut entries, frame);
+
+        let parent = parent_frame(filename, lineno, stack)?;
+        parent.classes.push(Symbol {
+            name,
+            arg: Some(SymbolArgs::ClassArgs(ClassArgStruct {
+                base,
+                entries,
+                link: None,
+            })),
+            text: comments,
+            annotations: Vec::new(),
+            since: None,
+            deprecated: None,
+            is_private: false,
+            line,
+            end_line,
+        });
+        for dependency in dependencies {
+            add_dependency(parent, dependency);
+        }
+    }
+
+    Ok(())
+}
+
+fn add_dependency(frame: &mut ClassFrame, path: String) {
+    if !frame.dependencies.contains(&path) {
+        frame.dependencies.push(path);
+    }
+}
+
+/// Collects the literal paths of all `preload("...")` and `load("...")` calls
+fn find_dependencies(frame: &mut ClassFrame, assignment: &str) {
+    let mut rest = assignment;
+    while let Some(pos) = rest.find("load(") {
+        let before = rest[..pos].strip_suffix("pre").unwrap_or(&rest[..pos]);
+        let is_call = !before.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '.');
+        rest = &rest[pos + 5..];
 
-                return Ok(DocumentationData {
-                    source_file: filename.to_string(),
-                    entries: entries,
-                });
+        if !is_call {
+            continue;
+        }
+
+        let argument = rest.trim_start();
+        let quote = match argument.chars().next() {
+            Some(x) if x == '"' || x == '\'' => x,
+            _ => continue,
+        };
+        if let Some(end) = argument[1..].find(quote) {
+            let path = &argument[1..end + 1];
+            if argument[end + 2..].trim_start().starts_with(')') {
+                add_dependency(frame, path.to_string());
             }
         }
     }
-
-    panic!()
 }
 
 fn add_entries(entries: &mut Vec<DocumentationEntry>, frame: ClassFrame) {
+    let start = entries.len();
     if !frame.classes.is_empty() {
         entries.push(DocumentationEntry {
             entry_type: EntryType::CLASS,
@@ -569,67 +1610,134 @@ fn add_entries(entries: &mut Vec<DocumentationEntry>, frame: ClassFrame) {
             symbols: frame.variables,
         })
     }
+
+    for symbol in entries[start..]
+        .iter_mut()
+        .flat_map(|x| x.symbols.iter_mut())
+    {
+        symbol.since = take_value_tag(&mut symbol.text, "@since").filter(|x| !x.is_empty());
+        symbol.deprecated = take_value_tag(&mut symbol.text, "@deprecated");
+        symbol.is_private = symbol.name.starts_with('_');
+    }
 }
 
 fn parse_class_content(
-    filename: &str,
-    lineno: u32,
+    state: &mut ParseState,
     line: &str,
     indent: u32,
     frame: &mut ClassFrame,
-    comment_buffer: &mut Vec<String>,
-    settings: &Settings,
-    override_visibility: &mut Option<bool>,
-    parsing_mode: &Vec<Mode>,
-) -> Result<Option<Mode>, String> {
-    if line.starts_with("class ") {
-        let name = line[5..].split(':').next().unwrap().trim().to_string();
-
-        if !name.starts_with("_") || settings.show_prefixed {
+    parsing_mode: &[Mode],
+) -> Result<Option<Mode>, ParseError> {
+    let ParseState {
+        filename,
+        lineno,
+        end_lineno,
+        options,
+        ..
+    } = *state;
+    if let Some(declaration) = line.strip_prefix("class ") {
+        // The base may be a quoted path, which contains a ':' itself
+        let declaration = match find(filename, lineno, declaration, ':', &mut Vec::new())? {
+            Some(pos) => &declaration[..pos],
+            None => declaration,
+        };
+        let (name, base) = match declaration.split_once(" extends ") {
+            Some((name, base)) => (name.trim().to_string(), Some(base.trim().to_string())),
+            None => (declaration.trim().to_string(), None),
+        };
+
+        if !name.starts_with("_") || options.show_prefixed.shows(EntryType::CLASS) {
             return Ok(Some(Mode::Class(
                 name,
                 (indent, None),
-                ClassFrame::default(),
-                comment_buffer.drain(..).collect(),
+                ClassFrame {
+                    base,
+                    ..ClassFrame::default()
+                },
+                state.comment_buffer.drain(..).collect(),
+                (lineno, end_lineno),
             )));
         }
-    } else if line.starts_with("signal ") {
-        let name = line[6..].trim().to_string();
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
+    } else if let Some(base) = line.strip_prefix("extends ") {
+        frame.base = Some(base.trim().to_string());
+    } else if let Some(declaration) = line.strip_prefix("class_name ") {
+        // Godot 3 allows an icon after the name, Godot 4 the base class
+        let (declaration, base) = match declaration.split_once(" extends ") {
+            Some((declaration, base)) => (declaration, Some(base.trim().to_string())),
+            None => (declaration, None),
+        };
+        let name = declaration.split(',').next().unwrap().trim();
+        frame.class_name = Some(name.to_string());
+        if base.is_some() {
+            frame.base = base;
+        }
+    } else if let Some(declaration) = line.strip_prefix("signal ") {
+        let mut name = String::new();
+        let mut arguments = Vec::new();
+        parse_function(
+            filename,
+            lineno,
+            declaration,
+            &mut name,
+            &mut arguments,
+            &mut None,
+            &mut None,
+        )?;
+
+        if (!name.starts_with("_") || options.show_prefixed.shows(EntryType::SIGNAL))
+            && state.override_visibility.unwrap_or(true)
         {
             frame.signals.push(Symbol {
                 name: name,
-                arg: None,
-                text: comment_buffer.drain(..).collect(),
+                arg: Some(SymbolArgs::SignalArgs(arguments)),
+                text: state.comment_buffer.drain(..).collect(),
+                annotations: std::mem::take(state.annotations),
+                since: None,
+                deprecated: None,
+                is_private: false,
+                line: lineno,
+                end_line: end_lineno,
             });
         }
-    } else if line.starts_with("func ") {
+    } else if let Some(declaration) = line.strip_prefix("func ") {
         let mut name = String::new();
         let mut arguments = Vec::new();
         let mut super_arguments = None;
         let mut return_type = None;
 
         parse_function(
-            &line[4..],
+            filename,
+            lineno,
+            declaration,
             &mut name,
             &mut arguments,
             &mut super_arguments,
             &mut return_type,
         )?;
 
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
+        if (!name.starts_with("_") || options.show_prefixed.shows(EntryType::FUNC))
+            && state.override_visibility.unwrap_or(true)
         {
+            let is_virtual = take_tag(state.comment_buffer, "@virtual");
             frame.functions.push(Symbol {
                 name: name,
                 arg: Some(SymbolArgs::FunctionArgs(FunctionArgStruct {
                     arguments: arguments,
                     super_arguments: super_arguments,
                     return_type: return_type,
+                    is_virtual,
+                    overrides: None,
                 })),
-                text: comment_buffer.drain(..).collect(),
+                text: state.comment_buffer.drain(..).collect(),
+                annotations: std::mem::take(state.annotations),
+                since: None,
+                deprecated: None,
+                is_private: false,
+                line: lineno,
+                end_line: end_lineno,
             });
         }
-    } else if line.starts_with("var ") {
+    } else if let Some(declaration) = line.strip_prefix("var ") {
         let mut name = String::new();
         let mut value_type = None;
         let mut assignment = None;
@@ -638,7 +1746,7 @@ fn parse_class_content(
         parse_assignment(
             filename,
             lineno,
-            &line[4..],
+            declaration,
             &mut name,
             &mut value_type,
             &mut assignment,
@@ -646,20 +1754,72 @@ fn parse_class_content(
             &mut getter,
         )?;
 
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
+        if let Some(ref assignment) = assignment {
+            find_dependencies(frame, assignment);
+        }
+
+        // A lambda ending with ':' is followed by its indented body
+        let new_mode = match assignment {
+            Some(ref x) if x.starts_with("func") && x.ends_with(':') => Some(Mode::Block(indent)),
+            _ => None,
+        };
+
+        // Godot 4 exports are variables with an @export annotation
+        let is_export = state.annotations.iter().any(|a| a.is_export());
+        let entry_type = if is_export {
+            EntryType::EXPORT
+        } else {
+            EntryType::VAR
+        };
+        if (!name.starts_with("_") || options.show_prefixed.shows(entry_type))
+            && state.override_visibility.unwrap_or(true)
         {
-            frame.variables.push(Symbol {
+            // The annotation with a hint, if there is one besides a plain @export
+            let export = state
+                .annotations
+                .iter()
+                .filter(|a| a.is_export())
+                .max_by_key(|a| a.export_hint() != Some(""));
+            let (symbols, arg) = match export {
+                Some(export) => (
+                    &mut frame.exports,
+                    SymbolArgs::ExportArgs(ExportArgStruct {
+                        value_type,
+                        assignment,
+                        options: export.arguments.clone(),
+                        setter,
+                        getter,
+                        export_hint: export
+                            .export_hint()
+                            .filter(|x| !x.is_empty())
+                            .map(str::to_string),
+                    }),
+                ),
+                None => (
+                    &mut frame.variables,
+                    SymbolArgs::VariableArgs(VariableArgStruct {
+                        value_type: value_type,
+                        assignment: assignment,
+                        setter: setter,
+                        getter: getter,
+                    }),
+                ),
+            };
+            symbols.push(Symbol {
                 name: name,
-                arg: Some(SymbolArgs::VariableArgs(VariableArgStruct {
-                    value_type: value_type,
-                    assignment: assignment,
-                    setter: setter,
-                    getter: getter,
-                })),
-                text: comment_buffer.drain(..).collect(),
+                arg: Some(arg),
+                text: state.comment_buffer.drain(..).collect(),
+                annotations: std::mem::take(state.annotations),
+                since: None,
+                deprecated: None,
+                is_private: false,
+                line: lineno,
+                end_line: end_lineno,
             });
         }
-    } else if line.starts_with("const ") {
+
+        return Ok(new_mode);
+    } else if let Some(declaration) = line.strip_prefix("const ") {
         let mut name = String::new();
         let mut value_type = None;
         let mut assignment = None;
@@ -668,7 +1828,7 @@ fn parse_class_content(
         parse_assignment(
             filename,
             lineno,
-            &line[6..],
+            declaration,
             &mut name,
             &mut value_type,
             &mut assignment,
@@ -676,7 +1836,12 @@ fn parse_class_content(
             &mut getter,
         )?;
 
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
+        if let Some(ref assignment) = assignment {
+            find_dependencies(frame, assignment);
+        }
+
+        if (!name.starts_with("_") || options.show_prefixed.shows(EntryType::CONST))
+            && state.override_visibility.unwrap_or(true)
         {
             frame.constants.push(Symbol {
                 name: name,
@@ -686,32 +1851,47 @@ fn parse_class_content(
                     setter: setter,
                     getter: getter,
                 })),
-                text: comment_buffer.drain(..).collect(),
+                text: state.comment_buffer.drain(..).collect(),
+                annotations: std::mem::take(state.annotations),
+                since: None,
+                deprecated: None,
+                is_private: false,
+                line: lineno,
+                end_line: end_lineno,
             });
         }
-    } else if line.starts_with("export") {
-        let pos = line.find(" var ");
-        let open_paren = line.find('(');
-        let close_paren = line.find(')');
-        if pos.is_none() {
-            return Err(format!("Invalid syntax: {}", line));
-        }
-
-        let pos = pos.unwrap();
-        let export_type = match (open_paren, close_paren) {
-            (Some(open), Some(close)) if open < close && close < pos => {
-                let mut arg_iterator = line[open + 1..close]
-                    .split(',')
+    } else if let Some(declaration) = line.strip_prefix("export") {
+        let pos =
+            find(filename, lineno, declaration, " var ", &mut Vec::new())?.ok_or_else(|| {
+                ParseError::new(filename, lineno, format!("invalid syntax '{}'", line))
+            })?;
+
+        let hint = declaration[..pos].trim();
+        let export_type = if hint.is_empty() {
+            None
+        } else if hint.starts_with('(') && hint.ends_with(')') {
+            let mut arg_iterator =
+                split_top_level(filename, lineno, &hint[1..hint.len() - 1], ',')?
+                    .into_iter()
                     .map(|x| x.trim().to_string());
-                let export_type = arg_iterator.next();
-                let options = arg_iterator.collect::<Vec<_>>();
-                Some((export_type.unwrap(), options))
-            }
-            (Some(x), Some(y)) if x > pos && y > pos => None,
-            (Some(x), None) if x > pos => None,
-            (None, Some(x)) if x > pos => None,
-            (None, None) => None,
-            _ => return Err(format!("Invalid syntax: {}", line)),
+            let export_type = arg_iterator
+                .next()
+                .filter(|x| !x.is_empty())
+                .ok_or_else(|| {
+                    ParseError::new(
+                        filename,
+                        lineno,
+                        format!("export hint without a type '{}'", hint),
+                    )
+                })?;
+            let options = arg_iterator.collect::<Vec<_>>();
+            Some((export_type, options))
+        } else {
+            return Err(ParseError::new(
+                filename,
+                lineno,
+                format!("invalid export hint '{}'", hint),
+            ));
         };
 
         let mut name = String::new();
@@ -722,7 +1902,7 @@ fn parse_class_content(
         parse_assignment(
             filename,
             lineno,
-            &line[pos + 5..],
+            &declaration[pos + 5..],
             &mut name,
             &mut value_type,
             &mut assignment,
@@ -730,8 +1910,12 @@ fn parse_class_content(
             &mut getter,
         )?;
 
-        if (name.starts_with("_") && !settings.show_prefixed)
-            || !override_visibility.unwrap_or(true)
+        if let Some(ref assignment) = assignment {
+            find_dependencies(frame, assignment);
+        }
+
+        if (name.starts_with("_") && !options.show_prefixed.shows(EntryType::EXPORT))
+            || !state.override_visibility.unwrap_or(true)
         {
             return Ok(None);
         }
@@ -749,167 +1933,329 @@ fn parse_class_content(
                 assignment: assignment,
                 setter: setter,
                 getter: getter,
+                export_hint: None,
             })),
-            text: comment_buffer.drain(..).collect(),
+            text: state.comment_buffer.drain(..).collect(),
+            annotations: std::mem::take(state.annotations),
+            since: None,
+            deprecated: None,
+            is_private: false,
+            line: lineno,
+            end_line: end_lineno,
         });
-    } else if line.starts_with("enum") {
-        let pos = line.find('{');
+    } else if let Some(declaration) = line.strip_prefix("enum") {
+        let pos = find(filename, lineno, declaration, '{', &mut Vec::new())?;
         if pos.is_none() {
-            return Err(format!("Invalid Syntax: {}", line));
+            return Err(ParseError::new(
+                filename,
+                lineno,
+                format!("invalid syntax '{}'", line),
+            ));
         }
 
         let pos = pos.unwrap();
-        let enum_name = line[5..pos].trim().to_string();
+        let enum_name = declaration[..pos].trim().to_string();
 
-        if (enum_name.starts_with("_") && !settings.show_prefixed)
-            || !override_visibility.unwrap_or(true)
+        if (enum_name.starts_with("_") && !options.show_prefixed.shows(EntryType::ENUM))
+            || !state.override_visibility.unwrap_or(true)
         {
             return Ok(None);
         }
 
-        let mut enum_frame = EnumFrame::default();
-        let end = line.find('}');
+        let mut enum_frame = EnumFrame {
+            text: state.comment_buffer.drain(..).collect(),
+            line: lineno,
+            ..EnumFrame::default()
+        };
+        let values = &declaration[pos + 1..];
+        let end = find(filename, lineno, values, '}', &mut vec!['{'])?;
         let slice = match end {
-            Some(x) => &line[pos + 1..x],
-            None => &line[pos + 1..],
+            Some(x) => &values[..x],
+            None => values,
         };
 
-        parse_enum(
-            settings,
-            parsing_mode,
-            slice,
-            &mut enum_frame,
-            override_visibility,
-            comment_buffer,
-        )?;
+        parse_enum(state, parsing_mode, Some(frame), slice, &mut enum_frame)?;
 
         if end.is_some() {
             frame.enums.push(Symbol {
                 name: enum_name,
                 arg: Some(SymbolArgs::EnumArgs(enum_frame.values)),
-                text: comment_buffer.drain(..).collect(),
+                text: enum_frame.text,
+                annotations: std::mem::take(state.annotations),
+                since: None,
+                deprecated: None,
+                is_private: false,
+                line: lineno,
+                end_line: end_lineno,
             });
         } else {
             return Ok(Some(Mode::Enum(enum_name, enum_frame)));
         }
+    } else if options.warn_unrecognized
+        && !UNDOCUMENTED_STATEMENTS.contains(&line)
+        // The root frame gets the lines of function bodies as well, only unindented ones are declarations
+        && (indent == 0 || !parsing_mode.is_empty())
+    {
+        crate::print_warning(&format!(
+            "{}, line {}: Unrecognized declaration '{}'",
+            filename, lineno, line
+        ));
     }
 
     Ok(None)
 }
 
-enum MatchType {
-    FAILURE,
-    MATCH,
-    FINISHED,
-}
-
 trait Predicate {
-    fn into_matcher(self) -> Box<dyn Matcher>;
+    fn is_prefix_of(&self, s: &str) -> bool;
+
+    /// Whether the pattern is found at the byte offset `i` of the line
+    fn matches(&self, line: &str, i: usize) -> bool {
+        self.is_prefix_of(&line[i..])
+    }
 }
 
 impl Predicate for char {
-    fn into_matcher(self) -> Box<dyn Matcher> {
-        Box::new(self)
+    fn is_prefix_of(&self, s: &str) -> bool {
+        s.starts_with(*self)
     }
 }
 
 impl Predicate for &str {
-    fn into_matcher(self) -> Box<dyn Matcher> {
-        Box::new(StringMatcher {
-            index: 0,
-            chars: self.chars().collect(),
-            len: self.len(),
-        })
+    fn is_prefix_of(&self, s: &str) -> bool {
+        s.starts_with(*self)
     }
 }
 
-trait Matcher {
-    fn matches(&mut self, c: char) -> MatchType;
+/// A keyword, which is separated from the rest of the line by any amount of whitespace.
+/// It matches at the whitespace before the keyword.
+struct Keyword(&'static str);
+
+impl Predicate for Keyword {
+    fn is_prefix_of(&self, s: &str) -> bool {
+        let rest = s.trim_start();
+        rest.len() < s.len()
+            && rest.starts_with(self.0)
+            && rest[self.0.len()..]
+                .chars()
+                .next()
+                .map_or(true, char::is_whitespace)
+    }
 }
 
-struct StringMatcher {
-    index: usize,
-    len: usize,
-    chars: Vec<char>,
-}
+/// The '=' of an assignment, which isn't part of a comparison like `==`, `!=`, `<=` or `>=`.
+/// The '=' of an inferred assignment `:=` is found as well.
+struct Assignment;
 
-impl Matcher for char {
-    fn matches(&mut self, c: char) -> MatchType {
-        if c == *self {
-            MatchType::FINISHED
-        } else {
-            MatchType::FAILURE
-        }
+impl Predicate for Assignment {
+    fn is_prefix_of(&self, s: &str) -> bool {
+        s.starts_with('=') && !s[1..].starts_with('=')
     }
-}
 
-impl Matcher for StringMatcher {
-    fn matches(&mut self, c: char) -> MatchType {
-        if self.index > self.len || self.chars[self.index] != c {
-            self.index = 0;
-            MatchType::FAILURE
-        } else if self.index == self.len - 1 && self.chars[self.index] == c {
-            MatchType::FINISHED
-        } else {
-            self.index += 1;
-            MatchType::MATCH
-        }
+    fn matches(&self, line: &str, i: usize) -> bool {
+        self.is_prefix_of(&line[i..]) && !line[..i].ends_with(['=', '!', '<', '>'])
     }
 }
 
+/// Searches for the pattern outside of strings, keeping track of the open brackets.
+/// The result is a byte offset, so it is always safe to slice `s` with it, even if
+/// the line contains multi-byte characters.
 fn find(
     filename: &str,
     lineno: u32,
     s: &str,
     p: impl Predicate,
     parentheses: &mut Vec<char>,
-) -> Result<Option<usize>, String> {
+) -> Result<Option<usize>, ParseError> {
+    let mut result = None;
+    scan(filename, lineno, s, parentheses, |i| {
+        if p.matches(s, i) {
+            result = Some(i);
+        }
+        result.is_some()
+    })?;
+
+    Ok(result)
+}
+
+/// Searches for the first occurrence of every pattern in a single pass, see `find`
+fn find_each<const N: usize>(
+    filename: &str,
+    lineno: u32,
+    s: &str,
+    patterns: [&dyn Predicate; N],
+    parentheses: &mut Vec<char>,
+) -> Result<[Option<usize>; N], ParseError> {
+    let mut result = [None; N];
+    scan(filename, lineno, s, parentheses, |i| {
+        for (pattern, pos) in patterns.iter().zip(result.iter_mut()) {
+            if pos.is_none() && pattern.matches(s, i) {
+                *pos = Some(i);
+            }
+        }
+        result.iter().all(|pos| pos.is_some())
+    })?;
+
+    Ok(result)
+}
+
+/// Calls `f` with the byte offset of every character outside of strings, until it returns true
+fn scan(
+    filename: &str,
+    lineno: u32,
+    s: &str,
+    parentheses: &mut Vec<char>,
+    mut f: impl FnMut(usize) -> bool,
+) -> Result<(), ParseError> {
     let mut single_string = false;
     let mut double_string = false;
+    let mut escaped = false;
 
-    let chars = s.chars().collect::<Vec<_>>();
-    let len = chars.len();
+    // A triple quoted string may span several lines, it's kept open on the bracket stack
+    let mut triple_string = match parentheses.last() {
+        Some(&x) if x == '"' || x == '\'' => parentheses.pop(),
+        _ => None,
+    };
+    let mut skip = 0;
 
-    let mut matcher = p.into_matcher();
-    for i in 0..len {
-        if !single_string && !double_string {
-            let mut j = 0;
-            while i + j < len {
-                let c = chars[i + j];
-                j += 1;
+    for (i, c) in s.char_indices() {
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
 
-                match matcher.as_mut().matches(c) {
-                    MatchType::FAILURE => break,
-                    MatchType::FINISHED => return Ok(Some(i)),
-                    _ => (),
-                }
+        if let Some(quote) = triple_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if s.as_bytes()[i..].starts_with(&[quote as u8; 3]) {
+                triple_string = None;
+                skip = 2;
             }
+            continue;
+        }
+
+        if !single_string && !double_string && f(i) {
+            return Ok(());
+        }
+
+        if !single_string
+            && !double_string
+            && (s[i..].starts_with("\"\"\"") || s[i..].starts_with("'''"))
+        {
+            triple_string = Some(c);
+            skip = 2;
+            continue;
         }
 
-        match chars[i] {
+        let error = |message| {
+            Err(ParseError::new(filename, lineno, message)
+                .with_kind(ParseErrorKind::MismatchedParentheses)
+                .with_column(s[..i].chars().count() + 1))
+        };
+
+        match c {
+            // Escape sequences can only appear inside of strings
+            _ if escaped => escaped = false,
+            '\\' if single_string || double_string => escaped = true,
+            '"' if double_string => double_string = false,
+            '\'' if single_string => single_string = false,
             '"' if !single_string => double_string = true,
             '\'' if !double_string => single_string = true,
+            _ if single_string || double_string => (),
             x if x == '(' || x == '[' || x == '{' => parentheses.push(x),
             ')' => match parentheses.pop() {
                 Some('(') => (),
-                Some(_) => return Err(format!("Failed to parse {}, line {}: Closing parentheses does not match opening parentheses", filename, lineno)),
-                None => return Err(format!("Failed to parse {}, line {}: extra ')'", filename, lineno))
-            }
+                Some(_) => return error("Closing parentheses does not match opening parentheses"),
+                None => return error("extra ')'"),
+            },
             ']' => match parentheses.pop() {
                 Some('[') => (),
-                Some(_) => return Err(format!("Failed to parse {}, line {}: Closing parentheses does not match opening parentheses", filename, lineno)),
-                None => return Err(format!("Failed to parse {}, line {}: extra ']'", filename, lineno))
-            }
+                Some(_) => return error("Closing parentheses does not match opening parentheses"),
+                None => return error("extra ']'"),
+            },
             '}' => match parentheses.pop() {
                 Some('{') => (),
-                Some(_) => return Err(format!("Failed to parse {}, line {}: Closing parentheses does not match opening parentheses", filename, lineno)),
-                None => return Err(format!("Failed to parse {}, line {}: extra '}}'", filename, lineno))
-            }
+                Some(_) => return error("Closing parentheses does not match opening parentheses"),
+                None => return error("extra '}'"),
+            },
             _ => (),
         }
     }
 
-    Ok(None)
+    if let Some(quote) = triple_string {
+        parentheses.push(quote);
+    }
+
+    Ok(())
+}
+
+/// Splits the string at every separator, which is neither inside of a string nor inside brackets
+fn split_top_level<'a>(
+    filename: &str,
+    lineno: u32,
+    s: &'a str,
+    separator: char,
+) -> Result<Vec<&'a str>, ParseError> {
+    let mut parts = Vec::new();
+    let mut parentheses = Vec::new();
+
+    let mut start = 0;
+    let mut pos = 0;
+    while let Some(offset) = find(filename, lineno, &s[pos..], separator, &mut parentheses)? {
+        if parentheses.is_empty() {
+            parts.push(&s[start..pos + offset]);
+            start = pos + offset + separator.len_utf8();
+        }
+        pos += offset + separator.len_utf8();
+    }
+    parts.push(&s[start..]);
+
+    Ok(parts)
+}
+
+/// Splits the line into the statements separated by ';', except for the body of a block, which
+/// follows the ':' of its header on the same line, like `func f(): a(); b()`
+fn split_statements<'a>(
+    filename: &str,
+    lineno: u32,
+    line: &'a str,
+) -> Result<Vec<&'a str>, ParseError> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    for statement in split_top_level(filename, lineno, line, ';')? {
+        if opens_block(filename, lineno, statement)? {
+            statements.push(&line[start..]);
+            break;
+        }
+        statements.push(statement);
+        start += statement.len() + ';'.len_utf8();
+    }
+
+    Ok(statements)
+}
+
+/// Whether the statement is the header of a block, like `func f():`, `if x:` or a variable
+/// with a lambda `var f = func():`
+fn opens_block(filename: &str, lineno: u32, statement: &str) -> Result<bool, ParseError> {
+    let keyword = statement
+        .split_whitespace()
+        .find(|x| !x.starts_with('@') && *x != "static")
+        .and_then(|x| x.split(['(', ':']).next());
+    let header = match keyword {
+        Some("func" | "class" | "if" | "elif" | "else" | "for" | "while" | "match") => statement,
+        Some("var" | "const") => {
+            match find(filename, lineno, statement, Assignment, &mut Vec::new())? {
+                Some(pos) if statement[pos + 1..].trim_start().starts_with("func") => {
+                    &statement[pos + 1..]
+                }
+                _ => return Ok(false),
+            }
+        }
+        _ => return Ok(false),
+    };
+
+    Ok(find(filename, lineno, header, ':', &mut Vec::new())?.is_some())
 }
 
 fn parse_assignment(
@@ -921,35 +2267,24 @@ fn parse_assignment(
     assignment: &mut Option<String>,
     setter: &mut Option<String>,
     getter: &mut Option<String>,
-) -> Result<(), String> {
-    let assignment_pos = find(filename, lineno, line, '=', &mut Vec::new())?;
-    let type_pos = find(filename, lineno, line, ':', &mut Vec::new())?;
-    let setget_pos = find(filename, lineno, line, " setget ", &mut Vec::new())?;
+) -> Result<(), ParseError> {
+    let [assignment_pos, type_pos, setget_pos] = find_each(
+        filename,
+        lineno,
+        line,
+        [&Assignment, &':', &Keyword("setget")],
+        &mut Vec::new(),
+    )?;
+
+    // A ':' after the assignment is part of the value (e.g. a lambda), not the type
+    let type_pos = match (assignment_pos, type_pos) {
+        (Some(apos), Some(tpos)) if tpos > apos => None,
+        (_, tpos) => tpos,
+    };
 
     match (assignment_pos, type_pos, setget_pos) {
         (Some(apos), Some(tpos), Some(spos)) if tpos < apos && apos < spos => {
-            let setget = &line[spos + 7..]
-                .split(',')
-                .map(|x| x.trim())
-                .collect::<Vec<_>>();
-            match setget.as_slice() {
-                ["", get] => {
-                    getter.get_or_insert(get.to_string());
-                }
-                [set] | [set, ""] => {
-                    setter.get_or_insert(set.to_string());
-                }
-                [set, get] => {
-                    setter.get_or_insert(set.to_string());
-                    getter.get_or_insert(get.to_string());
-                }
-                _ => {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: invalid syntax '{}'",
-                        filename, lineno, line
-                    ))
-                }
-            }
+            parse_setget(filename, lineno, line, spos, setter, getter)?;
             name.clone_from(&line[..tpos].trim().to_string());
             value_type.get_or_insert(line[tpos + 1..apos].trim().to_string());
             assignment.get_or_insert(line[apos + 1..spos].trim().to_string());
@@ -960,28 +2295,7 @@ fn parse_assignment(
             assignment.get_or_insert(line[apos + 1..].trim().to_string());
         }
         (Some(apos), None, Some(spos)) if apos < spos => {
-            let setget = &line[spos + 7..]
-                .split(',')
-                .map(|x| x.trim())
-                .collect::<Vec<_>>();
-            match setget.as_slice() {
-                ["", get] => {
-                    getter.get_or_insert(get.to_string());
-                }
-                [set] | [set, ""] => {
-                    setter.get_or_insert(set.to_string());
-                }
-                [set, get] => {
-                    setter.get_or_insert(set.to_string());
-                    getter.get_or_insert(get.to_string());
-                }
-                _ => {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: invalid syntax '{}'",
-                        filename, lineno, line
-                    ))
-                }
-            }
+            parse_setget(filename, lineno, line, spos, setter, getter)?;
             name.clone_from(&line[..apos].trim().to_string());
             assignment.get_or_insert(line[apos + 1..spos].trim().to_string());
         }
@@ -990,28 +2304,7 @@ fn parse_assignment(
             assignment.get_or_insert(line[apos + 1..].trim().to_string());
         }
         (None, Some(tpos), Some(spos)) if tpos < spos => {
-            let setget = &line[spos + 7..]
-                .split(',')
-                .map(|x| x.trim())
-                .collect::<Vec<_>>();
-            match setget.as_slice() {
-                ["", get] => {
-                    getter.get_or_insert(get.to_string());
-                }
-                [set] | [set, ""] => {
-                    setter.get_or_insert(set.to_string());
-                }
-                [set, get] => {
-                    setter.get_or_insert(set.to_string());
-                    getter.get_or_insert(get.to_string());
-                }
-                _ => {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: invalid syntax '{}'",
-                        filename, lineno, line
-                    ))
-                }
-            }
+            parse_setget(filename, lineno, line, spos, setter, getter)?;
             name.clone_from(&line[..tpos].trim().to_string());
             value_type.get_or_insert(line[tpos + 1..spos].trim().to_string());
         }
@@ -1020,51 +2313,79 @@ fn parse_assignment(
             value_type.get_or_insert(line[tpos + 1..].trim().to_string());
         }
         (None, None, Some(spos)) => {
-            let setget = &line[spos + 7..]
-                .split(',')
-                .map(|x| x.trim())
-                .collect::<Vec<_>>();
-            match setget.as_slice() {
-                ["", get] => {
-                    getter.get_or_insert(get.to_string());
-                }
-                [set] | [set, ""] => {
-                    setter.get_or_insert(set.to_string());
-                }
-                [set, get] => {
-                    setter.get_or_insert(set.to_string());
-                    getter.get_or_insert(get.to_string());
-                }
-                _ => {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: invalid syntax '{}'",
-                        filename, lineno, line
-                    ))
-                }
-            }
+            parse_setget(filename, lineno, line, spos, setter, getter)?;
             name.clone_from(&line[..spos].trim().to_string());
         }
         (None, None, None) => {
             name.clone_from(&line.trim().to_string());
         }
         _ => {
-            return Err(format!(
-                "Failed to parse {}, line {}: invalid syntax '{}'",
-                filename, lineno, line
+            return Err(ParseError::new(
+                filename,
+                lineno,
+                format!("invalid syntax '{}'", line),
             ))
         }
     };
 
+    // The type is inferred when using ':=', so there is nothing to show
+    if value_type.as_deref() == Some("") {
+        *value_type = None;
+    }
+
+    Ok(())
+}
+
+/// Parses the setter and getter after the `setget` keyword, which follows the whitespace at spos
+fn parse_setget(
+    filename: &str,
+    lineno: u32,
+    line: &str,
+    spos: usize,
+    setter: &mut Option<String>,
+    getter: &mut Option<String>,
+) -> Result<(), ParseError> {
+    let setget = line[spos..].trim_start();
+    let setget = setget
+        .strip_prefix("setget")
+        .unwrap_or(setget)
+        .split(',')
+        .map(|x| x.trim())
+        .collect::<Vec<_>>();
+    match setget.as_slice() {
+        ["", get] => {
+            getter.get_or_insert(get.to_string());
+        }
+        [set] | [set, ""] => {
+            setter.get_or_insert(set.to_string());
+        }
+        [set, get] => {
+            setter.get_or_insert(set.to_string());
+            getter.get_or_insert(get.to_string());
+        }
+        _ => {
+            return Err(ParseError::new(
+                filename,
+                lineno,
+                format!("invalid syntax '{}'", line),
+            ))
+        }
+    }
+
     Ok(())
 }
 
 fn parse_function(
+    filename: &str,
+    lineno: u32,
     line: &str,
     name: &mut String,
     arguments: &mut Vec<FunctionArgument>,
     super_arguments: &mut Option<Vec<FunctionArgument>>,
     return_type: &mut Option<String>,
-) -> Result<(), String> {
+) -> Result<(), ParseError> {
+    let invalid = || ParseError::new(filename, lineno, format!("Invalid syntax: {}", line));
+
     #[derive(PartialEq)]
     enum SIDE {
         Name,
@@ -1073,25 +2394,98 @@ fn parse_function(
         Invalid,
     }
 
-    let mut finished = false;
-
     let mut depth = 0;
     let mut parentheses_count = 0;
+    // Brackets opened inside a default value, like `Vector2(1, 2)` or `[1, 2]`
+    let mut value_depth = 0;
+    // Brackets opened inside a type, like `Dictionary[String, int]`
+    let mut type_depth = 0;
     let mut side = SIDE::Name;
     let mut last_char = None;
 
+    let mut string = None;
+    let mut escaped = false;
+
     let mut current_argument_name = String::new();
     let mut current_argument_type = None;
     let mut current_argument_assignment = None;
     for c in line.chars() {
+        // String literals are copied verbatim, including whitespace and separators
+        if string.is_some() || ((c == '"' || c == '\'') && depth > 0) {
+            match side {
+                SIDE::Assignment => current_argument_assignment
+                    .get_or_insert(String::new())
+                    .push(c),
+                SIDE::Type => current_argument_type.get_or_insert(String::new()).push(c),
+                _ => current_argument_name.push(c),
+            }
+
+            if escaped {
+                escaped = false;
+            } else if string.is_none() {
+                string = Some(c);
+            } else if c == '\\' {
+                escaped = true;
+            } else if string == Some(c) {
+                string = None;
+            }
+
+            last_char = Some(c);
+            continue;
+        }
+
+        // Nested values are copied verbatim, they don't end the argument
+        if side == SIDE::Assignment && depth > 0 {
+            let nested = match c {
+                '(' | '[' | '{' => {
+                    value_depth += 1;
+                    true
+                }
+                ')' | ']' | '}' if value_depth > 0 => {
+                    value_depth -= 1;
+                    true
+                }
+                _ => value_depth > 0,
+            };
+            if nested {
+                current_argument_assignment
+                    .get_or_insert(String::new())
+                    .push(c);
+                last_char = Some(c);
+                continue;
+            }
+        }
+
+        // Types of typed collections are copied verbatim as well
+        if side == SIDE::Type {
+            let nested = match c {
+                '[' => {
+                    type_depth += 1;
+                    true
+                }
+                ']' if type_depth > 0 => {
+                    type_depth -= 1;
+                    true
+                }
+                _ => type_depth > 0,
+            };
+            if nested {
+                match depth {
+                    0 => return_type.get_or_insert(String::new()).push(c),
+                    _ => current_argument_type.get_or_insert(String::new()).push(c),
+                }
+                last_char = Some(c);
+                continue;
+            }
+        }
+
         match c {
             x if x.is_whitespace() => (),
-            _ if finished => return Err(format!("Invalid syntax: {}", line)),
             '(' => {
                 if parentheses_count < 2 {
                     depth += 1
                 } else {
-                    return Err(format!("Invalid syntax: {}", line));
+                    return Err(invalid());
                 }
             }
             ')' => {
@@ -1120,7 +2514,7 @@ fn parse_function(
                             current_argument_type = None;
                             current_argument_assignment = None;
                         }
-                        _ => return Err(format!("Invalid syntax: {}", line)),
+                        _ => return Err(invalid()),
                     }
                 }
                 if depth == 0 {
@@ -1129,8 +2523,13 @@ fn parse_function(
                 }
             }
             '.' if depth == 0 && name == "_init" && parentheses_count == 1 => side = SIDE::Name,
-            '.' if depth == 0 => return Err(format!("Invalid syntax: {}", line)),
-            ':' if depth == 0 => finished = true,
+            '.' if depth == 0 => return Err(invalid()),
+            // The body may follow on the same line, it isn't documented
+            ':' if depth == 0 => break,
+            // Part of the default value, like in the node path `$Sprite:texture`
+            ':' if side == SIDE::Assignment => current_argument_assignment
+                .get_or_insert(String::new())
+                .push(c),
             ':' => {
                 side = SIDE::Type;
                 current_argument_type = Some(String::new());
@@ -1159,18 +2558,25 @@ fn parse_function(
                         current_argument_type = None;
                         current_argument_assignment = None;
                     }
-                    _ => return Err(format!("Invalid syntax: {}", line)),
+                    _ => return Err(invalid()),
                 };
+                side = SIDE::Name;
             }
             '-' if depth == 0 => (),
             '>' => {
                 if last_char == Some('-') {
                     side = SIDE::Type;
                 } else {
-                    return Err(format!("Invalid syntax: {}", line));
+                    return Err(invalid());
+                }
+            }
+            '=' if depth == 1 && side != SIDE::Assignment => {
+                // ':=' infers the type from the default value
+                if current_argument_type.as_deref() == Some("") {
+                    current_argument_type = None;
                 }
+                side = SIDE::Assignment
             }
-            '=' if depth == 1 && side != SIDE::Assignment => side = SIDE::Assignment,
             x if depth == 0 && side == SIDE::Name => name.push(x),
             x if depth == 0 && side == SIDE::Type => {
                 return_type.get_or_insert(String::new()).push(x)
@@ -1185,11 +2591,233 @@ fn parse_function(
             x if side == SIDE::Assignment => current_argument_assignment
                 .get_or_insert(String::new())
                 .push(x),
-            _ if side == SIDE::Invalid => return Err(format!("Invalid syntax: {}", line)),
-            _ => panic!("parse_function: Some case not covered"),
+            _ => return Err(invalid()),
         };
         last_char = Some(c);
     }
 
+    if string.is_some() {
+        return Err(ParseError::new(
+            filename,
+            lineno,
+            format!("Unterminated string: {}", line),
+        ));
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_in(s: &str, p: impl Predicate) -> Option<usize> {
+        find("test.gd", 1, s, p, &mut Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn assignment_skips_comparisons() {
+        assert_eq!(find_in("ok = a == b", Assignment), Some(3));
+        assert_eq!(find_in("z := 1", Assignment), Some(3));
+        assert_eq!(find_in("t = a if b >= c else d", Assignment), Some(2));
+        assert_eq!(find_in("a != b", Assignment), None);
+        assert_eq!(find_in("a <= b", Assignment), None);
+        assert_eq!(find_in("a == b", Assignment), None);
+    }
+
+    #[test]
+    fn find_skips_strings() {
+        assert_eq!(find_in("a = \"b = c\"", '='), Some(2));
+        assert_eq!(find_in("\"a = b\" = c", '='), Some(8));
+        assert_eq!(find_in("'a\\' = b' = c", '='), Some(10));
+        assert_eq!(find_in("\"\"\"a = \"b\" \"\"\" = c", '='), Some(15));
+        assert_eq!(find_in("\"#\" # comment", '#'), Some(4));
+        assert_eq!(find_in("a = 'b'", Keyword("setget")), None);
+        assert_eq!(find_in("äöü = 1", '='), Some(7));
+    }
+
+    #[test]
+    fn find_closes_strings() {
+        // Comments and setget after a string are found, the string ends at its closing quote
+        assert_eq!(find_in("var s = \"a\" # doc", '#'), Some(12));
+        assert_eq!(find_in("var s = 'a' # doc", '#'), Some(12));
+        assert_eq!(find_in("var s = \"a\\\"#\" # doc", '#'), Some(15));
+        assert_eq!(
+            find_in("t = \"x\" setget set_t", Keyword("setget")),
+            Some(7)
+        );
+        assert_eq!(
+            find_in("t = 'it\\'s' setget set_t", Keyword("setget")),
+            Some(11)
+        );
+        assert_eq!(find_in("t = \"a\" + \"b\" = c", '='), Some(2));
+
+        // Brackets inside of strings are text, the ones outside are tracked
+        let mut parentheses = Vec::new();
+        assert_eq!(
+            find("test.gd", 1, "f(\")\", '(' # c", '#', &mut parentheses).unwrap(),
+            Some(11)
+        );
+        assert_eq!(parentheses, vec!['(']);
+        let mut parentheses = Vec::new();
+        assert_eq!(
+            find("test.gd", 1, "f(\"(\") + \"]\" # c", '#', &mut parentheses).unwrap(),
+            Some(13)
+        );
+        assert!(parentheses.is_empty());
+    }
+
+    #[test]
+    fn find_tracks_brackets() {
+        let mut parentheses = Vec::new();
+        assert_eq!(
+            find("test.gd", 1, "f(a, [b", '#', &mut parentheses).unwrap(),
+            None
+        );
+        assert_eq!(parentheses, vec!['(', '[']);
+        assert_eq!(
+            find("test.gd", 2, "c]) # d", '#', &mut parentheses).unwrap(),
+            Some(4)
+        );
+        assert!(parentheses.is_empty());
+
+        let error = find("test.gd", 3, "a = (]", '#', &mut Vec::new()).unwrap_err();
+        assert_eq!(error.column, Some(6));
+        assert!(find("test.gd", 3, "a)", '#', &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn find_keeps_triple_strings_open() {
+        let mut parentheses = Vec::new();
+        assert_eq!(
+            find("test.gd", 1, "a = \"\"\"b = c", '=', &mut Vec::new()).unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            find("test.gd", 1, "\"\"\"b = c", '=', &mut parentheses).unwrap(),
+            None
+        );
+        assert_eq!(parentheses, vec!['"']);
+        assert_eq!(
+            find("test.gd", 2, "d = e\"\"\" = f", '=', &mut parentheses).unwrap(),
+            Some(9)
+        );
+        assert!(parentheses.is_empty());
+    }
+
+    #[test]
+    fn find_each_matches_find() {
+        let lines = [
+            "a: int = 5 setget set_a, get_a",
+            "a = {\"b\": 1} setget set_a",
+            "a := \"x: y = z\"",
+            "a setget , get_a",
+            "a = 1  setget\tset_a",
+            "a = my_setget",
+            "a = func(x): return x",
+            "a",
+        ];
+        for line in lines.iter() {
+            let positions = find_each(
+                "test.gd",
+                1,
+                line,
+                [&'=', &':', &Keyword("setget")],
+                &mut Vec::new(),
+            )
+            .unwrap();
+            assert_eq!(
+                positions,
+                [
+                    find_in(line, '='),
+                    find_in(line, ':'),
+                    find_in(line, Keyword("setget"))
+                ],
+                "{}",
+                line
+            );
+        }
+    }
+
+    fn default_values(line: &str) -> Vec<Option<String>> {
+        let mut name = String::new();
+        let mut arguments = Vec::new();
+        parse_function(
+            "test.gd",
+            1,
+            line,
+            &mut name,
+            &mut arguments,
+            &mut None,
+            &mut None,
+        )
+        .unwrap();
+        arguments.into_iter().map(|x| x.default_value).collect()
+    }
+
+    #[test]
+    fn parse_function_nested_defaults() {
+        assert_eq!(
+            default_values("f(v = Vector2(1, 2), w := 3):"),
+            [Some("Vector2(1, 2)".to_string()), Some("3".to_string())]
+        );
+        assert_eq!(
+            default_values("f(x = clamp(max(a, b), 0, 1), y = [1, (2)], z = {\"a\": f()}):"),
+            [
+                Some("clamp(max(a, b), 0, 1)".to_string()),
+                Some("[1, (2)]".to_string()),
+                Some("{\"a\": f()}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_function_super_arguments() {
+        let mut name = String::new();
+        let mut arguments = Vec::new();
+        let mut super_arguments = None;
+        parse_function(
+            "test.gd",
+            1,
+            "_init(a = Vector2(1, 2)).(a, 1):",
+            &mut name,
+            &mut arguments,
+            &mut super_arguments,
+            &mut None,
+        )
+        .unwrap();
+        assert_eq!(name, "_init");
+        assert_eq!(arguments[0].default_value.as_deref(), Some("Vector2(1, 2)"));
+        assert_eq!(super_arguments.map(|x| x.len()), Some(2));
+    }
+
+    #[test]
+    fn parse_function_typed_collections() {
+        let mut name = String::new();
+        let mut arguments = Vec::new();
+        let mut return_type = None;
+        parse_function(
+            "test.gd",
+            1,
+            "f(a: Dictionary[String, int], b: Array[int] = []) -> Dictionary[String, Array[int]]:",
+            &mut name,
+            &mut arguments,
+            &mut None,
+            &mut return_type,
+        )
+        .unwrap();
+        assert_eq!(name, "f");
+        let types = arguments
+            .iter()
+            .map(|x| x.value_type.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![Some("Dictionary[String, int]"), Some("Array[int]")]
+        );
+        assert_eq!(
+            return_type.as_deref(),
+            Some("Dictionary[String, Array[int]]")
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Matching scripts against the exclude and include patterns the same way on every platform
+
+use glob::{MatchOptions, Pattern};
+use std::path::{Component, Path};
+
+/// Patterns may separate directories with `\` as well, like paths on Windows
+pub fn normalize(pattern: &str) -> String {
+    pattern.replace('\\', "/")
+}
+
+pub fn compile_patterns<S: AsRef<str>>(
+    patterns: &[S],
+    source: &str,
+) -> Result<Vec<Pattern>, String> {
+    patterns
+        .iter()
+        .map(|s| {
+            Pattern::new(&normalize(s.as_ref())).map_err(|e| {
+                format!(
+                    "Couldn't parse pattern '{}' of {}: {}",
+                    s.as_ref(),
+                    source,
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+/// Like the file system, matching ignores the case on Windows
+pub fn match_options() -> MatchOptions {
+    MatchOptions {
+        case_sensitive: !cfg!(windows),
+        ..MatchOptions::new()
+    }
+}
+
+/// Matches the path relative to the input directory, with `/` as separator on every platform.
+/// Patterns may start with `./` or not.
+pub fn matching_pattern<'a>(path: &Path, patterns: &'a [Pattern]) -> Option<&'a Pattern> {
+    matching_pattern_with(path, patterns, match_options())
+}
+
+pub fn matching_pattern_with<'a>(
+    path: &Path,
+    patterns: &'a [Pattern],
+    options: MatchOptions,
+) -> Option<&'a Pattern> {
+    let relative_path = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(x) => Some(x.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    matching_path(&relative_path, patterns, options)
+}
+
+/// Matches a path relative to the input directory, which is separated by `/` or `\`
+fn matching_path<'a>(
+    relative_path: &str,
+    patterns: &'a [Pattern],
+    options: MatchOptions,
+) -> Option<&'a Pattern> {
+    let relative_path = normalize(relative_path);
+    let relative_path = relative_path.trim_start_matches("./");
+    let dotted_path = format!("./{}", relative_path);
+
+    patterns.iter().find(|pattern| {
+        pattern.matches_with(&dotted_path, options) || pattern.matches_with(relative_path, options)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str, options: MatchOptions) -> bool {
+        let patterns = compile_patterns(&[pattern], "the test").unwrap();
+        matching_path(path, &patterns, options).is_some()
+    }
+
+    #[test]
+    fn separators() {
+        let options = MatchOptions::new();
+        for pattern in &["ui/*.gd", "./ui/*.gd", "ui\\*.gd", ".\\ui\\*.gd"] {
+            for path in &[
+                "ui/button.gd",
+                "ui\\button.gd",
+                "./ui/button.gd",
+                ".\\ui\\button.gd",
+            ] {
+                assert!(matches(pattern, path, options), "{} {}", pattern, path);
+            }
+            assert!(!matches(pattern, "menu/button.gd", options));
+        }
+        assert!(matches("**/*.gd", "ui\\menu\\button.gd", options));
+        assert!(matches("ui/**", "ui\\menu\\button.gd", options));
+    }
+
+    #[test]
+    fn literal_separators() {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        assert!(matches("ui/*.gd", "ui\\button.gd", options));
+        assert!(!matches("ui/*.gd", "ui\\menu\\button.gd", options));
+    }
+
+    #[test]
+    fn case() {
+        let insensitive = MatchOptions {
+            case_sensitive: false,
+            ..MatchOptions::new()
+        };
+        assert!(matches("ui/*.gd", "UI\\Button.GD", insensitive));
+        assert!(!matches("ui/*.gd", "UI\\Button.GD", MatchOptions::new()));
+        assert_eq!(match_options().case_sensitive, !cfg!(windows));
+    }
+
+    #[test]
+    fn paths() {
+        let patterns = compile_patterns(&["ui\\*.gd"], "the test").unwrap();
+        let path = Path::new(".").join("ui").join("button.gd");
+        assert!(matching_pattern(&path, &patterns).is_some());
+        assert!(matching_pattern(Path::new("button.gd"), &patterns).is_none());
+    }
+}
@@ -0,0 +1,59 @@
+//! The `project.godot` of a Godot project, which the documented scripts belong to
+
+use std::path::{Path, PathBuf};
+
+/// The directory with the `project.godot`, the directory itself or one of its ancestors
+pub fn find_project(dir: &Path) -> Option<PathBuf> {
+    let dir = dir.canonicalize().ok()?;
+    dir.ancestors()
+        .find(|x| x.join("project.godot").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// The autoloads in the `[autoload]` section as pairs of their global name and `res://` path.
+/// Godot writes the paths in quotes, prefixed with `*` if the singleton is enabled.
+pub fn parse_autoloads(source: &str) -> Vec<(String, String)> {
+    let mut autoloads = Vec::new();
+    let mut in_section = false;
+    for line in source.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line == "[autoload]";
+            continue;
+        }
+        if !in_section || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, path)) = line.split_once('=') else {
+            continue;
+        };
+        let path = path.trim().trim_matches('"').trim_start_matches('*');
+        if !name.trim().is_empty() && !path.is_empty() {
+            autoloads.push((name.trim().to_string(), path.to_string()));
+        }
+    }
+    autoloads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autoloads() {
+        let source = "config_version=5\n\n[application]\n\nconfig/name=\"Game\"\n\n\
+            [autoload]\n\nGameState=\"*res://autoload/game_state.gd\"\n\
+            ; disabled singleton\nMusic=\"res://music.tscn\"\nEvents=*res://events.gd\n\n\
+            [display]\n\nwindow/size/viewport_width=640\n";
+        assert_eq!(
+            parse_autoloads(source),
+            vec![
+                (
+                    "GameState".to_string(),
+                    "res://autoload/game_state.gd".to_string()
+                ),
+                ("Music".to_string(), "res://music.tscn".to_string()),
+                ("Events".to_string(), "res://events.gd".to_string()),
+            ]
+        );
+    }
+}
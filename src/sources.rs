@@ -0,0 +1,94 @@
+//! Where every setting was taken from, as printed by `--print-config`
+
+use serde::Serialize;
+
+/// The source, which provided the value of a setting
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+    Cli,
+    Env,
+    Config,
+    Default,
+}
+
+#[derive(Debug, Serialize)]
+struct Setting {
+    name: &'static str,
+    source: Origin,
+    // TOML writes tables after plain values, so the value, which may be one, comes last
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<serde_json::Value>,
+}
+
+/// The resolved settings with their origin, in the order they were resolved
+#[derive(Debug, Default, Serialize)]
+pub struct Sources {
+    settings: Vec<Setting>,
+}
+
+impl Sources {
+    /// Takes the value of the first source, which provides one: the command line, the
+    /// environment or the config
+    pub fn pick<T: Serialize>(
+        &mut self,
+        name: &'static str,
+        (cli, env): (Option<T>, Option<T>),
+        config: Option<T>,
+    ) -> Option<T> {
+        let (origin, value) = match (cli, env, config) {
+            (Some(value), _, _) => (Origin::Cli, Some(value)),
+            (None, Some(value), _) => (Origin::Env, Some(value)),
+            (None, None, Some(value)) => (Origin::Config, Some(value)),
+            (None, None, None) => (Origin::Default, None),
+        };
+        self.record(name, origin, &value);
+        value
+    }
+
+    /// Like `pick`, but falls back to the default value, which is computed only if it's needed
+    pub fn pick_or_else<T: Serialize>(
+        &mut self,
+        name: &'static str,
+        values: (Option<T>, Option<T>),
+        config: Option<T>,
+        default: impl FnOnce() -> T,
+    ) -> T {
+        match self.pick(name, values, config) {
+            Some(value) => value,
+            None => {
+                let value = default();
+                self.settings.last_mut().unwrap().value = serde_json::to_value(&value).ok();
+                value
+            }
+        }
+    }
+
+    pub fn pick_or<T: Serialize>(
+        &mut self,
+        name: &'static str,
+        values: (Option<T>, Option<T>),
+        config: Option<T>,
+        default: T,
+    ) -> T {
+        self.pick_or_else(name, values, config, || default)
+    }
+
+    /// Records a setting, which was resolved in another way, like the patterns of excludes,
+    /// which are collected from several sources
+    pub fn record<T: Serialize>(&mut self, name: &'static str, origin: Origin, value: &T) {
+        self.settings.push(Setting {
+            name,
+            source: origin,
+            value: serde_json::to_value(value).ok().filter(|x| !x.is_null()),
+        });
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|e| format!("Failed to print the config as TOML: {}", e))
+    }
+}
@@ -0,0 +1,664 @@
+use godotdoc::backend::doxygenbackend::DoxygenBackend;
+use godotdoc::backend::markdownbackend::MarkdownBackend;
+use godotdoc::backend::{
+    group_pages, inheritance_tree, split_words, Backend, BackendError, CalloutStyle, GroupBy,
+    IndexPage, InheritedMembers, PrivateSection,
+};
+use godotdoc::{
+    parse_str, DocumentationData, InheritedClass, Override, ParseOptions, ShowPrefixed, SymbolArgs,
+};
+
+fn generate(backend: &dyn Backend, source: &str) -> String {
+    let data = parse_str("player.gd", source, &ParseOptions::default()).unwrap();
+    write_output(backend, data)
+}
+
+/// The Markdown backend with the default settings
+fn markdown() -> MarkdownBackend {
+    markdown_with(None, PrivateSection::None, CalloutStyle::Plain)
+}
+
+fn markdown_with(
+    source_url_template: Option<String>,
+    private_section: PrivateSection,
+    callout_style: CalloutStyle,
+) -> MarkdownBackend {
+    MarkdownBackend::new(
+        true,
+        0,
+        100,
+        source_url_template,
+        private_section,
+        callout_style,
+        InheritedMembers::None,
+    )
+}
+
+fn write_output(backend: &dyn Backend, data: DocumentationData) -> String {
+    let mut output = Vec::new();
+    backend.generate_output(data, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// A file system, which is full
+struct FullDisk;
+
+impl std::io::Write for FullDisk {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "No space left",
+        ))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn write_errors() {
+    let backends: [&dyn Backend; 2] = [&markdown(), &DoxygenBackend::new()];
+    for backend in backends {
+        let data = parse_str("player.gd", "var x\n", &ParseOptions::default()).unwrap();
+        match backend.generate_output(data, &mut FullDisk) {
+            Err(BackendError::Io(error)) => assert_eq!(error.to_string(), "No space left"),
+            result => panic!("Expected a write error, got {:?}", result),
+        }
+    }
+
+    let error = BackendError::UnsupportedBackend {
+        name: "markdwn".to_string(),
+        available: vec!["markdown".to_string(), "doxygen".to_string()],
+        suggestion: Some("markdown".to_string()),
+    };
+    assert_eq!(
+        error.to_string(),
+        "Unsupported backend 'markdwn', expected markdown, doxygen or all, did you mean 'markdown'?"
+    );
+}
+
+#[test]
+fn doxygen_stubs() {
+    let output = generate(
+        &DoxygenBackend::new(),
+        "class_name Player\nextends Node\n\n## Moves the player\n## @param direction Where to go\nfunc move(direction: Vector2, speed := 2.0) -> bool:\n\treturn true\n\nclass Inner:\n\tvar x: int\n",
+    );
+
+    assert!(output.contains("class Player : public Node {"));
+    assert!(output.contains("     * \\fn bool move(Vector2 direction, Variant speed)\n     * Moves the player\n     * @param direction Where to go\n     * \\param speed Defaults to `2.0`\n     * \\return bool\n"));
+    assert!(output.contains("    bool move(Vector2 direction, Variant speed);\n"));
+    assert!(output.contains("    class Inner {\n"));
+    assert!(output.contains("        int x;\n"));
+}
+
+#[test]
+fn doxygen_names_scripts_after_their_file() {
+    let output = generate(&DoxygenBackend::new(), "var x\n");
+    assert!(output.contains("class player {"));
+}
+
+#[test]
+fn markdown_source_links() {
+    let source = "func foo():\n\tpass\n\nclass Inner:\n\tvar x\n";
+    let output = generate(&markdown(), source);
+    assert!(!output.contains("[source]"));
+
+    let template = "https://example.com/{path}#L{line}".to_string();
+    let output = generate(
+        &markdown_with(Some(template), PrivateSection::None, CalloutStyle::Plain),
+        source,
+    );
+    assert!(output.contains("* foo() [source](https://example.com/player.gd#L1)"));
+    assert!(output.contains("* Inner [source](https://example.com/player.gd#L4)"));
+    assert!(output.contains("* x [source](https://example.com/player.gd#L5)"));
+}
+
+#[test]
+fn heading_offsets() {
+    let source = "class_name Player\nvar speed\n";
+    let with_offset = |heading_offset| {
+        let backend = MarkdownBackend::new(
+            true,
+            heading_offset,
+            100,
+            None,
+            PrivateSection::None,
+            CalloutStyle::Plain,
+            InheritedMembers::None,
+        );
+        generate(&backend, source)
+    };
+
+    let output = with_offset(0);
+    assert!(output.starts_with("## Player\n"));
+    assert!(output.contains("\n### Variables:  \n"));
+
+    let output = with_offset(2);
+    assert!(output.starts_with("#### Player\n"));
+    assert!(output.contains("\n##### Variables:  \n"));
+
+    // Level 6 is the deepest heading, anything beyond it is bold text
+    let output = with_offset(3);
+    assert!(output.starts_with("##### Player\n"));
+    assert!(output.contains("\n###### Variables:  \n"));
+    let output = with_offset(4);
+    assert!(output.starts_with("###### Player\n"));
+    assert!(output.contains("\n**Variables:**  \n"));
+    assert!(!output.contains("#######"));
+
+    let output = with_offset(u32::MAX);
+    assert!(output.starts_with("**Player**\n"));
+    assert!(!output.contains('#'));
+}
+
+#[test]
+fn signature_wrapping() {
+    let source = "func spawn_enemy(kind: String, position: Vector2 = Vector2(0, 0), count: int = 1) -> Node:\n\treturn null\nfunc short(a):\n\tpass\n";
+    let with_width = |signature_max_width| {
+        let backend = MarkdownBackend::new(
+            true,
+            0,
+            signature_max_width,
+            None,
+            PrivateSection::None,
+            CalloutStyle::Plain,
+            InheritedMembers::None,
+        );
+        generate(&backend, source)
+    };
+    let inline = "* spawn\\_enemy(kind: String, position: Vector2 = Vector2\\(0, 0\\), count: int = 1) -> Node  \n";
+    let wrapped = "* spawn\\_enemy  \n\n    ```\n    func spawn_enemy(\n        kind: String,\n        position: Vector2 = Vector2(0, 0),\n        count: int = 1\n    ) -> Node\n    ```\n";
+
+    // The signature is 84 characters wide
+    let output = with_width(84);
+    assert!(output.contains(inline));
+    let output = with_width(83);
+    assert!(output.contains(wrapped));
+    assert!(!output.contains("spawn\\_enemy("));
+    // Short signatures stay on one line
+    assert!(output.contains("* short(a)  \n"));
+
+    // 0 disables wrapping
+    let output = with_width(0);
+    assert!(output.contains(inline));
+    assert!(!output.contains("```"));
+}
+
+/// Compares the documentation of a script in tests/golden with the expected output next to it
+fn golden(backend: &dyn Backend, name: &str) {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let source = std::fs::read_to_string(dir.join(name)).unwrap();
+    let data = parse_str(name, &source, &ParseOptions::default()).unwrap();
+    let expected =
+        std::fs::read_to_string(dir.join(format!("{}.{}", name, backend.get_extension()))).unwrap();
+    assert_eq!(write_output(backend, data), expected);
+}
+
+#[test]
+fn markdown_export_options() {
+    golden(&markdown(), "exports.gd");
+}
+
+#[test]
+fn markdown_enum_tables() {
+    golden(&markdown(), "enums.gd");
+}
+
+#[test]
+fn markdown_export_setget() {
+    golden(&markdown(), "exports_setget.gd");
+}
+
+#[test]
+fn doxygen_export_setget() {
+    golden(&DoxygenBackend::new(), "exports_setget.gd");
+}
+
+#[test]
+fn markdown_signals() {
+    golden(&markdown(), "signals.gd");
+}
+
+#[test]
+fn doxygen_signals() {
+    golden(&DoxygenBackend::new(), "signals.gd");
+}
+
+#[test]
+fn export_options_with_commas() {
+    let output = generate(
+        &markdown(),
+        "export(String, \"Block, parry\", \"Dodge\") var action\n@export_enum(\"A, B\", \"C\") var choice\n",
+    );
+    assert!(output.contains("**Options**: \"Block, parry\", \"Dodge\"  \n"));
+    assert!(output.contains("**Options**: \"A, B\", \"C\"  \n"));
+}
+
+#[test]
+fn doxygen_export_options() {
+    let output = generate(
+        &DoxygenBackend::new(),
+        "@export_range(0, 100) var health: int\n",
+    );
+    assert!(output.contains("     * Range: 0–100\n     */\n    int health;\n"));
+}
+
+#[test]
+fn virtual_functions() {
+    let source = "## @virtual\nfunc take_turn() -> void:\n\tpass\n";
+    let output = generate(&markdown(), source);
+    assert!(output.contains("* take\\_turn() -> void `virtual`  \n"));
+    let output = generate(&DoxygenBackend::new(), source);
+    assert!(output.contains("    virtual void take_turn();\n"));
+}
+
+#[test]
+fn since_badges() {
+    let source = "## @since 1.2.0\nfunc jump():\n\tpass\n\nfunc walk():\n\tpass\n";
+    let output = generate(
+        &markdown_with(
+            Some("src/{path}#L{line}".to_string()),
+            PrivateSection::None,
+            CalloutStyle::Plain,
+        ),
+        source,
+    );
+    assert!(output.contains("* jump() `since 1.2.0` [source](src/player.gd#L2)  \n"));
+    assert!(output.contains("* walk() [source](src/player.gd#L5)  \n"));
+    let output = generate(&DoxygenBackend::new(), source);
+    assert!(output.contains("     * \\since 1.2.0\n"));
+}
+
+#[test]
+fn deprecated_callouts() {
+    let source = "## @deprecated Use move_to instead\nfunc move():\n\tpass\n\n## Speed in pixels\n## @deprecated\nexport(int) var speed = 3\n";
+    let output = generate(&markdown(), source);
+    assert!(output.contains("* move()  \n**Deprecated:** Use move\\_to instead  \n"));
+    assert!(output.contains("* speed: int = `3`  \n**Deprecated**  \n"));
+    assert!(output.contains("Speed in pixels"));
+    let output = generate(&DoxygenBackend::new(), source);
+    assert!(output.contains("     * \\deprecated Use move_to instead\n"));
+    assert!(output.contains("     * \\deprecated\n"));
+}
+
+#[test]
+fn private_sections() {
+    let source = "func _ready():\n\tpass\n\nfunc jump():\n\tpass\n\nvar _speed = 2\nvar health\n\nsignal _moved\n";
+    let markdown = |private_section| markdown_with(None, private_section, CalloutStyle::Plain);
+
+    let output = generate(&markdown(PrivateSection::None), source);
+    assert!(!output.contains("Private API"));
+    assert!(output.contains("### Functions:  \n* \\_ready()  \n"));
+
+    let output = generate(&markdown(PrivateSection::Heading), source);
+    let (public, private) = output.split_once("### Private API:  \n").unwrap();
+    assert!(public.contains("### Functions:  \n* jump()  \n"));
+    assert!(public.contains("### Variables:  \n* health  \n"));
+    assert!(!public.contains("\\_"));
+    assert!(!public.contains("### Signals:"));
+    assert!(private.contains("#### Signals:  \n* \\_moved()  \n"));
+    assert!(private.contains("#### Functions:  \n* \\_ready()  \n"));
+    assert!(private.contains("#### Variables:  \n* \\_speed = `2`  \n"));
+    assert!(!private.contains("jump") && !private.contains("health"));
+
+    let output = generate(&markdown(PrivateSection::Details), source);
+    let (public, private) = output
+        .split_once("<details>\n<summary>Private API</summary>\n\n")
+        .unwrap();
+    assert!(public.contains("* jump()"));
+    assert!(private.contains("#### Functions:  \n* \\_ready()  \n"));
+    assert!(private.ends_with("</details>\n\n"));
+
+    // Without private members, there is no section
+    let output = generate(&markdown(PrivateSection::Details), "func jump():\n\tpass\n");
+    assert!(!output.contains("Private API"));
+}
+
+#[test]
+fn class_name_words() {
+    assert_eq!(split_words("UIButton"), vec!["UI", "Button"]);
+    assert_eq!(split_words("UIMenu_Item2D"), vec!["UI", "Menu_", "Item2D"]);
+    assert_eq!(
+        split_words("player_controller"),
+        vec!["player_", "controller"]
+    );
+    assert_eq!(
+        split_words("HTTPRequestPool"),
+        vec!["HTTP", "Request", "Pool"]
+    );
+    assert_eq!(split_words("Player"), vec!["Player"]);
+}
+
+fn grouped(titles: &[&str], group_by: GroupBy) -> Vec<(Option<String>, String)> {
+    let mut pages = titles
+        .iter()
+        .map(|title| IndexPage {
+            path: format!("ui/{}.gd.md", title),
+            title: title.to_string(),
+            ..IndexPage::default()
+        })
+        .collect::<Vec<_>>();
+    group_pages(&mut pages, group_by);
+    pages.into_iter().map(|x| (x.group, x.title)).collect()
+}
+
+#[test]
+fn group_by_class_name_prefix() {
+    let groups = grouped(
+        &[
+            "UIButton",
+            "Player",
+            "UIMenuBar",
+            "UIMenuItem",
+            "enemy.gd",
+            "UIPanel",
+            "Enemy_Boss",
+            "Enemy_Minion",
+        ],
+        GroupBy::ClassNamePrefix,
+    );
+    let group = |x: &str| Some(x.to_string());
+    assert_eq!(
+        groups,
+        vec![
+            (None, "Player".to_string()),
+            (None, "enemy.gd".to_string()),
+            (group("Enemy"), "Enemy_Boss".to_string()),
+            (group("Enemy"), "Enemy_Minion".to_string()),
+            (group("UI"), "UIButton".to_string()),
+            (group("UI"), "UIPanel".to_string()),
+            (group("UIMenu"), "UIMenuBar".to_string()),
+            (group("UIMenu"), "UIMenuItem".to_string()),
+        ]
+    );
+
+    // A class named like the prefix of others isn't grouped with them
+    let groups = grouped(&["UI", "UIButton"], GroupBy::ClassNamePrefix);
+    assert_eq!(
+        groups,
+        vec![(None, "UI".to_string()), (None, "UIButton".to_string())]
+    );
+}
+
+#[test]
+fn markdown_index_groups() {
+    let mut pages = ["a.gd.md", "ui/b.gd.md", "ui/c.gd.md"]
+        .iter()
+        .map(|path| IndexPage {
+            path: path.to_string(),
+            title: path
+                .trim_end_matches(".md")
+                .rsplit('/')
+                .next()
+                .unwrap()
+                .to_string(),
+            ..IndexPage::default()
+        })
+        .collect::<Vec<_>>();
+    group_pages(&mut pages, GroupBy::Directory);
+    let mut output = Vec::new();
+    let backend = markdown();
+    assert!(backend.generate_index("Game", &pages, &mut output).unwrap());
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "# Game\n\n* [a.gd](a.gd.md)  \n\n## ui\n\n* [b.gd](ui/b.gd.md)  \n* [c.gd](ui/c.gd.md)  \n"
+    );
+}
+
+#[test]
+fn index_summaries() {
+    let pages = vec![
+        IndexPage {
+            path: "player.gd.md".to_string(),
+            title: "Player".to_string(),
+            class_name: Some("Player".to_string()),
+            base: Some("KinematicBody2D".to_string()),
+            summary: Some("Moves the player".to_string()),
+            ..IndexPage::default()
+        },
+        IndexPage {
+            path: "enemy.gd.md".to_string(),
+            title: "enemy.gd".to_string(),
+            ..IndexPage::default()
+        },
+    ];
+    let mut output = Vec::new();
+    assert!(markdown()
+        .generate_index("Game", &pages, &mut output)
+        .unwrap());
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "# Game\n\n* [Player](player.gd.md), extends KinematicBody2D  \n  Moves the player  \n* [enemy.gd](enemy.gd.md)  \n"
+    );
+
+    let mut output = Vec::new();
+    assert!(DoxygenBackend::new()
+        .generate_index("Game", &pages, &mut output)
+        .unwrap());
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains(
+        " * - \\ref Player, extends KinematicBody2D\n *   Moves the player\n * - \\ref enemy\n"
+    ));
+}
+
+#[test]
+fn autoload_index() {
+    let pages = vec![
+        IndexPage {
+            path: "events.gd.md".to_string(),
+            title: "events.gd".to_string(),
+            autoload: Some("Events".to_string()),
+            ..IndexPage::default()
+        },
+        IndexPage {
+            path: "ui/menu.gd.md".to_string(),
+            title: "Menu".to_string(),
+            class_name: Some("Menu".to_string()),
+            group: Some("ui".to_string()),
+            ..IndexPage::default()
+        },
+    ];
+    let mut output = Vec::new();
+    assert!(markdown()
+        .generate_index("Game", &pages, &mut output)
+        .unwrap());
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "# Game\n\n## Autoloads\n\n* [Events](events.gd.md), events.gd  \n\n## Scripts\n\n* [events.gd](events.gd.md)  \n\n## ui\n\n* [Menu](ui/menu.gd.md)  \n"
+    );
+
+    let mut output = Vec::new();
+    assert!(DoxygenBackend::new()
+        .generate_index("Game", &pages, &mut output)
+        .unwrap());
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains(" * \\section autoloads Autoloads\n *\n * - `Events`, \\ref events\n"));
+}
+
+fn script_page(path: &str, class_name: Option<&str>, base: Option<&str>) -> IndexPage {
+    IndexPage {
+        path: format!("{}.md", path),
+        title: class_name.unwrap_or(path).to_string(),
+        class_name: class_name.map(str::to_string),
+        base: base.map(str::to_string),
+        script: Some(format!("res://{}", path)),
+        ..IndexPage::default()
+    }
+}
+
+#[test]
+fn inheritance() {
+    let pages = vec![
+        script_page("actor.gd", Some("Actor"), Some("Node2D")),
+        script_page("player.gd", Some("Player"), Some("Actor")),
+        script_page("enemies/boss.gd", None, Some("\"../player.gd\"")),
+        script_page("enemies/minion.gd", None, Some("\"res://enemies/boss.gd\"")),
+        script_page("ghost.gd", None, Some("'res://missing.gd'")),
+        script_page("a.gd", Some("A"), Some("B")),
+        script_page("b.gd", Some("B"), Some("A")),
+        script_page("c.gd", None, Some("A")),
+        script_page("self.gd", Some("Self"), Some("Self")),
+        script_page("plain.gd", None, None),
+        IndexPage {
+            title: "broken.gd".to_string(),
+            base: Some("Actor".to_string()),
+            failed: true,
+            ..IndexPage::default()
+        },
+    ];
+    let tree = inheritance_tree(&pages);
+    let roots = tree
+        .roots
+        .iter()
+        .map(|(base, nodes)| (base.as_str(), nodes.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(roots, vec![("", 1), ("Node2D", 1)]);
+    assert_eq!(tree.unknown.len(), 1);
+    let cycles = tree
+        .cycles
+        .iter()
+        .map(|x| x.iter().map(|x| x.page.title.as_str()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(cycles, vec![vec!["A", "B"], vec!["Self"]]);
+
+    let mut output = Vec::new();
+    assert!(markdown()
+        .generate_inheritance("Game", &tree, &mut output)
+        .unwrap());
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "# Inheritance of Game
+
+* [plain.gd](plain.gd.md)
+* Node2D
+  * [Actor](actor.gd.md)
+    * [Player](player.gd.md)
+      * [enemies/boss.gd](enemies/boss.gd.md)
+        * [enemies/minion.gd](enemies/minion.gd.md)
+
+## Unknown base
+
+* `'res://missing.gd'`
+  * [ghost.gd](ghost.gd.md)
+
+## Cycles
+
+* [A](a.gd.md) extends [B](b.gd.md) extends [A](a.gd.md)
+  * [c.gd](c.gd.md)
+* [Self](self.gd.md) extends [Self](self.gd.md)
+"
+    );
+
+    // Doxygen draws the hierarchy of the classes itself
+    assert!(!DoxygenBackend::new()
+        .generate_inheritance("Game", &tree, &mut Vec::new())
+        .unwrap());
+}
+
+#[test]
+fn inherited_members() {
+    let actor = parse_str(
+        "actor.gd",
+        "class_name Actor\n## Health\nvar health\nfunc die():\n\tpass\n",
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    let mut data = parse_str(
+        "enemy.gd",
+        "extends Actor\nvar speed\n",
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    data.inherited = vec![InheritedClass {
+        title: "Actor".to_string(),
+        link: Some("actor.gd.md".to_string()),
+        source_path: "actor.gd".to_string(),
+        entries: actor.entries,
+    }];
+    let markdown = |inherited_members| {
+        MarkdownBackend::new(
+            true,
+            0,
+            100,
+            None,
+            PrivateSection::None,
+            CalloutStyle::Plain,
+            inherited_members,
+        )
+    };
+
+    let output = write_output(&markdown(InheritedMembers::None), data.clone());
+    assert!(!output.contains("Inherited"));
+    let output = write_output(&markdown(InheritedMembers::Linked), data.clone());
+    assert!(output.ends_with("Inherited from [Actor](actor.gd.md): die, health  \n\n"));
+    let output = write_output(&markdown(InheritedMembers::Listed), data);
+    assert!(output.ends_with(
+        "<details>\n<summary>Inherited from Actor</summary>\n\nDefined in [Actor](actor.gd.md)  \n\n\
+        #### Functions:  \n* die()  \n  \n  \n\
+        #### Variables:  \n* health  \n  \n    ```\n    Health\n    ```\n\n  \n</details>\n\n"
+    ));
+}
+
+#[test]
+fn overrides() {
+    let mut data = parse_str(
+        "enemy.gd",
+        "func _ready():\n\tpass\nfunc die():\n\tpass\n",
+        &ParseOptions {
+            show_prefixed: ShowPrefixed::all(true),
+            ..ParseOptions::default()
+        },
+    )
+    .unwrap();
+    let overrides = [
+        Override::Engine,
+        Override::Script {
+            title: "Actor".to_string(),
+            link: Some("actor.gd.md".to_string()),
+        },
+    ];
+    for (symbol, overrides) in data.entries[0].symbols.iter_mut().zip(overrides) {
+        if let Some(SymbolArgs::FunctionArgs(function)) = &mut symbol.arg {
+            function.overrides = Some(overrides);
+        }
+    }
+
+    let output = write_output(&markdown(), data.clone());
+    assert!(output.contains("* \\_ready()  \nEngine virtual  \n"));
+    assert!(output.contains("* die()  \nOverrides [Actor.die](actor.gd.md)  \n"));
+    let output = write_output(&DoxygenBackend::new(), data);
+    assert!(
+        output.contains("     * \\fn Variant _ready()\n     * \\remark Engine virtual\n     */\n")
+    );
+    assert!(output.contains("     * \\fn Variant die()\n     */\n"));
+}
+
+#[test]
+fn callout_styles() {
+    let source = "## Moves the player\n## @since 1.2.0\n## @deprecated Use move_to instead\n## @note Runs every frame\n## @warning Not thread safe\nfunc move():\n\tpass\n";
+    let markdown = |callout_style| markdown_with(None, PrivateSection::None, callout_style);
+
+    let output = generate(&markdown(CalloutStyle::Plain), source);
+    assert!(output.contains("* move() `since 1.2.0`  \n**Deprecated:** Use move\\_to instead  \n**Note:** Runs every frame  \n**Warning:** Not thread safe  \n"));
+    assert!(!output.contains("@note"));
+
+    let output = generate(&markdown(CalloutStyle::Gfm), source);
+    assert!(output.contains("* move()  \n"));
+    assert!(!output.contains("`since"));
+    assert!(output.contains("```\n\n> [!NOTE]\n> **Since:** 1.2.0\n\n"));
+    assert!(output.contains("> [!CAUTION]\n> **Deprecated:** Use move\\_to instead\n\n"));
+    assert!(output.contains("> [!NOTE]\n> **Note:** Runs every frame\n\n"));
+    assert!(output.contains("> [!WARNING]\n> **Warning:** Not thread safe\n\n"));
+
+    let output = generate(&markdown(CalloutStyle::Emoji), source);
+    assert!(output.contains(
+        "* move()  \n🆕 **Since:** 1.2.0  \n⛔ **Deprecated:** Use move\\_to instead  \n"
+    ));
+    assert!(output.contains(
+        "ℹ\u{fe0f} **Note:** Runs every frame  \n⚠\u{fe0f} **Warning:** Not thread safe  \n"
+    ));
+
+    let output = generate(&DoxygenBackend::new(), source);
+    assert!(output.contains("     * @note Runs every frame\n     * @warning Not thread safe\n"));
+}
@@ -0,0 +1,1793 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// A fresh input and output directory, which is removed at the end of the test
+struct Project {
+    root: PathBuf,
+}
+
+impl Project {
+    fn new(name: &str) -> Project {
+        let root = std::env::temp_dir().join(format!("godotdoc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("input")).unwrap();
+        Project { root }
+    }
+
+    fn input(&self) -> PathBuf {
+        self.root.join("input")
+    }
+
+    fn output(&self) -> PathBuf {
+        self.root.join("output")
+    }
+
+    fn write(&self, path: &str, content: &str) {
+        let path = self.input().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        let output = self.run_inputs(&[&self.input()], args);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output
+    }
+
+    /// Runs in the input directory with the given inputs, relative to it
+    fn run_inputs(&self, inputs: &[&Path], args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_godotdoc"))
+            .current_dir(self.input())
+            .args(inputs)
+            .arg("-o")
+            .arg(self.output())
+            .args(args)
+            .output()
+            .unwrap()
+    }
+}
+
+impl Drop for Project {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+fn skipped(output: &Output, path: &Path) -> bool {
+    String::from_utf8_lossy(&output.stderr)
+        .contains(&format!("Skipping {}: it is up to date", path.display()))
+}
+
+#[test]
+fn unchanged_files_are_skipped() {
+    let project = Project::new("incremental");
+    project.write("a.gd", "var a\n");
+    project.write("b.gd", "var b\n");
+
+    let output = project.run(&["--verbose"]);
+    assert!(!skipped(&output, &project.input().join("a.gd")));
+
+    let output = project.run(&["--verbose"]);
+    assert!(skipped(&output, &project.input().join("a.gd")));
+    assert!(skipped(&output, &project.input().join("b.gd")));
+
+    project.write("a.gd", "var c\n");
+    let output = project.run(&["--verbose"]);
+    assert!(!skipped(&output, &project.input().join("a.gd")));
+    assert!(skipped(&output, &project.input().join("b.gd")));
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    assert!(generated.contains("* c"));
+
+    let output = project.run(&["--verbose", "--force"]);
+    assert!(!skipped(&output, &project.input().join("b.gd")));
+}
+
+#[test]
+fn changed_settings_regenerate_everything() {
+    let project = Project::new("settings");
+    project.write("a.gd", "var _a\n");
+
+    project.run(&[]);
+    let output = project.run(&["--verbose", "--hide_prefixed"]);
+    assert!(!skipped(&output, &project.input().join("a.gd")));
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    assert!(!generated.contains("\\_a"));
+}
+
+#[test]
+fn missing_outputs_are_regenerated() {
+    let project = Project::new("missing");
+    project.write("a.gd", "var a\n");
+
+    project.run(&[]);
+    std::fs::remove_file(project.output().join("a.gd.md")).unwrap();
+    project.run(&[]);
+    assert!(project.output().join("a.gd.md").is_file());
+}
+
+#[test]
+fn several_inputs_keep_their_directories() {
+    let project = Project::new("inputs");
+    project.write("scripts/a.gd", "var a\n");
+    project.write("scripts/ignored.gd", "var ignored\n");
+    project.write(
+        "scripts/godotdoc_config.json",
+        r#"{"excluded_files": ["./ignored.gd"]}"#,
+    );
+    project.write("addons/tool/b.gd", "var b\n");
+    project.write("c.gd", "var c\n");
+
+    let inputs = [
+        Path::new("scripts"),
+        Path::new("addons/tool"),
+        Path::new("c.gd"),
+        Path::new("scripts/a.gd"),
+    ];
+    let output = project.run_inputs(&inputs, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(project.output().join("scripts/a.gd.md").is_file());
+    assert!(project.output().join("addons/tool/b.gd.md").is_file());
+    assert!(project.output().join("c.gd.md").is_file());
+    assert!(!project.output().join("scripts/ignored.gd.md").exists());
+}
+
+#[test]
+fn inputs_outside_of_the_current_directory_are_rejected() {
+    let project = Project::new("outside");
+    project.write("a.gd", "var a\n");
+
+    let output = project.run_inputs(&[Path::new("a.gd"), Path::new("..")], &[]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn flat_output() {
+    let project = Project::new("flat");
+    project.write(
+        "scripts/ui/button.gd",
+        "const Base = preload(\"res://base.gd\")\n",
+    );
+    project.write(
+        "base.gd",
+        "const Button = preload(\"res://scripts/ui/button.gd\")\n",
+    );
+
+    project.run(&["--flat"]);
+    let generated =
+        std::fs::read_to_string(project.output().join("scripts__ui__button.gd.md")).unwrap();
+    assert!(generated.contains("(base.gd.md)"));
+    let generated = std::fs::read_to_string(project.output().join("base.gd.md")).unwrap();
+    assert!(generated.contains("(scripts__ui__button.gd.md)"));
+    assert!(!project.output().join("scripts").exists());
+}
+
+#[test]
+fn root_url_links() {
+    let project = Project::new("root-url");
+    project.write(
+        "ui/button.gd",
+        "const Base = preload(\"res://base.gd\")\n\nclass Inner:\n\tvar a\n",
+    );
+    project.write("base.gd", "var b\n");
+    project.write(
+        "godotdoc_config.json",
+        "{\"source_url_template\": \"src/{path}#L{line}\"}",
+    );
+
+    let read = |path: &str| std::fs::read_to_string(project.output().join(path)).unwrap();
+    project.run(&["--split-classes"]);
+    let button = read("ui/button.gd.md");
+    assert!(button.contains("* [res://base.gd](../base.gd.md)"));
+    assert!(button.contains("* [Inner](button_Inner.gd.md)"));
+    assert!(button.contains("[source](src/ui/button.gd#L3)"));
+    assert!(read("ui/button_Inner.gd.md").contains("Defined in [button.gd](button.gd.md)"));
+    assert!(read("index.md").contains("* [button.gd](ui/button.gd.md)"));
+
+    project.run(&[
+        "--split-classes",
+        "--root-url",
+        "https://docs.example.com/game",
+    ]);
+    let button = read("ui/button.gd.md");
+    assert!(button.contains("(https://docs.example.com/game/base.gd.md)"));
+    assert!(button.contains("* [Inner](https://docs.example.com/game/ui/button_Inner.gd.md)"));
+    assert!(button.contains("[source](https://docs.example.com/game/src/ui/button.gd#L3)"));
+    assert!(read("ui/button_Inner.gd.md")
+        .contains("Defined in [button.gd](https://docs.example.com/game/ui/button.gd.md)"));
+    assert!(
+        read("index.md").contains("* [button.gd](https://docs.example.com/game/ui/button.gd.md)")
+    );
+}
+
+#[test]
+fn flat_output_rejects_collisions() {
+    let project = Project::new("flat-collision");
+    project.write("ui/button.gd", "var a\n");
+    project.write("ui__button.gd", "var b\n");
+
+    let output = project.run_inputs(&[Path::new(".")], &["--flat"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn stdin_to_stdout() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_godotdoc"))
+        .args(["--stdin", "--stdin-filename", "player.gd"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"## Moves the player\nfunc move():\n\tpass\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("## player.gd\n"));
+    assert!(stdout.contains("* move()"));
+    assert!(stdout.contains("Moves the player"));
+}
+
+#[test]
+fn include_and_exclude_patterns() {
+    let project = Project::new("patterns");
+    project.write(
+        "godotdoc_config.json",
+        r#"{"excluded_files": ["./ui/secret.gd"]}"#,
+    );
+    project.write("ui/button.gd", "var a\n");
+    project.write("ui/secret.gd", "var b\n");
+    project.write("ui/old.gd", "var c\n");
+    project.write("main.gd", "var d\n");
+
+    project.run(&["--include", "ui/*.gd", "--exclude", "./ui/old.gd"]);
+    assert!(project.output().join("ui/button.gd.md").is_file());
+    assert!(!project.output().join("ui/secret.gd.md").exists());
+    assert!(!project.output().join("ui/old.gd.md").exists());
+    assert!(!project.output().join("main.gd.md").exists());
+}
+
+#[test]
+fn invalid_patterns_name_their_flag() {
+    let project = Project::new("invalid-pattern");
+    let output = project.run_inputs(&[Path::new(".")], &["--include", "a/***"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'a/***' of --include"));
+}
+
+#[test]
+fn unrecognized_declarations_are_reported() {
+    let project = Project::new("unrecognized");
+    project.write(
+        "a.gd",
+        "tool\nfucn foo():\n\tbar()\n\nfunc baz():\n\tqux()\n",
+    );
+
+    let output = project.run(&["--warn-unrecognized"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("a.gd, line 2: Unrecognized declaration 'fucn foo():'"));
+    assert!(!stderr.contains("line 1"));
+    assert!(!stderr.contains("qux"));
+
+    let output = project.run(&["--force"]);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("Unrecognized"));
+}
+
+#[test]
+fn config_is_searched_in_parent_directories() {
+    let project = Project::new("config-search");
+    project.write(
+        "godotdoc.toml",
+        "backend = \"doxygen\"\nexcluded_files = [\"./scripts/secret.gd\", \"./other/*.gd\"]\n",
+    );
+    project.write("scripts/a.gd", "var a\n");
+    project.write("scripts/secret.gd", "var b\n");
+
+    let output = project.run_inputs(&[Path::new("scripts")], &[]);
+    assert!(output.status.success());
+    assert!(project.output().join("a.gd.h").is_file());
+    assert!(!project.output().join("secret.gd.h").exists());
+}
+
+#[test]
+fn explicit_config() {
+    let project = Project::new("config-flag");
+    project.write("a.gd", "var a\n");
+    project.write("godotdoc_config.json", r#"{"backend": "doxygen"}"#);
+    project.write("configs/docs.toml", "backend = \"markdown\"\n");
+
+    let output = project.run_inputs(&[Path::new(".")], &["--config", "configs/docs.toml"]);
+    assert!(output.status.success());
+    assert!(project.output().join("a.gd.md").is_file());
+    assert!(!project.output().join("a.gd.h").exists());
+
+    project.write("configs/broken.json", "{\n  \"backend\": \n}");
+    let output = project.run_inputs(&[Path::new(".")], &["--config", "configs/broken.json"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("broken.json"));
+    assert!(stderr.contains("line 3"));
+}
+
+#[test]
+fn show_prefixed_precedence() {
+    let configs = [None, Some(true), Some(false)];
+    let flags = [None, Some(true), Some(false)];
+    for (i, config) in configs.iter().enumerate() {
+        for flag in flags.iter() {
+            let project = Project::new(&format!("prefixed-{}-{:?}", i, flag));
+            project.write("a.gd", "var _a\n");
+            if let Some(config) = config {
+                project.write(
+                    "godotdoc_config.json",
+                    &format!("{{\"show_prefixed\": {}}}", config),
+                );
+            }
+            let args = match flag {
+                None => vec![],
+                Some(true) => vec!["--show_prefixed"],
+                Some(false) => vec!["--hide_prefixed"],
+            };
+
+            project.run(&args);
+            let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+            let expected = flag.or(*config).unwrap_or(true);
+            assert_eq!(
+                generated.contains("\\_a"),
+                expected,
+                "config {:?}, flag {:?}",
+                config,
+                flag
+            );
+        }
+    }
+}
+
+#[test]
+fn collapsed_prefixed_members() {
+    let project = Project::new("prefixed-collapsed");
+    project.write("a.gd", "var _a\nvar b\n");
+    project.write("godotdoc_config.json", "{\"show_prefixed\": \"collapsed\"}");
+
+    project.run(&[]);
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    let (public, private) = generated.split_once("<details>").unwrap();
+    assert!(public.contains("* b"));
+    assert!(!public.contains("\\_a"));
+    assert!(private.contains("\\_a"));
+
+    // An explicit private section wins
+    project.run(&["--private-section", "heading", "--force"]);
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    assert!(!generated.contains("<details>"));
+    assert!(generated.contains("### Private API:"));
+
+    project.write("godotdoc_config.json", "{\"show_prefixed\": \"folded\"}");
+    let output = project.run_inputs(&[Path::new(".")], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("godotdoc_config.json"));
+}
+
+#[test]
+fn show_and_hide_prefixed_conflict() {
+    let project = Project::new("prefixed-conflict");
+    let output = project.run_inputs(&[Path::new(".")], &["--show_prefixed", "--hide_prefixed"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn private_section_shows_prefixed_members() {
+    let project = Project::new("private-section");
+    project.write("a.gd", "var _a\nvar b\n");
+    project.write("godotdoc_config.json", "{\"show_prefixed\": false}");
+
+    project.run(&["--private-section", "heading"]);
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    let (public, private) = generated.split_once("### Private API:").unwrap();
+    assert!(public.contains("* b"));
+    assert!(private.contains("\\_a"));
+
+    let output = project.run_inputs(
+        &[Path::new(".")],
+        &["--private-section", "heading", "--hide_prefixed"],
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn callout_style_from_config() {
+    let project = Project::new("callout-style");
+    project.write("a.gd", "## @warning Not thread safe\nvar a\n");
+    project.write("godotdoc_config.json", "{\"callout_style\": \"gfm\"}");
+
+    project.run(&[]);
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    assert!(generated.contains("> [!WARNING]\n> **Warning:** Not thread safe"));
+
+    project.write("godotdoc_config.json", "{\"callout_style\": \"fancy\"}");
+    let output = project.run_inputs(&[Path::new(".")], &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown callout style 'fancy'"));
+}
+
+#[test]
+fn config_unknown_keys() {
+    let project = Project::new("config-unknown-keys");
+    project.write("a.gd", "var a\n");
+    project.write(
+        "godotdoc_config.json",
+        "{\"exclude_files\": [\"a.gd\"], \"show_prefix\": true, \"colour\": 1}",
+    );
+    let output = project.run_inputs(&[Path::new(".")], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(
+        "godotdoc_config.json: Unknown key 'exclude_files' at /exclude_files, did you mean 'excluded_files'?"
+    ));
+    assert!(
+        stderr.contains("Unknown key 'show_prefix' at /show_prefix, did you mean 'show_prefixed'?")
+    );
+    assert!(stderr.contains("Unknown key 'colour' at /colour\n"));
+    assert!(!project.output().join("a.gd.md").exists());
+
+    std::fs::remove_file(project.input().join("godotdoc_config.json")).unwrap();
+    project.write("godotdoc.toml", "flat = true\nbackend = \"markdwon\"\n");
+    let output = project.run_inputs(&[Path::new(".")], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "godotdoc.toml: Unsupported backend 'markdwon', expected markdown, doxygen or all, did you mean 'markdown'? at /backend"
+    ));
+}
+
+#[test]
+fn config_value_shapes() {
+    let project = Project::new("config-value-shapes");
+    project.write("a.gd", "var a\n");
+    project.write(
+        "godotdoc_config.json",
+        "{\"excluded_files\": [\"a.gd\", 3], \"ignored_comment_prefixes\": \"#!\"}",
+    );
+    let output = project.run_inputs(&[Path::new(".")], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Expected a string at /excluded_files/1, got 3"));
+    assert!(
+        stderr.contains("Expected an array of strings at /ignored_comment_prefixes, got \"#!\"")
+    );
+}
+
+#[test]
+fn resolve_constants_of_other_scripts() {
+    let project = Project::new("resolve-constants");
+    project.write("stats.gd", "class_name Stats\nconst MAX_LEVEL = 10\n");
+    project.write("level.gd", "enum Level {TOP = Stats.MAX_LEVEL, OVER}\n");
+
+    project.run(&[]);
+    let generated = std::fs::read_to_string(project.output().join("level.gd.md")).unwrap();
+    assert!(generated.contains("| TOP | Stats.MAX\\_LEVEL |"));
+    assert!(generated.contains("| OVER | Stats.MAX\\_LEVEL + 1 |"));
+
+    project.run(&["--resolve-constants"]);
+    let generated = std::fs::read_to_string(project.output().join("level.gd.md")).unwrap();
+    assert!(generated.contains("| TOP | 10 |"));
+    assert!(generated.contains("| OVER | 11 |"));
+
+    // Scripts, which refer to a constant, are documented again when it changes
+    project.write("stats.gd", "class_name Stats\nconst MAX_LEVEL = 20\n");
+    project.run(&["--resolve-constants"]);
+    let generated = std::fs::read_to_string(project.output().join("level.gd.md")).unwrap();
+    assert!(generated.contains("| TOP | 20 |"));
+}
+
+#[test]
+fn list_backends() {
+    let project = Project::new("list-backends");
+    let output = Command::new(env!("CARGO_BIN_EXE_godotdoc"))
+        .arg("list-backends")
+        .current_dir(project.input())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("markdown   .md   "));
+    assert!(lines[1].contains("Options: show_dependencies, heading_offset"));
+    assert!(lines[2].starts_with("doxygen    .h    "));
+    assert!(!project.output().exists());
+}
+
+#[test]
+fn unknown_backends_are_listed() {
+    let project = Project::new("unknown-backend");
+    project.write("a.gd", "var a\n");
+    let output = project.run_inputs(&[Path::new(".")], &["--backend", "markdwn"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(
+        "Unsupported backend 'markdwn', expected markdown, doxygen or all, did you mean 'markdown'?"
+    ));
+
+    let output = project.run_inputs(&[Path::new(".")], &["--backend", "html"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unsupported backend 'html', expected markdown, doxygen or all\n"));
+}
+
+#[test]
+fn print_ast_of_a_single_file() {
+    let project = Project::new("print-ast");
+    project.write(
+        "player.gd",
+        "extends Node\n## Speed in px/s\nvar speed = 3\nfunc run(a: int) -> void:\n\tpass\n",
+    );
+    let output = project.run_inputs(&[Path::new("player.gd")], &["--print-ast"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("DocumentationData {"));
+    for expected in [
+        "source_path: \"player.gd\"",
+        "\"Node\"",
+        "name: \"speed\"",
+        "\"Speed in px/s\"",
+        "name: \"run\"",
+        "\"void\"",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "{} is missing in {}",
+            expected,
+            stdout
+        );
+    }
+    assert!(!project.output().exists());
+    let files: Vec<_> = std::fs::read_dir(project.input())
+        .unwrap()
+        .map(|x| x.unwrap().file_name())
+        .collect();
+    assert_eq!(files, vec!["player.gd"]);
+}
+
+#[test]
+fn crlf_line_endings() {
+    let source = "class_name Player\nextends Node\n\n## Speed in px/s\nvar speed = 1 + \\\n\t2\nfunc run(a,\n\t\tb):\n\tpass\n";
+    let project = Project::new("crlf");
+    project.write("lf.gd", source);
+    // With a byte order mark before the class_name on the first line
+    project.write(
+        "crlf.gd",
+        &format!("\u{feff}{}", source.replace('\n', "\r\n")),
+    );
+    project.run(&[]);
+
+    let read = |name: &str| std::fs::read_to_string(project.output().join(name)).unwrap();
+    let crlf = read("crlf.gd.md");
+    assert!(!crlf.contains('\r'));
+    assert!(crlf.starts_with("## Player\n"));
+    assert!(crlf.contains("* speed = `1 + 2`"));
+    assert!(crlf.contains("* run(a, b)"));
+    assert_eq!(crlf, read("lf.gd.md"));
+}
+
+#[test]
+fn json_errors() {
+    let project = Project::new("json-errors");
+    project.write("bad.gd", "var a = (]\n");
+    project.write("good.gd", "var b\n");
+    let error_file = project.root.join("errors.json");
+    let output = project.run_inputs(
+        &[Path::new(".")],
+        &[
+            "--error-format",
+            "json",
+            "--error-file",
+            error_file.to_str().unwrap(),
+        ],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let errors: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&error_file).unwrap()).unwrap();
+    let errors = errors.as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]["file"].as_str().unwrap().ends_with("bad.gd"));
+    assert_eq!(errors[0]["line"], 1);
+    assert_eq!(errors[0]["column"], 10);
+    assert_eq!(errors[0]["kind"], "mismatched_parentheses");
+    assert!(errors[0]["message"].is_string());
+
+    // The array is written without errors as well, on stderr without an error file
+    std::fs::remove_file(project.input().join("bad.gd")).unwrap();
+    let output = project.run(&["--error-format", "json"]);
+    assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "[]");
+}
+
+#[test]
+fn deterministic_order() {
+    let files = ["z.gd", "b/y.gd", "a/x.gd", "m.gd"];
+    let first = Project::new("order-first");
+    let second = Project::new("order-second");
+    for file in files.iter() {
+        first.write(file, "var v\n");
+    }
+    for file in files.iter().rev() {
+        second.write(file, "var v\n");
+    }
+
+    let listing = |project: &Project| {
+        let output = project.run_inputs(&[Path::new(".")], &["--dry-run", "--jobs", "4"]);
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        stdout.replace(&project.output().display().to_string(), "")
+    };
+    let expected = listing(&first);
+    let pages = expected
+        .lines()
+        .filter_map(|x| x.strip_prefix("Would create /")?.split(' ').next())
+        .collect::<Vec<_>>();
+    assert_eq!(pages[..4], ["a/x.gd.md", "b/y.gd.md", "m.gd.md", "z.gd.md"]);
+    assert_eq!(listing(&first), expected);
+    assert_eq!(listing(&second), expected);
+
+    first.run(&[]);
+    second.run(&[]);
+    let index = |project: &Project| std::fs::read(project.output().join("index.md")).unwrap();
+    assert_eq!(index(&first), index(&second));
+}
+
+#[test]
+fn max_depth() {
+    let project = Project::new("max-depth");
+    project.write("a.gd", "var a\n");
+    project.write("one/b.gd", "var b\n");
+    project.write("one/two/c.gd", "var c\n");
+
+    project.run(&["--max-depth", "1"]);
+    assert!(project.output().join("a.gd.md").exists());
+    assert!(!project.output().join("one").exists());
+
+    project.run(&["--max-depth", "2"]);
+    assert!(project.output().join("one/b.gd.md").exists());
+    assert!(!project.output().join("one/two").exists());
+
+    // 0 means no limit
+    project.run(&["--max-depth", "0"]);
+    assert!(project.output().join("one/two/c.gd.md").exists());
+}
+
+#[test]
+fn backend_names_ignore_case() {
+    let project = Project::new("backend-case");
+    project.write("a.gd", "var a\n");
+    project.run(&["--output-format", "Markdown"]);
+    assert!(project.output().join("a.gd.md").exists());
+
+    project.run(&["--backend", "MARKDOWN,Doxygen"]);
+    assert!(project.output().join("a.gd.h").exists());
+}
+
+#[test]
+fn unknown_backends_fail_before_config() {
+    let project = Project::new("unknown-backend-early");
+    project.write("a.gd", "var a\n");
+    project.write("godotdoc_config.json", "{ not json");
+    let output = project.run_inputs(&[Path::new(".")], &["--output-format", "Markdwn"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(
+        "Unsupported backend 'Markdwn', expected markdown, doxygen or all, did you mean 'markdown'?"
+    ));
+    assert!(!stderr.contains("godotdoc_config.json"));
+}
+
+#[test]
+fn print_config_sources() {
+    let project = Project::new("print-config");
+    project.write("a.gd", "var a\n");
+    project.write(
+        "godotdoc_config.json",
+        "{\"excluded_files\": [\"./secret\"], \"heading_offset\": 2, \"flat\": false}",
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_godotdoc"))
+        .current_dir(project.input())
+        .args([".", "--print-config", "json", "--exclude", "*.tmp"])
+        .args(["--backend", "doxygen"])
+        .env("GODOTDOC_FLAT", "true")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let config: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let settings = config["settings"].as_array().unwrap();
+    let setting = |name: &str| {
+        settings
+            .iter()
+            .filter(|x| x["name"] == name)
+            .map(|x| (x["source"].as_str().unwrap(), x["value"].clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(setting("backend"), [("cli", "doxygen".into())]);
+    assert_eq!(setting("flat"), [("env", true.into())]);
+    assert_eq!(setting("heading_offset"), [("config", 2.into())]);
+    assert_eq!(setting("signature_max_width"), [("default", 100.into())]);
+    assert_eq!(setting("root_url"), [("default", serde_json::Value::Null)]);
+    assert_eq!(setting("output"), [("default", ".".into())]);
+    assert_eq!(
+        setting("excluded_files"),
+        [
+            ("cli", serde_json::json!(["*.tmp"])),
+            ("config", serde_json::json!(["./secret"]))
+        ]
+    );
+    assert!(!project.output().exists());
+    assert!(!project.input().join("a.gd.md").exists());
+
+    let output = project.run(&["--print-config", "toml"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout
+        .contains("[[settings]]\nname = \"heading_offset\"\nsource = \"config\"\nvalue = 2\n"));
+}
+
+#[test]
+fn environment_overrides() {
+    let project = Project::new("environment");
+    project.write("a.gd", "var a\nvar _b\n");
+    project.write(
+        "godotdoc_config.json",
+        "{\"backend\": \"doxygen\", \"show_prefixed\": true}",
+    );
+    let godotdoc = || {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_godotdoc"));
+        command
+            .current_dir(project.input())
+            .arg(".")
+            .env("GODOTDOC_OUTPUT", project.root.join("env-output"))
+            .env("GODOTDOC_BACKEND", "markdown")
+            .env("GODOTDOC_SHOW_PREFIXED", "false")
+            .env("GODOTDOC_EXCLUDE", "secret.gd");
+        command
+    };
+    project.write("secret.gd", "var c\n");
+
+    // The environment wins over the config
+    assert!(godotdoc().status().unwrap().success());
+    let generated = std::fs::read_to_string(project.root.join("env-output/a.gd.md")).unwrap();
+    assert!(!generated.contains("\\_b"));
+    assert!(!project.root.join("env-output/secret.gd.md").exists());
+
+    // Flags win over the environment
+    let output = godotdoc()
+        .arg("-o")
+        .arg(project.output())
+        .arg("--show_prefixed")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    assert!(generated.contains("\\_b"));
+
+    // Values of the environment are validated like flags
+    let output = godotdoc()
+        .env("GODOTDOC_BACKEND", "markdwn")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let flag_output = project.run_inputs(&[Path::new(".")], &["--backend", "markdwn"]);
+    let error = |output: &Output| String::from_utf8_lossy(&output.stderr).into_owned();
+    assert_eq!(error(&output), error(&flag_output));
+
+    let output = godotdoc()
+        .arg("--print-config")
+        .arg("json")
+        .output()
+        .unwrap();
+    let config: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let sources = config["settings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|x| {
+            ["output", "backend", "show_prefixed", "excluded_files"]
+                .contains(&x["name"].as_str().unwrap())
+        })
+        .map(|x| (x["name"].as_str().unwrap(), x["source"].as_str().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sources,
+        [
+            ("output", "env"),
+            ("backend", "env"),
+            ("show_prefixed", "env"),
+            ("excluded_files", "env")
+        ]
+    );
+}
+
+#[test]
+fn output_is_required() {
+    let project = Project::new("no-output");
+    project.write("a.gd", "var a\n");
+    let output = Command::new(env!("CARGO_BIN_EXE_godotdoc"))
+        .current_dir(project.input())
+        .arg(".")
+        .env_remove("GODOTDOC_OUTPUT")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("GODOTDOC_OUTPUT"));
+}
+
+#[test]
+fn title_and_index() {
+    let project = Project::new("title");
+    project.write(
+        "player.gd",
+        "## Moves the player.\n## @tutorial: https://example.com\nclass_name Player\nextends Node\nvar a\n",
+    );
+    project.write("ui/button.gd", "var b\n");
+
+    // The index is titled after the project directory by default
+    project.run(&[]);
+    let index = std::fs::read_to_string(project.output().join("index.md")).unwrap();
+    assert!(index.starts_with("# input\n\n"));
+    project.run(&["--no-index", "--clean"]);
+    assert!(!project.output().join("index.md").exists());
+
+    // Skipped scripts are listed as well, with their base class and summary
+    project.run(&["--title", "My Game"]);
+    project.run(&["--title", "My Game", "--clean"]);
+    let index = std::fs::read_to_string(project.output().join("index.md")).unwrap();
+    assert_eq!(
+        index,
+        "# My Game\n\n* [Player](player.gd.md), extends Node  \n  Moves the player.  \n\n## ui\n\n* [button.gd](ui/button.gd.md)  \n"
+    );
+    let page = std::fs::read_to_string(project.output().join("player.gd.md")).unwrap();
+    assert!(page.starts_with("## Player\n\nMoves the player.\n@tutorial: https://example.com\n\n"));
+
+    // Scripts, which are added or removed, are added to or removed from the index
+    project.write("enemy.gd", "var c\n");
+    std::fs::remove_file(project.input().join("ui/button.gd")).unwrap();
+    project.run(&["--title", "My Game"]);
+    let index = std::fs::read_to_string(project.output().join("index.md")).unwrap();
+    assert!(index.contains("* [Player](player.gd.md), extends Node  \n  Moves the player.  \n"));
+    assert!(index.contains("* [enemy.gd](enemy.gd.md)  \n"));
+    assert!(!index.contains("button.gd"));
+}
+
+#[test]
+fn inheritance_page() {
+    let project = Project::new("inheritance");
+    project.write("actor.gd", "class_name Actor\nextends Node2D\n");
+    project.write("enemies/boss.gd", "extends \"../actor.gd\"\n");
+    project.write("a.gd", "class_name A\nextends B\n");
+    project.write("b.gd", "class_name B\nextends A\n");
+
+    let output = project.run(&["--title", "Game"]);
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Scripts extend each other in a cycle: A extends B extends A"));
+    let page = std::fs::read_to_string(project.output().join("inheritance.md")).unwrap();
+    assert!(page.starts_with(
+        "# Inheritance of Game\n\n* Node2D\n  * [Actor](actor.gd.md)\n    * [boss.gd](enemies/boss.gd.md)\n"
+    ));
+    assert!(
+        page.ends_with("## Cycles\n\n* [A](a.gd.md) extends [B](b.gd.md) extends [A](a.gd.md)\n")
+    );
+
+    // It belongs to the index
+    project.run(&["--no-index", "--clean"]);
+    assert!(!project.output().join("inheritance.md").exists());
+    project.run(&["--backend", "doxygen", "--clean"]);
+    assert!(!project.output().join("inheritance.h").exists());
+}
+
+#[test]
+fn inherited_members() {
+    let project = Project::new("inherited-members");
+    project.write(
+        "actor.gd",
+        "class_name Actor\nextends Node2D\nvar health\nfunc take_damage(amount):\n\tpass\nfunc die():\n\tpass\n",
+    );
+    project.write(
+        "enemies/enemy.gd",
+        "class_name Enemy\nextends Actor\nfunc die():\n\tpass\n",
+    );
+    project.write(
+        "enemies/boss.gd",
+        "extends \"enemy.gd\"\nfunc take_damage(amount):\n\tpass\n",
+    );
+    project.write("ghost.gd", "extends \"res://missing.gd\"\n");
+    project.write("ghost2.gd", "extends \"res://missing.gd\"\n");
+
+    // Members, which are declared again, are listed with the nearest script, which declares them
+    let output = project.run(&["--inherited-members", "linked"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr
+            .matches("Can't find the script \"res://missing.gd\"")
+            .count(),
+        1
+    );
+    let page = std::fs::read_to_string(project.output().join("enemies/boss.gd.md")).unwrap();
+    assert!(page.contains(
+        "Inherited from [Enemy](../enemies/enemy.gd.md): die  \n\nInherited from [Actor](../actor.gd.md): health  \n"
+    ));
+    assert!(!page.contains("take\\_damage, "));
+    let page = std::fs::read_to_string(project.output().join("actor.gd.md")).unwrap();
+    assert!(!page.contains("Inherited"));
+
+    // Pages are updated, when a script they extend changes
+    project.write(
+        "actor.gd",
+        "class_name Actor\nextends Node2D\nvar health\nvar armor\n",
+    );
+    project.run(&["--inherited-members", "linked"]);
+    let page = std::fs::read_to_string(project.output().join("enemies/boss.gd.md")).unwrap();
+    assert!(page.contains("Inherited from [Actor](../actor.gd.md): health, armor  \n"));
+
+    project.run(&["--inherited-members", "none"]);
+    let page = std::fs::read_to_string(project.output().join("enemies/boss.gd.md")).unwrap();
+    assert!(!page.contains("Inherited"));
+}
+
+#[test]
+fn mark_overrides() {
+    let project = Project::new("mark-overrides");
+    project.write(
+        "actor.gd",
+        "class_name Actor\nfunc take_damage(amount):\n\tpass\nfunc _process(delta):\n\tpass\n",
+    );
+    project.write(
+        "enemy.gd",
+        "extends Actor\nfunc take_damage(amount, source):\n\tpass\nfunc _process(delta):\n\tpass\nfunc _ready():\n\tpass\nfunc spawn():\n\tpass\n",
+    );
+
+    let output = project.run(&["--mark-overrides", "--show_prefixed"]);
+    assert!(output.stderr.is_empty());
+    let page = std::fs::read_to_string(project.output().join("enemy.gd.md")).unwrap();
+    assert!(page.contains(
+        "* take\\_damage(amount, source)  \nOverrides [Actor.take\\_damage](actor.gd.md)  \n"
+    ));
+    assert!(page.contains("* \\_process(delta)  \nOverrides [Actor.\\_process](actor.gd.md)  \n"));
+    assert!(page.contains("* \\_ready()  \nEngine virtual  \n"));
+    assert!(page.contains("* spawn()  \n  \n"));
+    let page = std::fs::read_to_string(project.output().join("actor.gd.md")).unwrap();
+    assert!(!page.contains("Overrides"));
+
+    // Other arguments still override the function, strict runs warn about them
+    let output = project.run_inputs(
+        &[&project.input()],
+        &["--mark-overrides", "--show_prefixed", "--fail-on-warning"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "enemy.gd:2: take_damage takes 2 arguments, but the function it overrides in Actor takes 1"
+    ));
+}
+
+#[test]
+fn autoloads() {
+    let project = Project::new("autoloads");
+    project.write(
+        "project.godot",
+        "config_version=5\n\n[autoload]\n\nGameState=\"*res://scripts/game_state.gd\"\nHud=\"*res://hud.tscn\"\nMissing=\"*res://scripts/missing.gd\"\n",
+    );
+    project.write(
+        "scripts/game_state.gd",
+        "## Keeps the score\nvar score = 0\n",
+    );
+    project.write("scripts/player.gd", "var health\n");
+
+    // The project is found above the documented directory
+    let output = project.run_inputs(&[Path::new("scripts")], &[]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(
+        "The autoload Missing refers to res://scripts/missing.gd, which isn't documented"
+    ));
+    assert!(!stderr.contains("Hud"));
+    let page = std::fs::read_to_string(project.output().join("game_state.gd.md")).unwrap();
+    assert!(
+        page.contains("## game\\_state.gd\n\n> Autoload singleton, accessible as `GameState`\n\n")
+    );
+    let page = std::fs::read_to_string(project.output().join("player.gd.md")).unwrap();
+    assert!(!page.contains("Autoload"));
+    let index = std::fs::read_to_string(project.output().join("index.md")).unwrap();
+    assert!(index.contains(
+        "## Autoloads\n\n* [GameState](game_state.gd.md), game\\_state.gd  \n\n## Scripts\n\n"
+    ));
+}
+
+#[test]
+fn verbosity_and_color() {
+    let project = Project::new("verbosity");
+    project.write("a.gd", "fucn foo():\n\tpass\n");
+    project.write("b.gd", "var b\n");
+
+    let output = project.run(&["--warn-unrecognized", "--exclude", "./b.gd", "-v"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Warning: a.gd, line 1"));
+    assert!(stderr.contains("b.gd: it matches the exclude pattern './b.gd'"));
+    assert!(stderr.contains(&format!(
+        "Wrote {}",
+        project.output().join("a.gd.md").display()
+    )));
+    // Output, which isn't a terminal, isn't colored
+    assert!(!stderr.contains('\x1b'));
+
+    let output = project.run(&["--warn-unrecognized", "--force", "-q"]);
+    assert!(output.stderr.is_empty());
+
+    let output = project.run(&["--warn-unrecognized", "--force", "--color", "always"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("\x1b["));
+}
+
+#[test]
+fn check_and_diff() {
+    let project = Project::new("check");
+    project.write("a.gd", "var a\nvar b\n");
+    project.write("b.gd", "var c\n");
+
+    let output = project.run_inputs(&[Path::new(".")], &["--check"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&format!(
+        "Missing {}",
+        project.output().join("a.gd.md").display()
+    )));
+    assert!(!project.output().exists());
+
+    project.run(&["--title", "Game"]);
+    let output = project.run(&["--check", "--title", "Game"]);
+    assert!(output.stdout.is_empty());
+
+    project.write("a.gd", "var a\nvar d\n");
+    std::fs::remove_file(project.input().join("b.gd")).unwrap();
+    let output = project.run_inputs(&[Path::new(".")], &["--check", "--diff", "--title", "Game"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let a = project.output().join("a.gd.md").display().to_string();
+    assert!(stdout.contains(&format!("Would change {}", a)));
+    assert!(stdout.contains(&format!("--- {}\n+++ {}\n@@ ", a, a)));
+    assert!(stdout.contains("\n-* b  \n+* d  \n"));
+    assert!(stdout.contains(&format!(
+        "Would change {}",
+        project.output().join("index.md").display()
+    )));
+    assert!(stdout.contains(&format!(
+        "Stale extra file {}",
+        project.output().join("b.gd.md").display()
+    )));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("The documentation is out of date"));
+
+    // Nothing was written
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    assert!(generated.contains("* b"));
+}
+
+#[test]
+fn dry_run_lists_files() {
+    let project = Project::new("dry-run");
+    project.write("a.gd", "class Inner:\n\tvar a\n");
+    project.write("b.gd", "var b\n");
+
+    let output = project.run(&["--dry-run", "--split-classes"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let a = project.output().join("a.gd.md").display().to_string();
+    assert!(stdout.contains(&format!("Would create {} (", a)));
+    assert!(stdout.contains(&format!(
+        "Would create {} (",
+        project.output().join("a_Inner.gd.md").display()
+    )));
+    assert!(!project.output().exists());
+
+    project.run(&[]);
+    project.write("a.gd", "var c\n");
+    std::fs::remove_file(project.input().join("b.gd")).unwrap();
+    let output = project.run(&["--dry-run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let size = std::fs::metadata(project.output().join("b.gd.md"))
+        .unwrap()
+        .len();
+    assert!(stdout.contains(&format!("Would overwrite {} (", a)));
+    assert!(!stdout.contains("b.gd.md"));
+    let output = project.run(&["--dry-run", "--clean"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&format!(
+        "Would remove {}",
+        project.output().join("b.gd.md").display()
+    )));
+
+    // Nothing was written or removed
+    let generated = std::fs::read_to_string(project.output().join("a.gd.md")).unwrap();
+    assert!(generated.contains("Inner"));
+    assert_eq!(
+        std::fs::metadata(project.output().join("b.gd.md"))
+            .unwrap()
+            .len(),
+        size
+    );
+
+    project.write("c.gd", "var (\n");
+    let output = project.run_inputs(&[Path::new(".")], &["--dry-run"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn clean_removes_only_generated_files() {
+    let project = Project::new("clean");
+    project.write("a.gd", "var a\n");
+    project.write("ui/b.gd", "var b\n");
+    project.write("ui/c.gd", "var c\n");
+    project.run(&[]);
+    std::fs::write(project.output().join("notes.md"), "Hand-written\n").unwrap();
+
+    // Files, which aren't cleaned, are still removed by a later run
+    std::fs::remove_file(project.input().join("a.gd")).unwrap();
+    project.run(&[]);
+    assert!(project.output().join("a.gd.md").exists());
+
+    std::fs::remove_dir_all(project.input().join("ui")).unwrap();
+    project.write("godotdoc_config.json", "{\"clean_output\": true}");
+    project.write("d.gd", "var d\n");
+    project.run(&[]);
+    assert!(!project.output().join("a.gd.md").exists());
+    assert!(!project.output().join("ui").exists());
+    assert!(project.output().join("d.gd.md").exists());
+    assert!(project.output().join("notes.md").exists());
+}
+
+#[test]
+fn unchanged_outputs_are_not_rewritten() {
+    let project = Project::new("unchanged");
+    project.write("a.gd", "var a\n");
+    project.write("b.gd", "var b\n");
+    project.run(&["--title", "Game"]);
+    let modified = |name: &str| {
+        std::fs::metadata(project.output().join(name))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    let before = ["a.gd.md", "index.md"].map(modified);
+
+    project.write("b.gd", "var c\n");
+    let output = project.run(&["--title", "Game", "--force", "-v"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let a = project.output().join("a.gd.md");
+    assert!(stderr.contains(&format!("Unchanged {}", a.display())));
+    assert!(!stderr.contains(&format!("Wrote {}", a.display())));
+    assert!(stderr.contains(&format!(
+        "Wrote {}",
+        project.output().join("b.gd.md").display()
+    )));
+    assert_eq!(modified("a.gd.md"), before[0]);
+    assert_eq!(modified("index.md"), before[1]);
+
+    project.run(&["--title", "Game"]);
+    let manifest = modified(".godotdoc-manifest.json");
+    project.run(&["--title", "Game", "--force"]);
+    assert_eq!(modified(".godotdoc-manifest.json"), manifest);
+}
+
+#[test]
+fn failed_scripts_dont_stop_the_others() {
+    let project = Project::new("failed");
+    project.write("a.gd", "var a = (]\n");
+    project.write("b.gd", "var b\n");
+
+    let output = project.run_inputs(&[Path::new(".")], &["--title", "Game"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to parse ./a.gd, line 1"));
+    assert!(stderr.contains("Failed to document 1 of 2 scripts"));
+    assert!(project.output().join("b.gd.md").exists());
+    let index = std::fs::read_to_string(project.output().join("index.md")).unwrap();
+    assert_eq!(
+        index,
+        "# Game\n\n* a.gd (failed to document)  \n* [b.gd](b.gd.md)  \n"
+    );
+
+    std::fs::remove_dir_all(project.output()).unwrap();
+    let output = project.run_inputs(&[Path::new(".")], &["--title", "Game", "--fail-fast"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to parse ./a.gd, line 1"));
+    assert!(!stderr.contains("Failed to document"));
+    assert!(!project.output().join("index.md").exists());
+}
+
+#[test]
+fn default_excludes() {
+    let project = Project::new("default-excludes");
+    project.write("a.gd", "var a\n");
+    project.write(".godot/b.gd", "var b\n");
+    project.write("ui/.hidden/c.gd", "var c\n");
+    project.write("addons/plugin/d.gd", "var d\n");
+
+    let output = project.run(&["-v"]);
+    assert!(project.output().join("a.gd.md").exists());
+    assert!(!project.output().join(".godot").exists());
+    assert!(!project.output().join("ui").exists());
+    assert!(!project.output().join("addons").exists());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&format!(
+        "Skipping {}: it matches the default exclude pattern './addons'",
+        project.input().join("addons").display()
+    )));
+
+    project.run(&["--no-default-excludes"]);
+    assert!(project.output().join(".godot/b.gd.md").exists());
+    assert!(project.output().join("ui/.hidden/c.gd.md").exists());
+    assert!(project.output().join("addons/plugin/d.gd.md").exists());
+
+    std::fs::remove_dir_all(project.output()).unwrap();
+    project.write("godotdoc_config.json", "{\"default_excludes\": false}");
+    project.run(&[]);
+    assert!(project.output().join("addons/plugin/d.gd.md").exists());
+}
+
+#[test]
+fn navigation() {
+    let project = Project::new("nav");
+    project.write("player.gd", "class_name Player\nvar a\n");
+    project.write("ui/menus/main.gd", "var b\n");
+    project.write("ui/button.gd", "var c\n");
+    project.write("z.gd", "var d\n");
+
+    project.run(&["--nav", "docsify", "--title", "Game"]);
+    let sidebar = std::fs::read_to_string(project.output().join("_sidebar.md")).unwrap();
+    assert_eq!(
+        sidebar,
+        "* [Game](index.md)\n* [Player](player.gd.md)\n* ui\n  * [button.gd](ui/button.gd.md)\n  * menus\n    * [main.gd](ui/menus/main.gd.md)\n* [z.gd](z.gd.md)\n"
+    );
+
+    project.run(&["--nav", "mkdocs", "--no-index", "--clean"]);
+    assert!(!project.output().join("_sidebar.md").exists());
+    let nav = std::fs::read_to_string(project.output().join("mkdocs_nav.yml")).unwrap();
+    assert_eq!(
+        nav,
+        "nav:\n  - \"Player\": \"player.gd.md\"\n  - \"ui\":\n    - \"button.gd\": \"ui/button.gd.md\"\n    - \"menus\":\n      - \"main.gd\": \"ui/menus/main.gd.md\"\n  - \"z.gd\": \"z.gd.md\"\n"
+    );
+
+    let output = project.run_inputs(
+        &[Path::new(".")],
+        &["--nav", "mkdocs", "--backend", "doxygen"],
+    );
+    assert!(!output.status.success());
+}
+
+#[test]
+fn grouped_index() {
+    let project = Project::new("group-by");
+    project.write("player.gd", "class_name Player\n");
+    project.write("ui/button.gd", "class_name UIButton\n");
+    project.write("ui/panel.gd", "class_name UIPanel\n");
+
+    project.run(&["--title", "Game", "--group-by", "class_name_prefix"]);
+    let index = std::fs::read_to_string(project.output().join("index.md")).unwrap();
+    assert_eq!(
+        index,
+        "# Game\n\n* [Player](player.gd.md)  \n\n## UI\n\n* [UIButton](ui/button.gd.md)  \n* [UIPanel](ui/panel.gd.md)  \n"
+    );
+
+    let output = project.run_inputs(
+        &[Path::new(".")],
+        &["--title", "Game", "--group-by", "size"],
+    );
+    assert!(!output.status.success());
+}
+
+#[cfg(unix)]
+#[test]
+fn symbolic_links() {
+    let project = Project::new("symlinks");
+    project.write("scripts/a.gd", "var a\n");
+    project.write("shared/b.gd", "var b\n");
+    let links = [
+        (".", "scripts/loop"),
+        ("../shared", "scripts/shared"),
+        ("../shared/b.gd", "scripts/c.gd"),
+    ];
+    for (target, link) in links.iter() {
+        // Creating links may not be permitted, then there is nothing to test
+        if std::os::unix::fs::symlink(target, project.input().join(link)).is_err() {
+            return;
+        }
+    }
+    let scripts = project.input().join("scripts");
+
+    let output = project.run_inputs(&[&scripts], &["-v"]);
+    assert!(output.status.success());
+    assert!(project.output().join("a.gd.md").exists());
+    assert!(project.output().join("c.gd.md").exists());
+    assert!(!project.output().join("shared").exists());
+    assert!(!project.output().join("loop").exists());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Skipping symbolic link to directory"));
+
+    let output = project.run_inputs(&[&scripts], &["--follow-symlinks"]);
+    assert!(output.status.success());
+    assert!(project.output().join("shared/b.gd.md").exists());
+    assert!(!project.output().join("loop").exists());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&format!(
+        "it links back to {}, which was already documented",
+        scripts.canonicalize().unwrap().display()
+    )));
+}
+
+#[test]
+fn since_filter() {
+    let project = Project::new("since-filter");
+    project.write(
+        "player.gd",
+        "# @since 1.2.0\nfunc jump():\n\tpass\n\n# @since 1.0\nfunc walk():\n\tpass\n\nfunc run():\n\tpass\n",
+    );
+
+    project.run(&[]);
+    let full = std::fs::read_to_string(project.output().join("player.gd.md")).unwrap();
+    assert!(full.contains("* jump() `since 1.2.0`"));
+    assert!(full.contains("* walk() `since 1.0`"));
+    assert!(full.contains("* run()"));
+
+    project.run(&["--since-filter", ">=1.2.0"]);
+    let filtered = std::fs::read_to_string(project.output().join("player.gd.md")).unwrap();
+    assert!(filtered.contains("* jump() `since 1.2.0`"));
+    assert!(!filtered.contains("walk"));
+    assert!(!filtered.contains("run"));
+
+    let output = project.run_inputs(&[&project.input()], &["--since-filter", ">=soon"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid version 'soon'"));
+}
+
+#[test]
+fn output_names() {
+    let project = Project::new("output-names");
+    project.write(
+        "Player_Controller.gd",
+        "const Button = preload(\"res://ui/Big_Button.gd\")\n",
+    );
+    project.write(
+        "ui/Big_Button.gd",
+        "const Player = preload(\"res://Player_Controller.gd\")\n",
+    );
+
+    project.run(&["--output-names", "strip_extension"]);
+    assert!(project.output().join("Player_Controller.md").is_file());
+    assert!(!project.output().join("Player_Controller.gd.md").exists());
+
+    project.run(&["--output-names", "slug", "--title", "Game", "--clean"]);
+    let page = std::fs::read_to_string(project.output().join("player-controller.md")).unwrap();
+    assert!(page.contains("(ui/big-button.md)"));
+    let page = std::fs::read_to_string(project.output().join("ui/big-button.md")).unwrap();
+    assert!(page.contains("(../player-controller.md)"));
+    let index = std::fs::read_to_string(project.output().join("index.md")).unwrap();
+    assert!(index.contains("(player-controller.md)"));
+    assert!(index.contains("(ui/big-button.md)"));
+    assert!(!project.output().join("Player_Controller.md").exists());
+
+    project.run(&["--output-names", "docs_{stem}.{ext}", "--flat", "--clean"]);
+    let page = std::fs::read_to_string(project.output().join("docs_Player_Controller.md")).unwrap();
+    assert!(page.contains("(ui__docs_Big_Button.md)"));
+    assert!(project.output().join("ui__docs_Big_Button.md").is_file());
+
+    let output = project.run_inputs(&[&project.input()], &["--output-names", "lowercase"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown output names 'lowercase'"));
+}
+
+#[test]
+fn output_names_reject_collisions() {
+    let project = Project::new("output-names-collision");
+    project.write("Player.gd", "var a\n");
+    project.write("player.gd", "var b\n");
+
+    project.run(&["--output-names", "strip_extension"]);
+    let output = project.run_inputs(&[Path::new(".")], &["--output-names", "slug"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("./Player.gd"), "{}", stderr);
+    assert!(stderr.contains("./player.gd"), "{}", stderr);
+    assert!(
+        stderr.contains("would both be documented as player.md"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn output_names_reject_generated_files() {
+    let project = Project::new("output-names-generated");
+    project.write("index.gd", "var a\n");
+    project.write("_sidebar.gd", "var b\n");
+
+    // Without the index the name is free
+    project.run_inputs(
+        &[Path::new("index.gd")],
+        &["--output-names", "strip_extension", "--no-index"],
+    );
+    assert!(project.output().join("index.md").is_file());
+    let output = project.run_inputs(
+        &[Path::new("index.gd")],
+        &["--output-names", "strip_extension"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("The documentation of index.gd would overwrite the generated index.md"),
+        "{}",
+        stderr
+    );
+
+    let output = project.run_inputs(
+        &[Path::new("_sidebar.gd")],
+        &[
+            "--output-names",
+            "strip_extension",
+            "--no-index",
+            "--nav",
+            "docsify",
+        ],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("generated _sidebar.md"));
+}
+
+#[test]
+fn warn_deprecated() {
+    let project = Project::new("warn-deprecated");
+    project.write(
+        "player.gd",
+        "# @deprecated Use move_to instead\nfunc move():\n\tpass\n\n# @deprecated\nexport var speed = 3\n",
+    );
+
+    let output = project.run(&[]);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("deprecated"));
+
+    // Scripts, which are up to date, are checked as well
+    let output = project.run(&["--warn-deprecated"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("player.gd, line 2: move is deprecated: Use move_to instead"));
+    assert!(stderr.contains("player.gd, line 6: speed is deprecated\n"));
+}
+
+#[test]
+fn todo_lists_undocumented_symbols() {
+    let project = Project::new("todo");
+    project.write(
+        "player.gd",
+        "## Speed\nvar speed\nvar health\nfunc move():\n\tpass\nclass Inner:\n\t## Documented\n\tvar a\n\tvar b\nvar _hidden\n",
+    );
+    project.write("ui/button.gd", "## Pressed\nsignal pressed\n");
+
+    let output = run_in(&project, &["--todo", "."]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with(
+        "player.gd\n  Classes:\n    Inner (line 6)\n  Variables:\n    health (line 3)\n    b (line 9)\n    _hidden (line 10)\n  Functions:\n    move (line 4)\n"
+    ));
+    assert!(!stdout.contains("button.gd"));
+    assert!(stdout.ends_with("5 symbols without a description in 1 of 2 scripts\n"));
+    assert!(!project.output().exists());
+
+    let output = run_in(
+        &project,
+        &["--todo", ".", "--hide_prefixed", "--error-format", "json"],
+    );
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["total"], 4);
+    assert_eq!(report["files"][0]["file"], "player.gd");
+    assert_eq!(
+        report["files"][0]["symbols"][1],
+        serde_json::json!({"type": "var", "name": "health", "line": 3})
+    );
+}
+
+/// Runs in the input directory without the default output directory
+fn run_in(project: &Project, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_godotdoc"))
+        .current_dir(project.input())
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn output_inside_input() {
+    let project = Project::new("output-inside-input");
+    project.write("player.gd", "var a\n");
+    // A backend could write scripts, or a script was copied into the output by hand
+    project.write("docs/stray.gd", "var b\n");
+
+    let output = run_in(&project, &[".", "-o", "docs", "-v"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Skipping ./docs: it is the output directory"));
+    assert!(project.input().join("docs/player.gd.md").is_file());
+    assert!(!project.input().join("docs/docs").exists());
+    assert!(!project.input().join("docs/stray.gd.md").exists());
+
+    // The output directory is recognized by its path, not by its name
+    let output = run_in(&project, &[".", "-o", "./docs/../docs/"]);
+    assert!(output.status.success());
+    assert!(!project.input().join("docs/docs").exists());
+}
+
+#[test]
+fn input_inside_output() {
+    let project = Project::new("input-inside-output");
+    project.write("src/player.gd", "var a\n");
+
+    let output = run_in(&project, &["src", "-o", "."]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(project.input().join("player.gd.md").is_file());
+    assert_eq!(
+        std::fs::read_to_string(project.input().join("src/player.gd")).unwrap(),
+        "var a\n"
+    );
+}
+
+#[test]
+fn output_equal_to_input() {
+    let project = Project::new("output-equal-to-input");
+    project.write("player.gd", "var a\n");
+
+    let output = run_in(&project, &[".", "-o", "."]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(project.input().join("player.gd.md").is_file());
+    let output = run_in(&project, &[".", "-o", "."]);
+    assert!(output.status.success());
+
+    // Outputs must never replace the sources
+    let output = run_in(&project, &[".", "-o", ".", "--output-names", "{stem}.gd"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("The documentation of ./player.gd would overwrite the script ./player.gd"));
+    assert_eq!(
+        std::fs::read_to_string(project.input().join("player.gd")).unwrap(),
+        "var a\n"
+    );
+}
+
+#[test]
+fn split_classes() {
+    let project = Project::new("split-classes");
+    project.write(
+        "player.gd",
+        "class_name Player\nvar a\n\n# The items\nclass Inventory:\n\tvar slots\n\n\tclass Slot:\n\t\tvar item\n\nclass Stats:\n\tvar hp\n",
+    );
+    project.write("plain.gd", "var b\n");
+
+    project.run(&["--split-classes", "--title", "Game"]);
+    let page = std::fs::read_to_string(project.output().join("player.gd.md")).unwrap();
+    assert!(page.contains("* [Inventory](player_Inventory.gd.md)  \n"));
+    assert!(page.contains("* [Stats](player_Stats.gd.md)  \n"));
+    assert!(page.contains("The items"));
+    assert!(!page.contains("slots"));
+    let page = std::fs::read_to_string(project.output().join("player_Inventory.gd.md")).unwrap();
+    assert!(page.starts_with("## Inventory\n\nDefined in [Player](player.gd.md)  \n"));
+    assert!(page.contains("* slots"));
+    // Classes in inner classes stay on the page of their class
+    assert!(page.contains("* Slot"));
+    assert!(!project.output().join("player_Slot.gd.md").exists());
+
+    // Scripts without inner classes get a single page, the index only lists scripts
+    let files = std::fs::read_dir(project.output())
+        .unwrap()
+        .filter(|x| {
+            x.as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with("plain")
+        })
+        .count();
+    assert_eq!(files, 1);
+    let index = std::fs::read_to_string(project.output().join("index.md")).unwrap();
+    assert_eq!(
+        index,
+        "# Game\n\n* [plain.gd](plain.gd.md)  \n* [Player](player.gd.md)  \n"
+    );
+
+    project.write(
+        "player.gd",
+        "class_name Player\nclass Inventory:\n\tvar slots\n",
+    );
+    project.run(&["--split-classes", "--clean", "--output-names", "slug"]);
+    assert!(project.output().join("player-inventory.md").is_file());
+    let page = std::fs::read_to_string(project.output().join("player.md")).unwrap();
+    assert!(page.contains("* [Inventory](player-inventory.md)"));
+    assert!(!project.output().join("player_Stats.gd.md").exists());
+}
+
+#[test]
+fn split_classes_reject_collisions() {
+    let project = Project::new("split-classes-collision");
+    project.write("player.gd", "var a\n\nclass Inv:\n\tvar slots\n");
+    project.write("player_Inv.gd", "var b\n");
+
+    let output = project.run_inputs(&[Path::new(".")], &["--split-classes"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "and the class Inv of ./player.gd would both be documented as player_Inv.gd.md"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("./player_Inv.gd and"), "{}", stderr);
+    assert!(!project.output().join("player_Inv.gd.md").exists());
+
+    // Without split classes the inner class stays on the page of its script
+    project.run(&[]);
+}
+
+#[test]
+fn exit_codes() {
+    let project = Project::new("exit-codes");
+    project.write("player.gd", "var a\n");
+
+    let output = run_in(&project, &["--help"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("EXIT CODES:"));
+    assert_eq!(
+        project.run_inputs(&[Path::new(".")], &[]).status.code(),
+        Some(0)
+    );
+
+    // Bad usage and config
+    assert_eq!(
+        project
+            .run_inputs(&[Path::new(".")], &["--no-such-flag"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        project
+            .run_inputs(&[Path::new(".")], &["--group-by", "size"])
+            .status
+            .code(),
+        Some(2)
+    );
+    let output = project.run_inputs(&[Path::new(".")], &["--since-filter", "soon"]);
+    assert_eq!(output.status.code(), Some(2));
+    project.write("godotdoc.toml", "flat = \"yes\"\n");
+    assert_eq!(
+        project.run_inputs(&[Path::new(".")], &[]).status.code(),
+        Some(2)
+    );
+    std::fs::remove_file(project.input().join("godotdoc.toml")).unwrap();
+
+    // Scripts, which can't be documented
+    project.write("broken.gd", "func foo(:\n");
+    assert_eq!(
+        project.run_inputs(&[Path::new(".")], &[]).status.code(),
+        Some(1)
+    );
+    std::fs::remove_file(project.input().join("broken.gd")).unwrap();
+
+    // The output directory can't be created where a file is
+    std::fs::write(project.root.join("file"), "").unwrap();
+    let output = run_in(&project, &[".", "-o", "../file/docs"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to write"));
+}
+
+#[test]
+fn fail_on_warning() {
+    let project = Project::new("fail-on-warning");
+    project.write("player.gd", "class_name Player\nvar a\n");
+    project.write("ui/player.gd", "class_name Player\nvar b\n");
+
+    let output = project.run(&[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(
+        "The class_name Player is declared by several scripts: ./player.gd, ./ui/player.gd"
+    ));
+
+    // Warnings count, even if they aren't printed
+    let output = project.run_inputs(&[&project.input()], &["--fail-on-warning", "--quiet"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Failing because of --fail-on-warning, warnings: 1"));
+
+    project.write("ui/player.gd", "class_name PlayerButton\nvar b\n");
+    assert_eq!(
+        project
+            .run_inputs(&[&project.input()], &["--fail-on-warning"])
+            .status
+            .code(),
+        Some(0)
+    );
+}
+
+#[test]
+fn doxygen_index() {
+    let project = Project::new("doxygen-index");
+    project.write(
+        "player.gd",
+        "class_name Player\nextends KinematicBody2D\nvar a\n",
+    );
+    project.write("ui/button.gd", "var b\n");
+    project.write("broken.gd", "func foo(:\n");
+
+    let output = project.run_inputs(
+        &[&project.input()],
+        &[
+            "--backend",
+            "all",
+            "--title",
+            "Game",
+            "--group-by",
+            "directory",
+        ],
+    );
+    assert!(!output.status.success());
+    let index = std::fs::read_to_string(project.output().join("index.h")).unwrap();
+    assert_eq!(
+        index,
+        "/**\n * \\mainpage Game\n *\n * - broken.gd (failed to document)\n * - \\ref Player, extends KinematicBody2D\n * \\section group_1 ui\n *\n * - \\ref button\n */\n"
+    );
+    assert!(project.output().join("index.md").is_file());
+}
+
+#[test]
+fn invalid_utf8() {
+    let project = Project::new("invalid-utf8");
+    std::fs::create_dir_all(project.input()).unwrap();
+    std::fs::write(
+        project.input().join("bad.gd"),
+        b"## Caf\xe9 \xff\nvar speed = \"a\xfeb\"\nvar after\n",
+    )
+    .unwrap();
+
+    // Invalid bytes are replaced, the rest of the script is still documented
+    let output = project.run(&[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("bad.gd, line 1: invalid UTF-8 was replaced"));
+    assert!(stderr.contains("bad.gd, line 2: invalid UTF-8 was replaced"));
+    let page = std::fs::read_to_string(project.output().join("bad.gd.md")).unwrap();
+    assert!(page.contains("* speed = `\"a\u{fffd}b\"`"));
+    assert!(page.contains("Caf\u{fffd} \u{fffd}"));
+    assert!(page.contains("* after"));
+
+    let output = project.run_inputs(&[Path::new(".")], &["--strict-encoding", "--force"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("bad.gd, line 1: invalid UTF-8"));
+}
+
+#[cfg(unix)]
+#[test]
+fn invalid_utf8_file_names() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let project = Project::new("invalid-utf8-names");
+    std::fs::create_dir_all(project.input()).unwrap();
+    let name = std::ffi::OsStr::from_bytes(b"n\xffme.gd");
+    // Some file systems only allow UTF-8 names, then there is nothing to test
+    if std::fs::write(project.input().join(name), "var x\n").is_err() {
+        return;
+    }
+
+    project.run(&[]);
+    let page = std::fs::read_to_string(project.output().join("n\u{fffd}me.gd.md")).unwrap();
+    assert!(page.contains("* x"));
+}
@@ -0,0 +1,14 @@
+const BASE = 10
+
+## Explicit, auto-incremented and constant values
+enum Mixed {
+	FIRST, # Starts at zero
+	SECOND,
+	EXPLICIT = 5, # Set in the source
+	AFTER_EXPLICIT,
+	FROM_CONSTANT = BASE, # The value of BASE
+	AFTER_CONSTANT,
+	SAME_AS = SECOND,
+}
+
+enum {UNNAMED_A, UNNAMED_B = 3}
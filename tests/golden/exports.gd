@@ -0,0 +1,25 @@
+extends Node
+
+# Godot 3 enum
+export(int, "Up", "Down") var direction = 0
+# Godot 3 flags
+export(int, FLAGS, "Fire", "Water") var elements = 0
+# Godot 3 range
+export(float, 0, 1, 0.1) var volume = 0.5
+export(String, FILE, "*.txt") var notes
+# Options containing a comma
+export(String, "Block, parry", "Dodge") var action
+
+## Godot 4 enum
+@export_enum("Warrior", "Magician:3") var class_type: int
+## Godot 4 flags
+@export_flags("Fire", "Water") var spells = 0
+## Godot 4 range
+@export_range(0, 100) var health: int = 100
+@export_range(-10, 10, 0.5, "or_greater") var offset: float
+@export_file("*.png") var icon: String
+## Godot 4 annotations, which are newer than GodotDoc
+@export_future(1, "a") var future: int
+@export_storage var stored = 1
+@export_group("Stats")
+@export var speed: float
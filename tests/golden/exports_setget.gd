@@ -0,0 +1,12 @@
+extends Node
+
+## Health of the player
+export(int) var health = 100 setget set_health, get_health
+# Godot 3 range with a setter only
+export(int, 0, 10) var armor = 5 setget set_armor
+# Godot 3 enum with a getter only
+export(String, "Easy", "Hard") var difficulty = "Easy" setget , get_difficulty
+# Export type and type hint
+export(float) var speed: float = 2.0 setget set_speed, get_speed
+# Without a default value
+export(Texture) var icon setget set_icon
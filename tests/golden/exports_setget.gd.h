@@ -0,0 +1,48 @@
+/**
+ * \file
+ * Generated from exports_setget.gd
+ */
+
+/**
+ * \class exports_setget
+ */
+class exports_setget : public Node {
+public:
+    /** \name Exports */
+    /** @{ */
+    /**
+     * Health of the player
+     * Defaults to `100`
+     * Getter: get_health()
+     * Setter: set_health()
+     */
+    int health;
+    /**
+     * Godot 3 range with a setter only
+     * Range: 0–10
+     * Defaults to `5`
+     * Setter: set_armor()
+     */
+    int armor;
+    /**
+     * Godot 3 enum with a getter only
+     * Options: "Easy", "Hard"
+     * Defaults to `"Easy"`
+     * Getter: get_difficulty()
+     */
+    String difficulty;
+    /**
+     * Export type and type hint
+     * Defaults to `2.0`
+     * Getter: get_speed()
+     * Setter: set_speed()
+     */
+    float speed;
+    /**
+     * Without a default value
+     * Setter: set_icon()
+     */
+    Texture icon;
+    /** @} */
+
+};
@@ -0,0 +1,11 @@
+extends Node
+
+## Emitted when the player takes damage, before the health is updated.
+## @param amount How much health is lost
+## @param source The node, which dealt the damage
+signal damaged(amount: int, source: Node)
+
+## Emitted once the health reaches zero
+signal died
+
+signal healed(amount)
@@ -0,0 +1,32 @@
+/**
+ * \file
+ * Generated from signals.gd
+ */
+
+/**
+ * \class signals
+ */
+class signals : public Node {
+public:
+    /** \name Signals */
+    /** @{ */
+    /**
+     * \fn void damaged(int amount, Node source)
+     * Emitted when the player takes damage, before the health is updated.
+     * @param amount How much health is lost
+     * @param source The node, which dealt the damage
+     */
+    void damaged(int amount, Node source);
+    /**
+     * \fn void died()
+     * Emitted once the health reaches zero
+     */
+    void died();
+    /**
+     * \fn void healed(Variant amount)
+     * \param amount
+     */
+    void healed(Variant amount);
+    /** @} */
+
+};
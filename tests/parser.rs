@@ -0,0 +1,1302 @@
+use std::collections::HashMap;
+
+use godotdoc::parser::{EnumValue, FunctionArgument};
+use godotdoc::{
+    parse_source, parse_str, DocumentationData, EntryType, ParseErrorKind, ParseOptions,
+    ShowPrefixed, SinceFilter, Symbol, SymbolArgs, Version,
+};
+
+fn parse(source: &str) -> DocumentationData {
+    parse_with(source, &ParseOptions::default())
+}
+
+fn parse_with(source: &str, options: &ParseOptions) -> DocumentationData {
+    parse_str("test.gd", source, options).unwrap()
+}
+
+fn symbols(data: &DocumentationData, entry_type: EntryType) -> Vec<&Symbol> {
+    data.entries
+        .iter()
+        .filter(|entry| entry.entry_type == entry_type)
+        .flat_map(|entry| entry.symbols.iter())
+        .collect()
+}
+
+fn names(data: &DocumentationData, entry_type: EntryType) -> Vec<&str> {
+    symbols(data, entry_type)
+        .iter()
+        .map(|symbol| symbol.name.as_str())
+        .collect()
+}
+
+fn argument(name: &str, value_type: Option<&str>, default_value: Option<&str>) -> FunctionArgument {
+    FunctionArgument {
+        name: name.to_string(),
+        value_type: value_type.map(|s| s.to_string()),
+        default_value: default_value.map(|s| s.to_string()),
+    }
+}
+
+#[test]
+fn empty_source() {
+    let data = parse("");
+    assert_eq!(data.source_file, "test.gd");
+    assert!(data.entries.is_empty());
+    assert!(data.dependencies.is_empty());
+}
+
+#[test]
+fn parse_source_reads_any_reader() {
+    let source = "func foo():\n\tpass\n";
+    let data = parse_source("test.gd", source.as_bytes(), &ParseOptions::default()).unwrap();
+    assert_eq!(data, parse(source));
+}
+
+#[test]
+fn function_signature() {
+    let data = parse("func foo(a, b: int, c := 2, d: String = \"x\") -> bool:\n\treturn true\n");
+    let functions = symbols(&data, EntryType::FUNC);
+    assert_eq!(functions.len(), 1);
+    assert_eq!(functions[0].name, "foo");
+    match &functions[0].arg {
+        Some(SymbolArgs::FunctionArgs(args)) => {
+            assert_eq!(
+                args.arguments,
+                vec![
+                    argument("a", None, None),
+                    argument("b", Some("int"), None),
+                    argument("c", None, Some("2")),
+                    argument("d", Some("String"), Some("\"x\"")),
+                ]
+            );
+            assert_eq!(args.return_type.as_deref(), Some("bool"));
+        }
+        arg => panic!("Expected function arguments, got {:?}", arg),
+    }
+}
+
+#[test]
+fn one_line_function_body() {
+    let data = parse("func foo(): return 1\nfunc bar():\n\tpass\n");
+    assert_eq!(names(&data, EntryType::FUNC), vec!["foo", "bar"]);
+}
+
+#[test]
+fn comments_are_attached() {
+    let data = parse("# First line\n# Second line\nvar foo # Trailing\n");
+    let variables = symbols(&data, EntryType::VAR);
+    assert_eq!(
+        variables[0].text,
+        vec!["First line", "Second line", "Trailing"]
+    );
+}
+
+#[test]
+fn hide_and_show_override_the_prefix() {
+    let data = parse("# [Hide]\nfunc foo():\n\tpass\n# [Show]\nfunc _bar():\n\tpass\n");
+    assert_eq!(names(&data, EntryType::FUNC), vec!["_bar"]);
+}
+
+#[test]
+fn show_prefixed_per_type() {
+    let source = "func _foo():\n\tpass\nvar _bar\n";
+    let options = ParseOptions {
+        show_prefixed: ShowPrefixed::all(false),
+        ..ParseOptions::default()
+    };
+    let data = parse_with(source, &options);
+    assert!(names(&data, EntryType::FUNC).is_empty());
+    assert!(names(&data, EntryType::VAR).is_empty());
+
+    let mut per_type = HashMap::new();
+    per_type.insert("func".to_string(), true);
+    let options = ParseOptions {
+        show_prefixed: ShowPrefixed::new(false, per_type).unwrap(),
+        ..ParseOptions::default()
+    };
+    let data = parse_with(source, &options);
+    assert_eq!(names(&data, EntryType::FUNC), vec!["_foo"]);
+    assert!(names(&data, EntryType::VAR).is_empty());
+}
+
+#[test]
+fn show_prefixed_rejects_unknown_types() {
+    let mut per_type = HashMap::new();
+    per_type.insert("function".to_string(), true);
+    assert!(ShowPrefixed::new(true, per_type).is_err());
+}
+
+#[test]
+fn enum_values() {
+    let data = parse("enum Foo {\n\tA, # First\n\tB = 5,\n\tC\n}\n");
+    let enums = symbols(&data, EntryType::ENUM);
+    assert_eq!(enums[0].name, "Foo");
+    match &enums[0].arg {
+        Some(SymbolArgs::EnumArgs(values)) => assert_eq!(
+            values,
+            &vec![
+                EnumValue {
+                    name: "A".to_string(),
+                    value: 0,
+                    expression: None,
+                    text: vec!["First".to_string()],
+                    line: 2,
+                },
+                EnumValue {
+                    name: "B".to_string(),
+                    value: 5,
+                    expression: None,
+                    text: vec![],
+                    line: 3,
+                },
+                EnumValue {
+                    name: "C".to_string(),
+                    value: 6,
+                    expression: None,
+                    text: vec![],
+                    line: 4,
+                },
+            ]
+        ),
+        arg => panic!("Expected enum values, got {:?}", arg),
+    }
+
+    // Comments above the enum describe the enum, not its first value
+    let data = parse("# Foos\nenum Foo {\n\t# First\n\tA\n}\n# Bars\nenum Bar {B}\n");
+    let enums = symbols(&data, EntryType::ENUM);
+    assert_eq!(enums[0].text, vec!["Foos"]);
+    assert_eq!(enums[1].text, vec!["Bars"]);
+    match (&enums[0].arg, &enums[1].arg) {
+        (Some(SymbolArgs::EnumArgs(foo)), Some(SymbolArgs::EnumArgs(bar))) => {
+            assert_eq!(foo[0].text, vec!["First"]);
+            assert!(bar[0].text.is_empty());
+        }
+        args => panic!("Expected enum values, got {:?}", args),
+    }
+}
+
+#[test]
+fn braces_in_enums() {
+    let values = |data: &DocumentationData| match &symbols(data, EntryType::ENUM)[0].arg {
+        Some(SymbolArgs::EnumArgs(values)) => values
+            .iter()
+            .map(|v| (v.name.clone(), v.value, v.text.clone()))
+            .collect::<Vec<_>>(),
+        arg => panic!("Expected enum values, got {:?}", arg),
+    };
+    // A '}' in a comment doesn't end the enum
+    let data = parse(
+        "enum Odd {\n\tA, # Ends with }\n\tWEIRD = 3, # { and } in a comment\n\tB,\n}\nvar after\n",
+    );
+    assert_eq!(
+        values(&data),
+        vec![
+            ("A".to_string(), 0, vec!["Ends with }".to_string()]),
+            (
+                "WEIRD".to_string(),
+                3,
+                vec!["{ and } in a comment".to_string()]
+            ),
+            ("B".to_string(), 4, vec![]),
+        ]
+    );
+    assert_eq!(names(&data, EntryType::VAR), vec!["after"]);
+
+    // The '}' on its own line after a trailing comma
+    let data = parse("enum Trailing {\n\tA,\n\tB,\n\t}\n");
+    assert_eq!(values(&data).len(), 2);
+    assert_eq!(symbols(&data, EntryType::ENUM)[0].end_line, 4);
+}
+
+#[test]
+fn cross_file_enum_constants() {
+    let stats = parse("class_name Stats\nconst MAX_LEVEL = 10\nconst NAME = \"stats\"\nenum Kind {A, B = 4}\nenum {SMALL = 2}\n");
+    let constants = stats.qualified_constants();
+    assert!(constants.contains(&("Stats.MAX_LEVEL".to_string(), "10".to_string())));
+    assert!(constants.contains(&("Stats.Kind.B".to_string(), "4".to_string())));
+    assert!(constants.contains(&("Stats.SMALL".to_string(), "2".to_string())));
+    assert!(parse("const A = 1\n").qualified_constants().is_empty());
+
+    let values = |data: &DocumentationData| match &symbols(data, EntryType::ENUM)[0].arg {
+        Some(SymbolArgs::EnumArgs(values)) => values
+            .iter()
+            .map(|v| (v.value, v.expression.clone()))
+            .collect::<Vec<_>>(),
+        arg => panic!("Expected enum values, got {:?}", arg),
+    };
+    let source = "enum Level {\n\tTOP = Stats.MAX_LEVEL,\n\tOVER,\n\tKIND = Stats . Kind . B,\n}\n";
+    let options = ParseOptions {
+        constants: constants.into_iter().collect(),
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        values(&parse_with(source, &options)),
+        vec![(10, None), (11, None), (4, None)]
+    );
+
+    // Without the constants of the other script, the values are kept as they are written
+    assert_eq!(
+        values(&parse(source)),
+        vec![
+            (0, Some("Stats.MAX_LEVEL".to_string())),
+            (0, Some("Stats.MAX_LEVEL + 1".to_string())),
+            (0, Some("Stats . Kind . B".to_string())),
+        ]
+    );
+    let data = parse("enum {A = Stats.MAX_LEVEL, B, C, D = A, E = 7, F}\n");
+    assert_eq!(
+        values(&data),
+        vec![
+            (0, Some("Stats.MAX_LEVEL".to_string())),
+            (0, Some("Stats.MAX_LEVEL + 1".to_string())),
+            (0, Some("Stats.MAX_LEVEL + 2".to_string())),
+            (0, Some("Stats.MAX_LEVEL".to_string())),
+            (7, None),
+            (8, None),
+        ]
+    );
+
+    // Names without a scope are still expected in the script
+    assert!(parse_str("test.gd", "enum {A = MISSING}\n", &ParseOptions::default()).is_err());
+}
+#[test]
+fn export_and_setget() {
+    let data = parse("export(int, 1, 8) var foo = 5\nvar bar setget set_bar, get_bar\n");
+    match &symbols(&data, EntryType::EXPORT)[0].arg {
+        Some(SymbolArgs::ExportArgs(args)) => {
+            assert_eq!(args.value_type.as_deref(), Some("int"));
+            assert_eq!(args.options, vec!["1", "8"]);
+            assert_eq!(args.assignment.as_deref(), Some("5"));
+        }
+        arg => panic!("Expected export arguments, got {:?}", arg),
+    }
+    match &symbols(&data, EntryType::VAR)[0].arg {
+        Some(SymbolArgs::VariableArgs(args)) => {
+            assert_eq!(args.setter.as_deref(), Some("set_bar"));
+            assert_eq!(args.getter.as_deref(), Some("get_bar"));
+        }
+        arg => panic!("Expected variable arguments, got {:?}", arg),
+    }
+}
+
+#[test]
+fn setget_whitespace() {
+    let data = parse(
+        "var a = 1  setget  set_a\nvar b: int\tsetget\tset_b ,get_b # comment\nvar c setget ,get_c\nvar my_setget = 2\n",
+    );
+    let variables = symbols(&data, EntryType::VAR)
+        .iter()
+        .map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::VariableArgs(args)) => (
+                symbol.name.as_str(),
+                args.assignment.as_deref(),
+                args.setter.as_deref(),
+                args.getter.as_deref(),
+            ),
+            arg => panic!("Expected variable arguments, got {:?}", arg),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variables,
+        vec![
+            ("a", Some("1"), Some("set_a"), None),
+            ("b", None, Some("set_b"), Some("get_b")),
+            ("c", None, None, Some("get_c")),
+            ("my_setget", Some("2"), None, None),
+        ]
+    );
+}
+
+#[test]
+fn node_paths() {
+    let paths = [
+        "$UI/Label",
+        "$\"Path With Spaces/Label\"",
+        "$'A # B'",
+        "$Sprite:texture",
+        "$\"../Sibling\"",
+        "%Player",
+        "%\"Unique Name\"",
+        "$A/%B",
+        "get_node(\"A/B\")",
+    ];
+    let source = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| format!("@onready var v{} := {} # Comment\n", i, path))
+        .collect::<String>();
+    let data = parse(&source);
+    let assignments = symbols(&data, EntryType::VAR)
+        .iter()
+        .map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::VariableArgs(args)) => args.assignment.clone().unwrap(),
+            arg => panic!("Expected variable arguments, got {:?}", arg),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(assignments, paths);
+
+    let source = format!("func foo(a = {}):\n\tpass\n", paths.join(", a = "));
+    let data = parse(&source);
+    match &symbols(&data, EntryType::FUNC)[0].arg {
+        Some(SymbolArgs::FunctionArgs(args)) => assert_eq!(
+            args.arguments
+                .iter()
+                .map(|x| x.default_value.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            paths
+        ),
+        arg => panic!("Expected function arguments, got {:?}", arg),
+    }
+}
+
+#[test]
+fn typed_collections() {
+    let data = parse(
+        "var inventory: Dictionary[String, int]\nvar nested: Dictionary[String, Array[int]] = {}\nconst POINTS: Array[Vector2] = [Vector2(1, 2)]\n@export var nodes: Dictionary[StringName, Node]\nexport var ids: Array[int] = [1, 2]\n",
+    );
+    let types = symbols(&data, EntryType::VAR)
+        .into_iter()
+        .chain(symbols(&data, EntryType::CONST))
+        .chain(symbols(&data, EntryType::EXPORT))
+        .map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::VariableArgs(args)) => {
+                (args.value_type.clone(), args.assignment.clone())
+            }
+            Some(SymbolArgs::ExportArgs(args)) => {
+                (args.value_type.clone(), args.assignment.clone())
+            }
+            arg => panic!("Expected variable arguments, got {:?}", arg),
+        })
+        .collect::<Vec<_>>();
+    let typed = |value_type: &str, assignment: Option<&str>| {
+        (
+            Some(value_type.to_string()),
+            assignment.map(|x| x.to_string()),
+        )
+    };
+    assert_eq!(
+        types,
+        vec![
+            typed("Dictionary[String, int]", None),
+            typed("Dictionary[String, Array[int]]", Some("{}")),
+            typed("Array[Vector2]", Some("[Vector2(1, 2)]")),
+            typed("Dictionary[StringName, Node]", None),
+            typed("Array[int]", Some("[1, 2]")),
+        ]
+    );
+}
+
+#[test]
+fn assignments_with_comparisons() {
+    let data = parse(
+        "var ok = a == b\nvar z := 1\nvar t: int = a if b >= c else d\nconst NE = a != b\nvar y = foo().bar(x <= 2, 3)\n",
+    );
+    let variables = symbols(&data, EntryType::VAR)
+        .into_iter()
+        .chain(symbols(&data, EntryType::CONST))
+        .map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::VariableArgs(args)) => (
+                symbol.name.as_str(),
+                args.value_type.as_deref(),
+                args.assignment.as_deref(),
+            ),
+            arg => panic!("Expected variable arguments, got {:?}", arg),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variables,
+        vec![
+            ("ok", None, Some("a == b")),
+            ("z", None, Some("1")),
+            ("t", Some("int"), Some("a if b >= c else d")),
+            ("y", None, Some("foo().bar(x <= 2, 3)")),
+            ("NE", None, Some("a != b")),
+        ]
+    );
+}
+
+#[test]
+fn annotations() {
+    let data = parse("@onready var foo = $Node\n@export_range(0, 10) var bar := 3\n");
+    let variables = symbols(&data, EntryType::VAR);
+    assert_eq!(variables[0].name, "foo");
+    assert_eq!(variables[0].annotations[0].name, "onready");
+    assert_eq!(names(&data, EntryType::EXPORT), vec!["bar"]);
+}
+
+#[test]
+fn stacked_annotations() {
+    let data = parse(
+        "@onready\n@rpc(\"any_peer\", \"call_local\") @static_unload\n@warning_ignore(\"unused\")\nvar foo\n\
+        @onready @export var bar = 1\nvar plain\n",
+    );
+    let variables = symbols(&data, EntryType::VAR);
+    let annotations: Vec<_> = variables[0]
+        .annotations
+        .iter()
+        .map(|a| (a.name.as_str(), a.arguments.clone()))
+        .collect();
+    assert_eq!(
+        annotations,
+        vec![
+            ("onready", vec![]),
+            (
+                "rpc",
+                vec!["\"any_peer\"".to_string(), "\"call_local\"".to_string()]
+            ),
+            ("static_unload", vec![]),
+        ]
+    );
+    let exports = symbols(&data, EntryType::EXPORT);
+    let names: Vec<_> = exports[0].annotations.iter().map(|a| &a.name).collect();
+    assert_eq!(names, vec!["onready", "export"]);
+    // Annotations only belong to the next declaration
+    assert!(variables[1].annotations.is_empty());
+}
+
+#[test]
+fn script_annotations() {
+    let data = parse("@tool\n@icon(\"res://icon.svg\")\nextends Node\n\n@tool\nvar a = 1\n");
+    assert!(symbols(&data, EntryType::VAR)[0].annotations.is_empty());
+    assert_eq!(data.base.as_deref(), Some("Node"));
+}
+
+#[test]
+fn export_hints() {
+    let data = parse(
+        "export(int, \"A\") var old\n@export var plain\n@export_range(0, 10) var range\n@export_future(1, \"a\") var future\n@export_storage var stored\n@export_group(\"Stats\")\n@export var speed\n@export_category(\"Misc\")\nvar other\n",
+    );
+    let hints = symbols(&data, EntryType::EXPORT)
+        .iter()
+        .map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::ExportArgs(args)) => (
+                symbol.name.as_str(),
+                args.export_hint.as_deref(),
+                args.options.clone(),
+            ),
+            arg => panic!("Expected export arguments, got {:?}", arg),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hints,
+        vec![
+            ("old", None, vec!["\"A\"".to_string()]),
+            ("plain", None, vec![]),
+            (
+                "range",
+                Some("range"),
+                vec!["0".to_string(), "10".to_string()]
+            ),
+            (
+                "future",
+                Some("future"),
+                vec!["1".to_string(), "\"a\"".to_string()]
+            ),
+            ("stored", Some("storage"), vec![]),
+            ("speed", None, vec![]),
+        ]
+    );
+    // Groups and categories only arrange the exports in the inspector
+    assert_eq!(names(&data, EntryType::VAR), vec!["other"]);
+}
+
+#[test]
+fn lambda_assignments() {
+    let data = parse(
+        "var callback := func(x): return x * 2\nvar other = 1\nvar multi = func(a, b):\n\tvar local = a\n\treturn local + b\nvar after\nclass A:\n\tvar inner = func():\n\t\tpass\n\tvar inner_after\n",
+    );
+    let variables = symbols(&data, EntryType::VAR)
+        .iter()
+        .map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::VariableArgs(args)) => {
+                (symbol.name.as_str(), args.assignment.as_deref())
+            }
+            arg => panic!("Expected variable arguments, got {:?}", arg),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variables,
+        vec![
+            ("callback", Some("func(x): return x * 2")),
+            ("other", Some("1")),
+            ("multi", Some("func(a, b):")),
+            ("after", None),
+        ]
+    );
+    // The body of the lambda isn't mistaken for the end of the class
+    match &symbols(&data, EntryType::CLASS)[0].arg {
+        Some(SymbolArgs::ClassArgs(class)) => {
+            let names = class.entries[0].symbols.iter().map(|x| x.name.as_str());
+            assert_eq!(names.collect::<Vec<_>>(), vec!["inner", "inner_after"]);
+        }
+        arg => panic!("Expected class arguments, got {:?}", arg),
+    }
+}
+
+#[test]
+fn inner_classes() {
+    let data =
+        parse("class Foo extends Node:\n\tvar a\n\n\tclass Bar:\n\t\tvar b\n\n\tvar c\nvar d\n");
+    let classes = symbols(&data, EntryType::CLASS);
+    assert_eq!(classes.len(), 1);
+    assert_eq!((classes[0].line, classes[0].end_line), (1, 7));
+    match &classes[0].arg {
+        Some(SymbolArgs::ClassArgs(class)) => {
+            assert_eq!(class.base.as_deref(), Some("Node"));
+            let entry_types: Vec<_> = class.entries.iter().map(|e| &e.entry_type).collect();
+            assert_eq!(entry_types, vec![&EntryType::CLASS, &EntryType::VAR]);
+        }
+        arg => panic!("Expected class arguments, got {:?}", arg),
+    }
+    assert_eq!(names(&data, EntryType::VAR), vec!["d"]);
+}
+
+#[test]
+fn line_numbers_with_continuations_and_inner_classes() {
+    let source = "extends Node\n\
+        var a = 1 + \\\n\t2\n\
+        class Outer:\n\
+        \tconst B = [\n\t\t1,\n\t]\n\
+        \tclass Inner:\n\
+        \t\tfunc c(x,\n\t\t\t\ty):\n\
+        \t\t\tpass\n\
+        \n\
+        \tvar d = \\\n\t\t3\n\
+        func e():\n\
+        \tpass\n";
+    let data = parse(source);
+    let mut lines: Vec<_> = data
+        .symbols()
+        .into_iter()
+        .map(|(_, symbol)| (symbol.name.as_str(), symbol.line, symbol.end_line))
+        .collect();
+    lines.sort_by_key(|&(_, line, _)| line);
+    assert_eq!(
+        lines,
+        vec![
+            ("a", 2, 3),
+            ("Outer", 4, 14),
+            ("B", 5, 7),
+            ("Inner", 8, 11),
+            ("c", 9, 10),
+            ("d", 13, 14),
+            ("e", 15, 15),
+        ]
+    );
+    assert_eq!(data.end_line, 16);
+}
+
+#[test]
+fn deeply_nested_classes() {
+    // Three levels of classes indented with spaces, with function bodies between the members
+    let data = parse(
+        "class A:\n    var a\n    func f():\n        pass\n    class B:\n        class C:\n            func g():\n                return 1\n            var c\n        var b\n    var a2\nvar top\n",
+    );
+    assert_eq!(names(&data, EntryType::VAR), vec!["top"]);
+    let class_members = |class: &Symbol| match &class.arg {
+        Some(SymbolArgs::ClassArgs(class)) => class
+            .entries
+            .iter()
+            .flat_map(|e| e.symbols.iter().map(|s| s.name.clone()))
+            .collect::<Vec<_>>(),
+        arg => panic!("Expected class arguments, got {:?}", arg),
+    };
+    let all = data.symbols();
+    let class = |name: &str| {
+        all.iter()
+            .find(|(t, s)| **t == EntryType::CLASS && s.name == name)
+            .unwrap()
+            .1
+    };
+    assert_eq!(class_members(class("A")), vec!["B", "f", "a", "a2"]);
+    assert_eq!(class_members(class("B")), vec!["C", "b"]);
+    assert_eq!(class_members(class("C")), vec!["g", "c"]);
+    assert_eq!((class("A").line, class("A").end_line), (1, 11));
+    assert_eq!((class("C").line, class("C").end_line), (6, 9));
+}
+
+#[test]
+fn class_bodies_ending_at_eof() {
+    // The file ends inside of the innermost class, without a newline at the end
+    let data = parse("var top\nclass A:\n\tclass B:\n\t\tclass C:\n\t\t\tfunc f():\n\t\t\t\tpass\n\t\t\tvar last");
+    assert_eq!(names(&data, EntryType::VAR), vec!["top"]);
+    let lines: Vec<_> = data
+        .symbols()
+        .into_iter()
+        .map(|(_, symbol)| (symbol.name.as_str(), symbol.line, symbol.end_line))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            ("A", 2, 7),
+            ("B", 3, 7),
+            ("C", 4, 7),
+            ("f", 5, 5),
+            ("last", 7, 7),
+            ("top", 1, 1),
+        ]
+    );
+}
+
+#[test]
+fn inner_classes_extending_paths() {
+    let data = parse(
+        "class Foo extends \"res://base.gd\":\n\tvar a\nclass Bar extends \"res://a:b.gd\".Inner:\n\tvar b\n",
+    );
+    let bases: Vec<_> = symbols(&data, EntryType::CLASS)
+        .iter()
+        .map(|class| match &class.arg {
+            Some(SymbolArgs::ClassArgs(args)) => (class.name.as_str(), args.base.clone()),
+            arg => panic!("Expected class arguments, got {:?}", arg),
+        })
+        .collect();
+    assert_eq!(
+        bases,
+        vec![
+            ("Foo", Some("\"res://base.gd\"".to_string())),
+            ("Bar", Some("\"res://a:b.gd\".Inner".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn statements_spanning_lines() {
+    let data = parse("const FOO = [\n\t1,\n\t2,\n]\nvar bar = \"\"\"\nfoo\n\"\"\"\nvar baz\n");
+    let constants = symbols(&data, EntryType::CONST);
+    assert_eq!((constants[0].line, constants[0].end_line), (1, 4));
+    let variables = symbols(&data, EntryType::VAR);
+    assert_eq!(variables.len(), 2);
+    assert_eq!(variables[1].line, 8);
+    assert_eq!(data.end_line, 8);
+}
+
+#[test]
+fn mixed_brackets_spanning_lines() {
+    let data = parse(
+        "const DATA = {\n\t\"a\": [1, (2 +\n\t\t3)], # Comment\n\t\"b\": {\"c\": [\n\t\t4]},\n}\nfunc foo(a = [\n\t1], b = {\n\t}):\n\tpass\nvar after\n",
+    );
+    let constants = symbols(&data, EntryType::CONST);
+    assert_eq!((constants[0].line, constants[0].end_line), (1, 6));
+    match &constants[0].arg {
+        Some(SymbolArgs::VariableArgs(args)) => assert_eq!(
+            args.assignment.as_deref(),
+            Some("{\"a\": [1, (2 +3)],\"b\": {\"c\": [4]},}")
+        ),
+        arg => panic!("Expected variable arguments, got {:?}", arg),
+    }
+    let functions = symbols(&data, EntryType::FUNC);
+    assert_eq!(functions[0].line, 7);
+    assert_eq!(functions[0].name, "foo");
+    assert_eq!(symbols(&data, EntryType::VAR)[0].line, 11);
+}
+
+#[test]
+fn brackets_spanning_lines_in_classes() {
+    // Continuation lines may be indented less than the class body, the first line counts
+    let data = parse(
+        "class A:\n\tconst TABLE = {\n\"a\": [1, 2,],  # First\n\t\t\"b\": {\"c\": (3),},\n}\n\tvar after\nvar outside\n",
+    );
+    match &symbols(&data, EntryType::CLASS)[0].arg {
+        Some(SymbolArgs::ClassArgs(class)) => {
+            let constant = &class.entries[0].symbols[0];
+            assert_eq!((constant.line, constant.end_line), (2, 5));
+            assert_eq!(constant.text, vec!["First"]);
+            match &constant.arg {
+                Some(SymbolArgs::VariableArgs(args)) => assert_eq!(
+                    args.assignment.as_deref(),
+                    Some("{\"a\": [1, 2,],\"b\": {\"c\": (3),},}")
+                ),
+                arg => panic!("Expected variable arguments, got {:?}", arg),
+            }
+            assert_eq!(class.entries[1].symbols[0].name, "after");
+        }
+        arg => panic!("Expected class arguments, got {:?}", arg),
+    }
+    assert_eq!(names(&data, EntryType::VAR), vec!["outside"]);
+}
+
+#[test]
+fn trailing_comments() {
+    let data = parse(
+        "var speed = 100 # movement speed in px/s\nconst MAX = 3 ## the maximum\n\
+        signal hit(amount) # emitted on hit\n## Above\nvar health = 10 # after\nvar plain\n",
+    );
+    let variables = symbols(&data, EntryType::VAR);
+    assert_eq!(variables[0].text, vec!["movement speed in px/s"]);
+    assert_eq!(variables[1].text, vec!["Above", "after"]);
+    // The comment belongs to its own line, not to the next declaration
+    assert!(variables[2].text.is_empty());
+    assert_eq!(
+        symbols(&data, EntryType::CONST)[0].text,
+        vec!["the maximum"]
+    );
+    assert_eq!(
+        symbols(&data, EntryType::SIGNAL)[0].text,
+        vec!["emitted on hit"]
+    );
+}
+
+#[test]
+fn function_signature_spanning_lines() {
+    let data = parse(
+        "var before\n\n## Spawns an enemy.\nfunc spawn(kind: String, # Scene of the enemy\n\t\tat := Vector2(0, # x\n\t\t\t0), # y\n\t\tcount = 1) -> Node: # Returns the first\n\treturn null\n",
+    );
+    let functions = symbols(&data, EntryType::FUNC);
+    assert_eq!((functions[0].line, functions[0].end_line), (4, 7));
+    match &functions[0].arg {
+        Some(SymbolArgs::FunctionArgs(args)) => assert_eq!(
+            args.arguments,
+            vec![
+                argument("kind", Some("String"), None),
+                argument("at", None, Some("Vector2(0,0)")),
+                argument("count", None, Some("1")),
+            ]
+        ),
+        arg => panic!("Expected function arguments, got {:?}", arg),
+    }
+    // Comments after an argument document it, comments after the signature the function
+    assert_eq!(
+        functions[0].text,
+        vec![
+            "Spawns an enemy.",
+            "@param kind Scene of the enemy",
+            "@param at x y",
+            "Returns the first",
+        ]
+    );
+
+    let data = parse("signal hit(\n\t# Not an argument\n\tamount, # Damage\n\tsource\n)\n");
+    assert_eq!(
+        symbols(&data, EntryType::SIGNAL)[0].text,
+        vec!["Not an argument", "@param amount Damage"]
+    );
+    let data = parse("var a = max(1, # One\n\t2)\n");
+    assert_eq!(symbols(&data, EntryType::VAR)[0].text, vec!["One"]);
+}
+
+#[test]
+fn semicolon_separated_statements() {
+    let data = parse("var a; var b = \"x;y\"\n");
+    assert_eq!(names(&data, EntryType::VAR), vec!["a", "b"]);
+
+    // The comments above the line belong to the first statement, the one on it to the last
+    let data = parse("## Above\nvar a = 1; var b = 2 # On the line\n");
+    let variables = symbols(&data, EntryType::VAR);
+    assert_eq!(variables[0].text, vec!["Above"]);
+    assert_eq!(variables[1].text, vec!["On the line"]);
+}
+
+#[test]
+fn semicolons_in_one_line_bodies() {
+    // Everything after the ':' of a block header is its body, even after a ';'
+    let data = parse(
+        "func f(): return 1; var b = 2 # Returns one\n\
+        var c = 3; func g(a: int) -> void: print(a); var d\n\
+        var callback = func(): pass; var e\n\
+        var after; var last\n",
+    );
+    assert_eq!(names(&data, EntryType::FUNC), vec!["f", "g"]);
+    assert_eq!(symbols(&data, EntryType::FUNC)[0].text, vec!["Returns one"]);
+    assert_eq!(
+        names(&data, EntryType::VAR),
+        vec!["c", "callback", "after", "last"]
+    );
+}
+
+#[test]
+fn signals_and_dependencies() {
+    let data = parse("signal hit(damage)\nconst Enemy = preload(\"res://enemy.gd\")\n");
+    assert_eq!(names(&data, EntryType::SIGNAL), vec!["hit"]);
+    assert_eq!(data.dependencies[0].path, "res://enemy.gd");
+}
+
+#[test]
+fn signal_arguments() {
+    let data = parse(
+        "signal died
+signal moved(from: Vector2, to: Vector2)
+signal hit(\n\tdamage: int,\n\tsource\n)
+",
+    );
+    let signals = symbols(&data, EntryType::SIGNAL)
+        .into_iter()
+        .map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::SignalArgs(arguments)) => (symbol.name.as_str(), arguments.clone()),
+            arg => panic!("Expected signal arguments, got {:?}", arg),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        signals,
+        vec![
+            ("died", vec![]),
+            (
+                "moved",
+                vec![
+                    argument("from", Some("Vector2"), None),
+                    argument("to", Some("Vector2"), None)
+                ]
+            ),
+            (
+                "hit",
+                vec![
+                    argument("damage", Some("int"), None),
+                    argument("source", None, None)
+                ]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn editor_directives_are_skipped() {
+    let options = ParseOptions {
+        ignored_comment_prefixes: vec!["gdlint:".to_string()],
+        ..ParseOptions::default()
+    };
+    let data = parse_with(
+        "# warning-ignore:unused_argument\n# gdlint: ignore=foo\n# Docs\nfunc foo(a):\n\tpass\n",
+        &options,
+    );
+    assert_eq!(symbols(&data, EntryType::FUNC)[0].text, vec!["Docs"]);
+}
+
+#[test]
+fn byte_order_mark_and_crlf() {
+    let data = parse("\u{feff}# Docs\r\nvar foo\r\n");
+    let variables = symbols(&data, EntryType::VAR);
+    assert_eq!(variables[0].name, "foo");
+    assert_eq!(variables[0].text, vec!["Docs"]);
+}
+
+#[test]
+fn windows_line_endings() {
+    let data = parse(
+        "\u{feff}class_name Player\r\nextends Node\r\n\r\nvar foo = 1 + \\\r\n\t2\r\nvar bar = \"\"\"a\r\nb\"\"\"\r\nfunc baz(a,\r\n\t\tb):\r\n\tpass\r\n",
+    );
+    assert_eq!(data.class_name.as_deref(), Some("Player"));
+    assert_eq!(data.base.as_deref(), Some("Node"));
+
+    let variables = symbols(&data, EntryType::VAR);
+    match (&variables[0].arg, &variables[1].arg) {
+        (Some(SymbolArgs::VariableArgs(foo)), Some(SymbolArgs::VariableArgs(bar))) => {
+            assert_eq!(foo.assignment.as_deref(), Some("1 + 2"));
+            assert_eq!(bar.assignment.as_deref(), Some("\"\"\"a\nb\"\"\""));
+        }
+        args => panic!("Expected variable arguments, got {:?}", args),
+    }
+    match &symbols(&data, EntryType::FUNC)[0].arg {
+        Some(SymbolArgs::FunctionArgs(args)) => assert_eq!(args.arguments.len(), 2),
+        arg => panic!("Expected function arguments, got {:?}", arg),
+    }
+}
+
+#[test]
+fn invalid_utf8() {
+    let source: &[u8] = b"# \xff\nvar foo\n";
+    assert!(parse_source("test.gd", source, &ParseOptions::default()).is_ok());
+
+    let options = ParseOptions {
+        strict_encoding: true,
+        ..ParseOptions::default()
+    };
+    let error = parse_source("test.gd", source, &options).unwrap_err();
+    assert_eq!(error.line, 1);
+    assert_eq!(error.kind, ParseErrorKind::Encoding);
+}
+
+#[test]
+fn errors_report_their_position() {
+    let error = parse_str(
+        "test.gd",
+        "var a\nvar b = \"\"\"foo\n",
+        &ParseOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.file, "test.gd");
+    assert_eq!(error.line, 2);
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedEof);
+
+    let error = parse_str("test.gd", "var a = (]\n", &ParseOptions::default()).unwrap_err();
+    assert_eq!(error.line, 1);
+    assert!(error.column.is_some());
+    assert_eq!(error.kind, ParseErrorKind::MismatchedParentheses);
+
+    let error = parse_str(
+        "test.gd",
+        "var a setget x, y, z\n",
+        &ParseOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::Syntax);
+
+    // Signatures are parsed by the same typed errors
+    let error = parse_str(
+        "test.gd",
+        "var a\nfunc f(a) > int:\n\tpass\n",
+        &ParseOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!((error.line, error.kind), (2, ParseErrorKind::Syntax));
+    assert_eq!(error.message, "Invalid syntax: f(a) > int:");
+}
+
+#[test]
+fn truncated_and_garbage_sources_dont_panic() {
+    let source = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/test.gd")).unwrap();
+    // Every prefix of a valid script, cut at any character
+    for (end, _) in source.char_indices() {
+        let _ = parse_str("test.gd", &source[..end], &ParseOptions::default());
+    }
+
+    let fragments = [
+        "enum {",
+        "enum X {A,",
+        "}",
+        "class A:",
+        "\tfunc f(a=(1,2)):",
+        "func (",
+        "func f(a:=1) -> int:",
+        "var x = ",
+        "\"",
+        "'",
+        "\"\"\"",
+        "(",
+        "[",
+        "{",
+        ")",
+        "]",
+        "#",
+        "\\",
+        " setget ",
+        "export(",
+        "export(int) var",
+        "signal s(",
+        "const C = 1",
+        "\n",
+        "\t",
+        ":",
+        "=",
+        ",",
+        ".",
+        "@",
+        "@rpc(",
+        "func _init().(a):",
+        "é",
+        "日本",
+        "😀",
+    ];
+    // A fixed linear congruential generator, so failures can be reproduced
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..2000 {
+        let mut garbage = String::new();
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        for _ in 0..(state >> 59) + 1 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            garbage += fragments[(state >> 33) as usize % fragments.len()];
+        }
+        let _ = parse_str("test.gd", &garbage, &ParseOptions::default());
+    }
+}
+
+#[test]
+fn unicode_text() {
+    let data = parse(
+        "## Déplacement du joueur\nvar vitesse_é: String = \"café\" # 速度 (px/s)\n\
+        ## 挨拶する 😀\nfunc salut(nom = \"😀\", 名前: String = \"日本語\") -> String:\n\treturn nom\n\
+        signal été(ça: int) # 🎉\n\
+        enum Couleur { ROUGE_é, 青 = 5, VERT_😀 } # 色\n\
+        var ü = \"ä\" setget set_ü, get_ü\n\
+        export(String, \"é\", \"😀\") var choix\n\
+        class Öl:\n\tconst ÉTÉ = \"☀\"\n",
+    );
+
+    let variables = symbols(&data, EntryType::VAR);
+    assert_eq!(variables[0].name, "vitesse_é");
+    assert_eq!(
+        variables[0].text,
+        vec!["Déplacement du joueur", "速度 (px/s)"]
+    );
+    match &variables[0].arg {
+        Some(SymbolArgs::VariableArgs(args)) => {
+            assert_eq!(args.value_type.as_deref(), Some("String"));
+            assert_eq!(args.assignment.as_deref(), Some("\"café\""));
+        }
+        arg => panic!("Expected variable arguments, got {:?}", arg),
+    }
+    match &variables[1].arg {
+        Some(SymbolArgs::VariableArgs(args)) => {
+            assert_eq!(variables[1].name, "ü");
+            assert_eq!(args.assignment.as_deref(), Some("\"ä\""));
+            assert_eq!(args.setter.as_deref(), Some("set_ü"));
+            assert_eq!(args.getter.as_deref(), Some("get_ü"));
+        }
+        arg => panic!("Expected variable arguments, got {:?}", arg),
+    }
+
+    let functions = symbols(&data, EntryType::FUNC);
+    assert_eq!(functions[0].text, vec!["挨拶する 😀"]);
+    match &functions[0].arg {
+        Some(SymbolArgs::FunctionArgs(args)) => assert_eq!(
+            args.arguments,
+            vec![
+                argument("nom", None, Some("\"😀\"")),
+                argument("名前", Some("String"), Some("\"日本語\"")),
+            ]
+        ),
+        arg => panic!("Expected function arguments, got {:?}", arg),
+    }
+
+    let signals = symbols(&data, EntryType::SIGNAL);
+    assert_eq!(signals[0].name, "été");
+    assert_eq!(signals[0].text, vec!["🎉"]);
+
+    let enums = symbols(&data, EntryType::ENUM);
+    assert_eq!(enums[0].name, "Couleur");
+    match &enums[0].arg {
+        Some(SymbolArgs::EnumArgs(values)) => {
+            let values: Vec<_> = values.iter().map(|v| (v.name.as_str(), v.value)).collect();
+            assert_eq!(values, vec![("ROUGE_é", 0), ("青", 5), ("VERT_😀", 6)]);
+        }
+        arg => panic!("Expected enum values, got {:?}", arg),
+    }
+
+    match &symbols(&data, EntryType::EXPORT)[0].arg {
+        Some(SymbolArgs::ExportArgs(args)) => {
+            assert_eq!(args.value_type.as_deref(), Some("String"));
+            assert_eq!(args.options, vec!["\"é\"", "\"😀\""]);
+        }
+        arg => panic!("Expected export arguments, got {:?}", arg),
+    }
+
+    let classes = symbols(&data, EntryType::CLASS);
+    assert_eq!(classes[0].name, "Öl");
+    match &classes[0].arg {
+        Some(SymbolArgs::ClassArgs(class)) => {
+            assert_eq!(class.entries[0].symbols[0].name, "ÉTÉ");
+        }
+        arg => panic!("Expected class arguments, got {:?}", arg),
+    }
+}
+
+#[test]
+fn unterminated_blocks_at_eof() {
+    // The values collected up to the end of the file are kept
+    let data = parse("enum Foo {\n\tA,\n\tB");
+    match &symbols(&data, EntryType::ENUM)[0].arg {
+        Some(SymbolArgs::EnumArgs(values)) => assert_eq!(values.len(), 2),
+        arg => panic!("Expected enum values, got {:?}", arg),
+    }
+
+    let data = parse("class Foo:\n\tclass Bar:\n\t\tvar a");
+    let all = data
+        .symbols()
+        .into_iter()
+        .map(|(_, symbol)| symbol.name.as_str());
+    assert_eq!(all.collect::<Vec<_>>(), vec!["Foo", "Bar", "a"]);
+    let data = parse("class Empty:");
+    assert_eq!(names(&data, EntryType::CLASS), vec!["Empty"]);
+
+    let error = parse_str("test.gd", "func foo(a,\n", &ParseOptions::default()).unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedEof);
+    let error = parse_str("test.gd", "var a = 1 + \\", &ParseOptions::default()).unwrap_err();
+    assert_eq!((error.line, error.kind), (1, ParseErrorKind::UnexpectedEof));
+}
+
+#[test]
+fn blank_lines_separate_comments() {
+    let data = parse("# Not about foo\n\nfunc foo():\n\tpass\n");
+    assert!(symbols(&data, EntryType::FUNC)[0].text.is_empty());
+
+    let data = parse("# [Hide]\n\nfunc foo():\n\tpass\n");
+    assert_eq!(names(&data, EntryType::FUNC), vec!["foo"]);
+}
+
+#[test]
+fn doc_comments() {
+    let data = parse("## Unrelated\n\n## First\n## Second\n# warning-ignore:unused_argument\nfunc foo(a):\n\tpass\n");
+    assert_eq!(
+        symbols(&data, EntryType::FUNC)[0].text,
+        vec!["First", "Second"]
+    );
+}
+
+#[test]
+fn serialization_round_trip() {
+    let source = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/test.gd")).unwrap();
+    let data = parse(&source);
+
+    let json = serde_json::to_string(&data).unwrap();
+    let deserialized: DocumentationData = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, data);
+}
+
+#[test]
+fn serialization_format() {
+    let data = parse("func foo(a):\n\tpass\n");
+    let value = serde_json::to_value(&data).unwrap();
+    assert_eq!(value["entries"][0]["entry_type"], "func");
+    assert_eq!(
+        value["entries"][0]["symbols"][0]["arg"]["kind"],
+        "FunctionArgs"
+    );
+    assert_eq!(
+        value["entries"][0]["symbols"][0]["arg"]["args"]["arguments"][0]["name"],
+        "a"
+    );
+}
+
+#[test]
+fn virtual_functions() {
+    let data = parse("# Called every turn\n# @virtual\nfunc take_turn():\n\tpass\n\n# Not meant to be overridden\nfunc end_turn():\n\tpass\n");
+    let functions = symbols(&data, EntryType::FUNC);
+    match (&functions[0].arg, &functions[1].arg) {
+        (Some(SymbolArgs::FunctionArgs(tagged)), Some(SymbolArgs::FunctionArgs(untagged))) => {
+            assert!(tagged.is_virtual);
+            assert!(!untagged.is_virtual);
+        }
+        args => panic!("Expected function arguments, got {:?}", args),
+    }
+    assert_eq!(functions[0].text, vec!["Called every turn"]);
+}
+
+#[test]
+fn since_tags() {
+    let data = parse("## Jumps\n## @since 1.2.0\nfunc jump():\n\tpass\n\nfunc walk():\n\tpass\n\n# @since\nvar speed\n\nclass Inner:\n\t# @since v2.0\n\tconst MAX = 3\n");
+    let functions = symbols(&data, EntryType::FUNC);
+    assert_eq!(functions[0].since.as_deref(), Some("1.2.0"));
+    assert_eq!(functions[0].text, vec!["Jumps"]);
+    assert_eq!(functions[1].since, None);
+    // A tag without a version is dropped
+    let variables = symbols(&data, EntryType::VAR);
+    assert_eq!(variables[0].since, None);
+    assert!(variables[0].text.is_empty());
+
+    match &symbols(&data, EntryType::CLASS)[0].arg {
+        Some(SymbolArgs::ClassArgs(class)) => {
+            assert_eq!(class.entries[0].symbols[0].since.as_deref(), Some("v2.0"))
+        }
+        arg => panic!("Expected class arguments, got {:?}", arg),
+    }
+}
+
+#[test]
+fn version_comparison() {
+    let version = |text| Version::parse(text).unwrap();
+    assert!(version("1.2.0") < version("1.10.0"));
+    assert!(version("1.2.0") > version("1.1.9"));
+    assert_eq!(version("1.2"), version("1.2.0"));
+    assert_eq!(version("v1.2.0"), version("1.2.0"));
+    assert_eq!(version("1.2.0+build.5"), version("1.2.0"));
+    assert!(version("1.2.0-beta") < version("1.2.0"));
+    assert!(version("1.2.0-alpha") < version("1.2.0-beta"));
+    assert!(version("1.2.0-beta") > version("1.1.0"));
+    assert!(Version::parse("soon").is_err());
+    assert!(Version::parse("1..2").is_err());
+
+    let filter = SinceFilter::parse(">=1.2.0").unwrap();
+    assert!(filter.accepts(Some("1.2.0")));
+    assert!(filter.accepts(Some("2.0")));
+    assert!(!filter.accepts(Some("1.1.5")));
+    assert!(!filter.accepts(Some("1.2.0-rc1")));
+    assert!(!filter.accepts(Some("later")));
+    assert!(!filter.accepts(None));
+    assert_eq!(SinceFilter::parse("1.2.0"), Ok(filter));
+    assert!(SinceFilter::parse("<1.2").unwrap().accepts(Some("1.1.9")));
+    assert!(!SinceFilter::parse(">1.2").unwrap().accepts(Some("1.2.0")));
+    assert!(SinceFilter::parse("=1.2").unwrap().accepts(Some("1.2.0")));
+    assert!(SinceFilter::parse(">=").is_err());
+}
+
+#[test]
+fn since_filter() {
+    let mut data = parse("# @since 1.0\nsignal hit\n\n# @since 1.3\nfunc jump():\n\tpass\n\nfunc walk():\n\tpass\n\nclass Old:\n\t# @since 1.2\n\tvar height\n\n\tvar width\n\nclass Older:\n\tvar depth\n");
+    data.filter_since(&SinceFilter::parse(">=1.2.0").unwrap());
+
+    assert_eq!(names(&data, EntryType::FUNC), vec!["jump"]);
+    assert!(names(&data, EntryType::SIGNAL).is_empty());
+    assert!(data.entries.iter().all(|x| !x.symbols.is_empty()));
+    // Classes without a tag are kept for their new members only
+    let classes = symbols(&data, EntryType::CLASS);
+    assert_eq!(classes.len(), 1);
+    match &classes[0].arg {
+        Some(SymbolArgs::ClassArgs(class)) => {
+            assert_eq!(class.entries.len(), 1);
+            assert_eq!(class.entries[0].symbols.len(), 1);
+            assert_eq!(class.entries[0].symbols[0].name, "height");
+        }
+        arg => panic!("Expected class arguments, got {:?}", arg),
+    }
+}
+
+#[test]
+fn deprecated_tags() {
+    let data = parse("## Moves\n## @deprecated Use move_to instead\nfunc move():\n\tpass\n\n# @deprecated\nexport(int) var speed = 3\n\n# @deprecated\nvar a\n# @deprecated\nsignal b\n# @deprecated\nconst C = 1\n# @deprecated\nenum D {E}\n# @deprecated\nclass F:\n\t# @deprecated Gone\n\tvar g\n\nfunc kept():\n\tpass\n");
+    let functions = symbols(&data, EntryType::FUNC);
+    assert_eq!(
+        functions[0].deprecated.as_deref(),
+        Some("Use move_to instead")
+    );
+    assert_eq!(functions[0].text, vec!["Moves"]);
+    assert_eq!(functions[1].deprecated, None);
+    let exports = symbols(&data, EntryType::EXPORT);
+    assert_eq!(exports[0].name, "speed");
+    assert_eq!(exports[0].deprecated.as_deref(), Some(""));
+    assert!(exports[0].text.is_empty());
+
+    let deprecated = data
+        .deprecated_symbols()
+        .iter()
+        .map(|x| x.name.as_str())
+        .collect::<Vec<_>>();
+    for name in ["move", "speed", "a", "b", "C", "D", "F", "g"] {
+        assert!(deprecated.contains(&name), "{} in {:?}", name, deprecated);
+    }
+    assert_eq!(deprecated.len(), 8);
+}
+
+#[test]
+fn script_text() {
+    // Godot 3 style, above the header
+    let data = parse(
+        "## The player\n## Moves around\nclass_name Player\nextends Node\n\n## Speed\nvar speed\n",
+    );
+    assert_eq!(data.text, vec!["The player", "Moves around"]);
+    assert_eq!(data.summary(), Some("The player"));
+    assert_eq!(symbols(&data, EntryType::VAR)[0].text, vec!["Speed"]);
+
+    // Godot 4 style, below the header up to a blank line
+    let data = parse("extends Node\n## @tutorial: https://example.com\n## The player\n##\n## Moves around\n\nvar speed\n");
+    assert_eq!(data.summary(), Some("The player"));
+    assert_eq!(data.text.len(), 4);
+    assert!(symbols(&data, EntryType::VAR)[0].text.is_empty());
+
+    // Comments directly above a declaration document the declaration
+    let data = parse("extends Node\n## Speed\nvar speed\n\n# Not the script\n\nvar health\n");
+    assert!(data.text.is_empty());
+    assert_eq!(data.summary(), None);
+    assert_eq!(symbols(&data, EntryType::VAR)[0].text, vec!["Speed"]);
+    assert!(parse("# Foo\nfunc foo():\n\tpass\n").text.is_empty());
+}
+
+#[test]
+fn all_symbols() {
+    let data = parse(
+        "var a\nclass Inner:\n\tfunc b():\n\t\tpass\n\tclass Nested:\n\t\tconst C = 1\nsignal d\n",
+    );
+    let symbols = data
+        .symbols()
+        .into_iter()
+        .map(|(entry_type, symbol)| (entry_type.clone(), symbol.name.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        vec![
+            (EntryType::CLASS, "Inner"),
+            (EntryType::CLASS, "Nested"),
+            (EntryType::CONST, "C"),
+            (EntryType::FUNC, "b"),
+            (EntryType::SIGNAL, "d"),
+            (EntryType::VAR, "a"),
+        ]
+    );
+}